@@ -77,6 +77,12 @@ pub type CheckWeight = GenericSignedExtensionSchema<(), ()>;
 /// The `SignedExtensionSchema` for `pallet_transaction_payment::ChargeTransactionPayment`.
 pub type ChargeTransactionPayment<Balance> = GenericSignedExtensionSchema<Compact<Balance>, ()>;
 
+/// The `SignedExtensionSchema` for `frame-metadata-hash-extension::CheckMetadataHash`.
+///
+/// The payload is the encoded `Mode` of the extension (`0` for disabled, `1` for enabled), and
+/// the additional signed data is the metadata hash, which is only set when the mode is enabled.
+pub type CheckMetadataHash = GenericSignedExtensionSchema<u8, Option<[u8; 32]>>;
+
 /// The `SignedExtensionSchema` for `polkadot-runtime-common::PrevalidateAttests`.
 pub type PrevalidateAttests = GenericSignedExtensionSchema<(), ()>;
 
@@ -100,14 +106,19 @@ impl SignedExtensionSchema for Tuple {
 
 /// A simplified version of signed extensions meant for producing signed transactions
 /// and signed payloads in the client code.
+///
+/// Decoding a `GenericSignedExtension` only recovers its `payload`, since the additional signed
+/// data is not part of the transaction. Client code that knows the context of the transaction
+/// (e.g. the genesis hash and spec version of the chain) can attach it back with
+/// [`GenericSignedExtension::with_additional_signed`] or
+/// [`GenericSignedExtension::recover_additional_signed`]. Until then,
+/// [`SignedExtension::additional_signed`] returns an error.
 #[derive(Encode, Decode, Debug, PartialEq, Eq, Clone, TypeInfo)]
 pub struct GenericSignedExtension<S: SignedExtensionSchema> {
 	pub payload: S::Payload,
 	#[codec(skip)]
-	// It may be set to `None` if extensions are decoded. We are never reconstructing transactions
-	// (and it makes no sense to do that) => decoded version of `SignedExtensions` is only used to
-	// read fields of the `payload`. And when resigning transaction, we're reconstructing
-	// `SignedExtensions` from scratch.
+	// It may be set to `None` if extensions are decoded, until client code recovers it. When
+	// resigning transaction, we're reconstructing `SignedExtensions` from scratch anyway.
 	additional_signed: Option<S::AdditionalSigned>,
 }
 
@@ -115,6 +126,73 @@ impl<S: SignedExtensionSchema> GenericSignedExtension<S> {
 	pub fn new(payload: S::Payload, additional_signed: Option<S::AdditionalSigned>) -> Self {
 		Self { payload, additional_signed }
 	}
+
+	/// Attach the additional signed data, e.g. after the extension has been decoded.
+	pub fn with_additional_signed(mut self, additional_signed: S::AdditionalSigned) -> Self {
+		self.additional_signed = Some(additional_signed);
+		self
+	}
+
+	/// Compute the additional signed data from the payload and attach it, if it is missing.
+	pub fn recover_additional_signed(
+		&mut self,
+		recover: impl FnOnce(&S::Payload) -> S::AdditionalSigned,
+	) {
+		if self.additional_signed.is_none() {
+			self.additional_signed = Some(recover(&self.payload));
+		}
+	}
+
+	/// Returns true if the additional signed data is known, i.e. if
+	/// [`SignedExtension::additional_signed`] won't fail.
+	pub fn has_additional_signed(&self) -> bool {
+		self.additional_signed.is_some()
+	}
+}
+
+/// A builder of `GenericSignedExtension`, pushing the extensions one by one, in the same order as
+/// in the runtime.
+///
+/// Every pushed extension is paired with the schema built so far, so pushing `A`, `B` and `C`
+/// builds a `GenericSignedExtension<((((), A), B), C)>`. It is encoded exactly like a
+/// `GenericSignedExtension<(A, B, C)>`, but pushing extensions in a different order results in a
+/// different type, which is caught at compile time.
+#[derive(Debug, Clone)]
+pub struct GenericSignedExtensionBuilder<S: SignedExtensionSchema> {
+	payload: S::Payload,
+	additional_signed: S::AdditionalSigned,
+}
+
+impl GenericSignedExtensionBuilder<()> {
+	/// Create a builder without any extension.
+	pub fn new() -> Self {
+		Self { payload: (), additional_signed: () }
+	}
+}
+
+impl Default for GenericSignedExtensionBuilder<()> {
+	fn default() -> Self {
+		Self::new()
+	}
+}
+
+impl<S: SignedExtensionSchema> GenericSignedExtensionBuilder<S> {
+	/// Push the next extension, with its payload and additional signed data.
+	pub fn push<E: SignedExtensionSchema>(
+		self,
+		payload: E::Payload,
+		additional_signed: E::AdditionalSigned,
+	) -> GenericSignedExtensionBuilder<(S, E)> {
+		GenericSignedExtensionBuilder {
+			payload: (self.payload, payload),
+			additional_signed: (self.additional_signed, additional_signed),
+		}
+	}
+
+	/// Build the signed extension from the extensions pushed so far.
+	pub fn build(self) -> GenericSignedExtension<S> {
+		GenericSignedExtension::new(self.payload, Some(self.additional_signed))
+	}
 }
 
 impl<S> SignedExtension for GenericSignedExtension<S>
@@ -150,3 +228,86 @@ where
 		Ok(())
 	}
 }
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn check_metadata_hash_encoding_works() {
+		let metadata_hash = [42u8; 32];
+		let extension =
+			GenericSignedExtension::<CheckMetadataHash>::new(1, Some(Some(metadata_hash)));
+
+		// Only the mode ends up in the transaction.
+		let encoded = extension.encode();
+		assert_eq!(encoded, vec![1]);
+		let decoded =
+			GenericSignedExtension::<CheckMetadataHash>::decode(&mut &encoded[..]).unwrap();
+		assert_eq!(decoded.payload, 1);
+
+		// The metadata hash is signed as an `Option`.
+		let additional_signed = extension.additional_signed().unwrap();
+		assert_eq!(additional_signed.encode(), [&[1u8][..], &metadata_hash[..]].concat());
+
+		let extension = GenericSignedExtension::<CheckMetadataHash>::new(0, Some(None));
+		assert_eq!(extension.encode(), vec![0]);
+		assert_eq!(extension.additional_signed().unwrap().encode(), vec![0]);
+	}
+
+	#[test]
+	fn additional_signed_can_be_recovered_after_decode() {
+		type Schema = (CheckSpecVersion, CheckNonce<u32>);
+		let extension = GenericSignedExtension::<Schema>::new(((), 42.into()), Some((100, ())));
+
+		let decoded =
+			GenericSignedExtension::<Schema>::decode(&mut &extension.encode()[..]).unwrap();
+		assert!(!decoded.has_additional_signed());
+		assert!(decoded.additional_signed().is_err());
+
+		let recovered = decoded.clone().with_additional_signed((100, ()));
+		assert!(recovered.has_additional_signed());
+		assert_eq!(recovered.additional_signed(), Ok((100, ())));
+
+		let mut recovered = decoded;
+		recovered.recover_additional_signed(|payload| {
+			assert_eq!(payload.1, 42.into());
+			(100, ())
+		});
+		assert_eq!(recovered.additional_signed(), Ok((100, ())));
+
+		// Known additional signed data is kept.
+		recovered.recover_additional_signed(|_| (200, ()));
+		assert_eq!(recovered, extension);
+	}
+
+	#[test]
+	fn builder_encodes_like_tuple() {
+		let genesis_hash = [1u8; 32];
+		let built = GenericSignedExtensionBuilder::new()
+			.push::<CheckNonZeroSender>((), ())
+			.push::<CheckSpecVersion>((), 100)
+			.push::<CheckGenesis<[u8; 32]>>((), genesis_hash)
+			.push::<CheckNonce<u32>>(42.into(), ())
+			.push::<ChargeTransactionPayment<u64>>(1_000.into(), ())
+			.build();
+
+		type Schema = (
+			CheckNonZeroSender,
+			CheckSpecVersion,
+			CheckGenesis<[u8; 32]>,
+			CheckNonce<u32>,
+			ChargeTransactionPayment<u64>,
+		);
+		let hand_written = GenericSignedExtension::<Schema>::new(
+			((), (), (), 42.into(), 1_000.into()),
+			Some(((), 100, genesis_hash, (), ())),
+		);
+
+		assert_eq!(built.encode(), hand_written.encode());
+		assert_eq!(
+			built.additional_signed().unwrap().encode(),
+			hand_written.additional_signed().unwrap().encode(),
+		);
+	}
+}
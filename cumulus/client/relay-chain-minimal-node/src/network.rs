@@ -83,6 +83,8 @@ pub(crate) fn build_collator_network(
 		protocol_id,
 		metrics_registry: config.prometheus_config.as_ref().map(|config| config.registry.clone()),
 		block_announce_config,
+		// This node doesn't sync, so it is never major syncing.
+		is_major_syncing: Default::default(),
 	};
 
 	let network_worker = sc_network::NetworkWorker::new(network_params)?;
@@ -486,6 +486,7 @@ where
 		import_queue,
 		block_announce_validator_builder: Some(Box::new(move |_| block_announce_validator)),
 		warp_sync_params,
+		warp_sync_options: Default::default(),
 		block_relay: None,
 	})
 }
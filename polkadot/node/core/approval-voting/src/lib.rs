@@ -3105,7 +3105,7 @@ async fn launch_approval<Context>(
 			Ok(Ok(a)) => a,
 			Ok(Err(e)) => {
 				match &e {
-					&RecoveryError::Unavailable => {
+					&RecoveryError::Unavailable | &RecoveryError::Timeout { .. } => {
 						gum::warn!(
 							target: LOG_TARGET,
 							?para_id,
@@ -24,21 +24,30 @@ use futures::{
 	channel::{mpsc, oneshot},
 	future,
 	lock::Mutex,
-	FutureExt,
+	pin_mut, Future, FutureExt,
 };
 use polkadot_node_subsystem::{
 	errors::RuntimeApiError,
 	jaeger,
 	messages::{
-		AvailabilityStoreMessage, BitfieldDistributionMessage, RuntimeApiMessage, RuntimeApiRequest,
+		AvailabilityStoreMessage, BitfieldDistributionMessage, BitfieldSigningMessage,
+		RuntimeApiMessage, RuntimeApiRequest,
 	},
 	overseer, ActivatedLeaf, FromOrchestra, OverseerSignal, PerLeafSpan, SpawnedSubsystem,
 	SubsystemError, SubsystemResult, SubsystemSender,
 };
 use polkadot_node_subsystem_util::{self as util, Validator};
-use polkadot_primitives::{AvailabilityBitfield, CoreState, Hash, ValidatorIndex};
+use polkadot_primitives::{
+	AvailabilityBitfield, CandidateHash, CoreState, Hash, SignedAvailabilityBitfield,
+	SigningContext, ValidatorId, ValidatorIndex,
+};
 use sp_keystore::{Error as KeystoreError, KeystorePtr};
-use std::{collections::HashMap, iter::FromIterator, time::Duration};
+use std::{
+	collections::{HashMap, HashSet},
+	iter::FromIterator,
+	sync::Arc,
+	time::Duration,
+};
 use wasm_timer::{Delay, Instant};
 
 mod metrics;
@@ -49,6 +58,13 @@ mod tests;
 
 /// Delay between starting a bitfield signing job and its attempting to create a bitfield.
 const SPAWNED_TASK_DELAY: Duration = Duration::from_millis(1500);
+/// Default time to wait for in-flight bitfield signing jobs on shutdown.
+///
+/// Long enough for a job that was just started to sit out `SPAWNED_TASK_DELAY` and query the
+/// availability store.
+const DEFAULT_SHUTDOWN_TIMEOUT: Duration = Duration::from_secs(3);
+/// Default time to wait for the availability store to answer a chunk availability query.
+const DEFAULT_AVAILABILITY_QUERY_TIMEOUT: Duration = Duration::from_millis(500);
 const LOG_TARGET: &str = "parachain::bitfield-signing";
 
 // TODO: use `fatality` (https://github.com/paritytech/polkadot/issues/5540).
@@ -73,15 +89,27 @@ pub enum Error {
 
 	#[error("Keystore failed: {0:?}")]
 	Keystore(KeystoreError),
+
+	#[error("Key was found at construction, but while signing it could not be found")]
+	SigningKeyDisappeared,
+
+	#[error(
+		"Validator index {validator_index:?} is out of bounds for {validator_count} validators"
+	)]
+	ValidatorIndexOutOfBounds { validator_index: ValidatorIndex, validator_count: usize },
 }
 
 /// If there is a candidate pending availability, query the Availability Store
 /// for whether we have the availability chunk for our validator index.
+///
+/// If the Availability Store doesn't answer within `timeout`, the chunk is assumed to be missing.
 async fn get_core_availability(
 	core: &CoreState,
 	validator_idx: ValidatorIndex,
 	sender: &Mutex<&mut impl SubsystemSender<overseer::BitfieldSigningOutgoingMessages>>,
 	span: &jaeger::Span,
+	timeout: Duration,
+	metrics: &Metrics,
 ) -> Result<bool, Error> {
 	if let CoreState::Occupied(core) = core {
 		let _span = span.child("query-chunk-availability");
@@ -100,7 +128,21 @@ async fn get_core_availability(
 			)
 			.await;
 
-		let res = rx.await.map_err(Into::into);
+		let timeout = Delay::new(timeout);
+		pin_mut!(timeout);
+		let res = match future::select(rx, timeout).await {
+			future::Either::Left((res, _)) => res.map_err(Into::into),
+			future::Either::Right(_) => {
+				gum::warn!(
+					target: LOG_TARGET,
+					para_id = %core.para_id(),
+					?core.candidate_hash,
+					"Timed out querying chunk availability, assuming unavailable",
+				);
+				metrics.on_availability_query_timeout();
+				Ok(false)
+			},
+		};
 
 		gum::trace!(
 			target: LOG_TARGET,
@@ -135,19 +177,76 @@ async fn get_availability_cores(
 	}
 }
 
+/// Candidates whose chunk was available in the most recently constructed bitfield.
+///
+/// Backs the opt-in diagnostic enabled by [`Config::availability_self_check`].
+#[derive(Clone, Default)]
+struct AvailabilityHistory(Arc<Mutex<HashSet<CandidateHash>>>);
+
+impl AvailabilityHistory {
+	/// Compare the availability of the candidates pending on `cores` against the previous call
+	/// and remember it for the next one.
+	///
+	/// Candidates which were available before but are reported unavailable now are logged and
+	/// returned. A chunk we once had is not expected to disappear, so this may indicate a
+	/// corrupted availability store. Leaves on competing forks are checked against each other
+	/// as well, which can produce false positives.
+	async fn check_and_record(
+		&self,
+		relay_parent: Hash,
+		cores: &[CoreState],
+		availability: &[bool],
+	) -> Vec<CandidateHash> {
+		let mut previously_available = self.0.lock().await;
+		let mut available = HashSet::new();
+		let mut regressed = Vec::new();
+
+		for (core, is_available) in cores.iter().zip(availability) {
+			let CoreState::Occupied(core) = core else { continue };
+
+			if *is_available {
+				available.insert(core.candidate_hash);
+			} else if previously_available.contains(&core.candidate_hash) {
+				gum::warn!(
+					target: LOG_TARGET,
+					?relay_parent,
+					para_id = %core.para_id(),
+					?core.candidate_hash,
+					"Chunk was available in the previous bitfield, but is unavailable now",
+				);
+				regressed.push(core.candidate_hash);
+			}
+		}
+
+		*previously_available = available;
+		regressed
+	}
+}
+
 /// - get the list of core states from the runtime
 /// - for each core, concurrently determine chunk availability (see `get_core_availability`)
 /// - return the bitfield if there were no errors at any point in this process (otherwise, it's
 ///   prone to false negatives)
+///
+/// If `config.lenient_core_availability` is set, errors while determining the availability of a
+/// single core are logged and the core is reported as unavailable instead of failing the whole
+/// bitfield.
+///
+/// If a `history` is passed, the result is checked against the previously constructed bitfield,
+/// see [`AvailabilityHistory::check_and_record`]. This never alters the returned bitfield.
 async fn construct_availability_bitfield(
 	relay_parent: Hash,
 	span: &jaeger::Span,
 	validator_idx: ValidatorIndex,
+	config: &Config,
+	metrics: &Metrics,
+	history: Option<&AvailabilityHistory>,
 	sender: &mut impl SubsystemSender<overseer::BitfieldSigningOutgoingMessages>,
 ) -> Result<AvailabilityBitfield, Error> {
 	// get the set of availability cores from the runtime
 	let availability_cores = {
 		let _span = span.child("get-availability-cores");
+		let _timer = metrics.time_runtime_api("availability_cores");
 		get_availability_cores(relay_parent, sender).await?
 	};
 
@@ -160,13 +259,34 @@ async fn construct_availability_bitfield(
 
 	// Handle all cores concurrently
 	// `try_join_all` returns all results in the same order as the input futures.
-	let results = future::try_join_all(
-		availability_cores
-			.iter()
-			.map(|core| get_core_availability(core, validator_idx, &sender, span)),
-	)
+	let results = future::try_join_all(availability_cores.iter().map(|core| {
+		get_core_availability(
+			core,
+			validator_idx,
+			&sender,
+			span,
+			config.availability_query_timeout,
+			metrics,
+		)
+		.map(move |res| match res {
+			Err(err) if config.lenient_core_availability => {
+				gum::warn!(
+					target: LOG_TARGET,
+					?relay_parent,
+					?err,
+					"Failed to determine core availability, assuming unavailable",
+				);
+				Ok(false)
+			},
+			res => res,
+		})
+	}))
 	.await?;
 
+	if let Some(history) = history {
+		history.check_and_record(relay_parent, &availability_cores, &results).await;
+	}
+
 	let core_bits = FromIterator::from_iter(results.into_iter());
 	gum::debug!(
 		target: LOG_TARGET,
@@ -179,16 +299,266 @@ async fn construct_availability_bitfield(
 	Ok(AvailabilityBitfield(core_bits))
 }
 
+/// Encode the bits of `bitfield` as lower-case hex, in the order they are stored in memory.
+fn hex_bits(bitfield: &AvailabilityBitfield) -> String {
+	bitfield.0.as_raw_slice().iter().map(|byte| format!("{byte:02x}")).collect()
+}
+
+/// Configuration for the bitfield signing subsystem.
+#[derive(Debug, Clone)]
+pub struct Config {
+	/// How long to wait for in-flight signing jobs to finish once `Conclude` is received.
+	///
+	/// Jobs wait 1.5s before signing, so shorter timeouts abort jobs that were just started.
+	pub shutdown_timeout: Duration,
+	/// Whether errors while querying the availability of a single core should mark that core as
+	/// unavailable instead of failing the whole bitfield.
+	pub lenient_core_availability: bool,
+	/// How long to wait for the availability store to answer whether our chunk of a candidate is
+	/// available. The core is reported as unavailable on timeout.
+	pub availability_query_timeout: Duration,
+	/// Whether to warn about candidates whose chunk was available in the previous bitfield, but
+	/// is not anymore. Purely diagnostic, the produced bitfields are not affected.
+	pub availability_self_check: bool,
+	/// Whether to emit a structured `info` event for every signed bitfield, carrying the relay
+	/// parent, validator index, session index and the hex-encoded bits. Meant for feeding
+	/// external observability pipelines.
+	pub report_signed_bitfields: bool,
+}
+
+impl Default for Config {
+	fn default() -> Self {
+		Self {
+			shutdown_timeout: DEFAULT_SHUTDOWN_TIMEOUT,
+			lenient_core_availability: false,
+			availability_query_timeout: DEFAULT_AVAILABILITY_QUERY_TIMEOUT,
+			availability_self_check: false,
+			report_signed_bitfields: false,
+		}
+	}
+}
+
+/// Determine the availability bitfield the validator with the given index would sign at
+/// `relay_parent`, without requiring access to its keys.
+///
+/// This is the exact logic used by the subsystem before signing, see
+/// `construct_availability_bitfield` for details. As there is no leaf span to attach to, a new
+/// root span is created for `relay_parent`.
+pub async fn availability_bitfield_for<Sender>(
+	relay_parent: Hash,
+	validator_index: ValidatorIndex,
+	validator_count: usize,
+	config: &Config,
+	sender: &mut Sender,
+) -> Result<AvailabilityBitfield, Error>
+where
+	Sender: overseer::BitfieldSigningSenderTrait,
+{
+	let span = jaeger::Span::new(relay_parent, "availability-bitfield");
+
+	checked_availability_bitfield(
+		relay_parent,
+		&span,
+		validator_index,
+		validator_count,
+		config,
+		&Metrics::default(),
+		None,
+		sender,
+	)
+	.await
+}
+
+/// Check that `validator_index` is in bounds, then construct the bitfield under `span`.
+async fn checked_availability_bitfield<Sender>(
+	relay_parent: Hash,
+	span: &jaeger::Span,
+	validator_index: ValidatorIndex,
+	validator_count: usize,
+	config: &Config,
+	metrics: &Metrics,
+	history: Option<&AvailabilityHistory>,
+	sender: &mut Sender,
+) -> Result<AvailabilityBitfield, Error>
+where
+	Sender: overseer::BitfieldSigningSenderTrait,
+{
+	if validator_index.0 as usize >= validator_count {
+		return Err(Error::ValidatorIndexOutOfBounds { validator_index, validator_count })
+	}
+
+	construct_availability_bitfield(
+		relay_parent,
+		span,
+		validator_index,
+		config,
+		metrics,
+		history,
+		sender,
+	)
+	.await
+}
+
+/// A bitfield handed out for signing by an external signer.
+struct PendingExternalBitfield {
+	bitfield: AvailabilityBitfield,
+	signing_context: SigningContext,
+	validator: ValidatorId,
+}
+
+#[derive(Default)]
+struct ExternalBitfieldsState {
+	/// Leaves bitfields are constructed at. Bitfields are only kept while their leaf is active.
+	active_leaves: HashSet<Hash>,
+	pending: HashMap<(Hash, ValidatorIndex), PendingExternalBitfield>,
+}
+
+/// Bitfields constructed on request of `BitfieldSigningMessage::ConstructUnsigned`, which are
+/// waiting for the external signature.
+#[derive(Clone, Default)]
+struct ExternalBitfields(Arc<Mutex<ExternalBitfieldsState>>);
+
+impl ExternalBitfields {
+	/// Construct the bitfield of `validator_index` at `relay_parent` and remember it until the
+	/// signed version comes back.
+	///
+	/// Returns `None` if `relay_parent` is not an active leaf, or the validator is not part of
+	/// the active set at `relay_parent`.
+	async fn construct<Sender>(
+		&self,
+		relay_parent: Hash,
+		validator_index: ValidatorIndex,
+		config: &Config,
+		metrics: &Metrics,
+		sender: &mut Sender,
+	) -> Result<Option<AvailabilityBitfield>, Error>
+	where
+		Sender: overseer::BitfieldSigningSenderTrait,
+	{
+		if !self.0.lock().await.active_leaves.contains(&relay_parent) {
+			return Ok(None)
+		}
+
+		let (validators, session_index) = futures::try_join!(
+			timed_runtime_api(
+				metrics,
+				"validators",
+				util::request_validators(relay_parent, sender).await,
+			),
+			timed_runtime_api(
+				metrics,
+				"session_index_for_child",
+				util::request_session_index_for_child(relay_parent, sender).await,
+			),
+		)?;
+		let validators = validators?;
+		let session_index = session_index?;
+
+		let Some(validator) = validators.get(validator_index.0 as usize).cloned() else {
+			return Ok(None)
+		};
+
+		let span = jaeger::Span::new(relay_parent, "external-bitfield");
+		let bitfield = checked_availability_bitfield(
+			relay_parent,
+			&span,
+			validator_index,
+			validators.len(),
+			config,
+			metrics,
+			None,
+			sender,
+		)
+		.await?;
+
+		let signing_context = SigningContext { session_index, parent_hash: relay_parent };
+		let mut state = self.0.lock().await;
+		// The leaf may have been deactivated in the meantime.
+		if !state.active_leaves.contains(&relay_parent) {
+			return Ok(None)
+		}
+		state.pending.insert(
+			(relay_parent, validator_index),
+			PendingExternalBitfield { bitfield: bitfield.clone(), signing_context, validator },
+		);
+
+		Ok(Some(bitfield))
+	}
+
+	/// Distribute an externally signed bitfield, if it signs exactly the bitfield constructed
+	/// for its validator at `relay_parent`.
+	///
+	/// Returns whether the bitfield was distributed.
+	async fn distribute<Sender>(
+		&self,
+		relay_parent: Hash,
+		signed: SignedAvailabilityBitfield,
+		sender: &mut Sender,
+	) -> bool
+	where
+		Sender: overseer::BitfieldSigningSenderTrait,
+	{
+		let key = (relay_parent, signed.validator_index());
+		let mut state = self.0.lock().await;
+		let pending = &mut state.pending;
+
+		let matches = pending.get(&key).map_or(false, |expected| {
+			signed.payload() == &expected.bitfield &&
+				signed
+					.as_unchecked()
+					.clone()
+					.try_into_checked(&expected.signing_context, &expected.validator)
+					.is_ok()
+		});
+
+		if !matches {
+			gum::warn!(
+				target: LOG_TARGET,
+				?relay_parent,
+				validator_index = signed.validator_index().0,
+				"Dropping externally signed bitfield which does not match the constructed one",
+			);
+			return false
+		}
+
+		pending.remove(&key);
+		drop(state);
+
+		sender
+			.send_message(BitfieldDistributionMessage::DistributeBitfield(relay_parent, signed))
+			.await;
+
+		true
+	}
+
+	/// Start accepting bitfields at the `activated` leaf, and forget the bitfields constructed at
+	/// any of the `deactivated` leaves.
+	async fn update_leaves(&self, activated: Option<Hash>, deactivated: &[Hash]) {
+		let mut state = self.0.lock().await;
+		for leaf in deactivated {
+			state.active_leaves.remove(leaf);
+		}
+		state.active_leaves.extend(activated);
+		state.pending.retain(|(relay_parent, _), _| !deactivated.contains(relay_parent));
+	}
+}
+
 /// The bitfield signing subsystem.
 pub struct BitfieldSigningSubsystem {
 	keystore: KeystorePtr,
+	config: Config,
 	metrics: Metrics,
 }
 
 impl BitfieldSigningSubsystem {
 	/// Create a new instance of the `BitfieldSigningSubsystem`.
 	pub fn new(keystore: KeystorePtr, metrics: Metrics) -> Self {
-		Self { keystore, metrics }
+		Self::with_config(keystore, Config::default(), metrics)
+	}
+
+	/// Create a new instance of the `BitfieldSigningSubsystem` with the given config.
+	pub fn with_config(keystore: KeystorePtr, config: Config, metrics: Metrics) -> Self {
+		Self { keystore, config, metrics }
 	}
 }
 
@@ -196,7 +566,7 @@ impl BitfieldSigningSubsystem {
 impl<Context> BitfieldSigningSubsystem {
 	fn start(self, ctx: Context) -> SpawnedSubsystem {
 		let future = async move {
-			run(ctx, self.keystore, self.metrics)
+			run(ctx, self.keystore, self.config, self.metrics)
 				.await
 				.map_err(|e| SubsystemError::with_origin("bitfield-signing", e))
 		}
@@ -210,20 +580,25 @@ impl<Context> BitfieldSigningSubsystem {
 async fn run<Context>(
 	mut ctx: Context,
 	keystore: KeystorePtr,
+	config: Config,
 	metrics: Metrics,
 ) -> SubsystemResult<()> {
 	// Track spawned jobs per active leaf.
-	let mut running = HashMap::<Hash, future::AbortHandle>::new();
+	let mut running = HashMap::<Hash, RunningJob>::new();
+	let history = config.availability_self_check.then(AvailabilityHistory::default);
+	let external = ExternalBitfields::default();
 
 	loop {
 		match ctx.recv().await? {
 			FromOrchestra::Signal(OverseerSignal::ActiveLeaves(update)) => {
 				// Abort jobs for deactivated leaves.
 				for leaf in &update.deactivated {
-					if let Some(handle) = running.remove(leaf) {
-						handle.abort();
+					if let Some(job) = running.remove(leaf) {
+						job.abort_handle.abort();
 					}
 				}
+				let activated = update.activated.as_ref().map(|leaf| leaf.hash);
+				external.update_leaves(activated, &update.deactivated).await;
 
 				if let Some(leaf) = update.activated {
 					let sender = ctx.sender().clone();
@@ -233,26 +608,116 @@ async fn run<Context>(
 						sender,
 						leaf,
 						keystore.clone(),
+						config.clone(),
 						metrics.clone(),
+						history.clone(),
 					));
 
-					running.insert(leaf_hash, handle);
+					let (done_tx, done) = oneshot::channel();
+					running.insert(leaf_hash, RunningJob { abort_handle: handle, done });
 
-					ctx.spawn("bitfield-signing-job", fut.map(drop).boxed())?;
+					ctx.spawn("bitfield-signing-job", fut.map(move |_| drop(done_tx)).boxed())?;
 				}
 			},
 			FromOrchestra::Signal(OverseerSignal::BlockFinalized(..)) => {},
-			FromOrchestra::Signal(OverseerSignal::Conclude) => return Ok(()),
-			FromOrchestra::Communication { .. } => {},
+			FromOrchestra::Signal(OverseerSignal::Conclude) => {
+				drain_running_jobs(running, config.shutdown_timeout).await;
+				return Ok(())
+			},
+			FromOrchestra::Communication {
+				msg: BitfieldSigningMessage::ConstructUnsigned(relay_parent, validator_index, tx),
+			} => {
+				let mut sender = ctx.sender().clone();
+				let external = external.clone();
+				let config = config.clone();
+				let metrics = metrics.clone();
+
+				ctx.spawn(
+					"bitfield-signing-construct-unsigned",
+					async move {
+						let bitfield = external
+							.construct(
+								relay_parent,
+								validator_index,
+								&config,
+								&metrics,
+								&mut sender,
+							)
+							.await
+							.unwrap_or_else(|err| {
+								gum::warn!(
+									target: LOG_TARGET,
+									?relay_parent,
+									?err,
+									"Failed to construct bitfield for external signing",
+								);
+								None
+							});
+						let _ = tx.send(bitfield);
+					}
+					.boxed(),
+				)?;
+			},
+			FromOrchestra::Communication {
+				msg: BitfieldSigningMessage::DistributeExternallySigned(relay_parent, signed),
+			} => {
+				external.distribute(relay_parent, signed, ctx.sender()).await;
+			},
+		}
+	}
+}
+
+/// A spawned bitfield signing job.
+struct RunningJob {
+	abort_handle: future::AbortHandle,
+	/// Resolves once the job has finished, either by completing or by being aborted.
+	done: oneshot::Receiver<()>,
+}
+
+/// Wait up to `timeout` for the given jobs to finish, aborting any that are still running
+/// afterwards.
+async fn drain_running_jobs(running: HashMap<Hash, RunningJob>, timeout: Duration) {
+	if running.is_empty() {
+		return
+	}
+
+	let (abort_handles, done): (Vec<_>, Vec<_>) =
+		running.into_values().map(|job| (job.abort_handle, job.done)).unzip();
+
+	let all_done = future::join_all(done);
+	let timeout = Delay::new(timeout);
+	pin_mut!(all_done, timeout);
+
+	if let future::Either::Right(_) = future::select(all_done, timeout).await {
+		gum::debug!(
+			target: LOG_TARGET,
+			jobs = abort_handles.len(),
+			"Timed out waiting for bitfield signing jobs to finish, aborting",
+		);
+
+		for handle in abort_handles {
+			handle.abort();
 		}
 	}
 }
 
+/// Await the `response` to the runtime API `call`, observing how long it took.
+async fn timed_runtime_api<F: Future>(
+	metrics: &Metrics,
+	call: &'static str,
+	response: F,
+) -> F::Output {
+	let _timer = metrics.time_runtime_api(call);
+	response.await
+}
+
 async fn handle_active_leaves_update<Sender>(
 	mut sender: Sender,
 	leaf: ActivatedLeaf,
 	keystore: KeystorePtr,
+	config: Config,
 	metrics: Metrics,
+	history: Option<AvailabilityHistory>,
 ) -> Result<(), Error>
 where
 	Sender: overseer::BitfieldSigningSenderTrait,
@@ -263,7 +728,23 @@ where
 
 	// now do all the work we can before we need to wait for the availability store
 	// if we're not a validator, we can just succeed effortlessly
-	let validator = match Validator::new(leaf.hash, keystore.clone(), &mut sender).await {
+	let (validators, session_index) = futures::try_join!(
+		timed_runtime_api(
+			&metrics,
+			"validators",
+			util::request_validators(leaf.hash, &mut sender).await,
+		),
+		timed_runtime_api(
+			&metrics,
+			"session_index_for_child",
+			util::request_session_index_for_child(leaf.hash, &mut sender).await,
+		),
+	)?;
+	let validators = validators?;
+	let session_index = session_index?;
+	let signing_context = SigningContext { session_index, parent_hash: leaf.hash };
+
+	let validator = match Validator::construct(&validators, signing_context, keystore.clone()) {
 		Ok(validator) => validator,
 		Err(util::Error::NotAValidator) => return Ok(()),
 		Err(err) => return Err(Error::Util(err)),
@@ -279,10 +760,14 @@ where
 	drop(span_delay);
 	let span_availability = span.child("availability");
 
-	let bitfield = match construct_availability_bitfield(
+	let bitfield = match checked_availability_bitfield(
 		leaf.hash,
 		&span_availability,
 		validator.index(),
+		validators.len(),
+		&config,
+		&metrics,
+		history.as_ref(),
 		&mut sender,
 	)
 	.await
@@ -307,11 +792,22 @@ where
 					target: LOG_TARGET,
 					"Key was found at construction, but while signing it could not be found.",
 				);
-				return Ok(())
+				metrics.on_signing_key_missing();
+				return Err(Error::SigningKeyDisappeared)
 			},
 		};
 
 	metrics.on_bitfield_signed();
+	if config.report_signed_bitfields {
+		gum::info!(
+			target: LOG_TARGET,
+			relay_parent = ?leaf.hash,
+			validator_index = signed_bitfield.validator_index().0,
+			session_index,
+			bitfield = %hex_bits(signed_bitfield.payload()),
+			"Signed availability bitfield",
+		);
+	}
 
 	drop(span_signing);
 	let _span_gossip = span.child("gossip");
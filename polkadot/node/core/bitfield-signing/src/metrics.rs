@@ -19,7 +19,10 @@ use polkadot_node_subsystem_util::metrics::{self, prometheus};
 #[derive(Clone)]
 pub(crate) struct MetricsInner {
 	pub(crate) bitfields_signed_total: prometheus::Counter<prometheus::U64>,
+	pub(crate) signing_key_missing_total: prometheus::Counter<prometheus::U64>,
+	pub(crate) availability_query_timeouts_total: prometheus::Counter<prometheus::U64>,
 	pub(crate) run: prometheus::Histogram,
+	pub(crate) runtime_api_duration: prometheus::HistogramVec,
 }
 
 /// Bitfield signing metrics.
@@ -33,10 +36,32 @@ impl Metrics {
 		}
 	}
 
+	pub fn on_signing_key_missing(&self) {
+		if let Some(metrics) = &self.0 {
+			metrics.signing_key_missing_total.inc();
+		}
+	}
+
+	pub fn on_availability_query_timeout(&self) {
+		if let Some(metrics) = &self.0 {
+			metrics.availability_query_timeouts_total.inc();
+		}
+	}
+
 	/// Provide a timer for `prune_povs` which observes on drop.
 	pub fn time_run(&self) -> Option<metrics::prometheus::prometheus::HistogramTimer> {
 		self.0.as_ref().map(|metrics| metrics.run.start_timer())
 	}
+
+	/// Provide a timer for the runtime API `call` which observes on drop.
+	pub fn time_runtime_api(
+		&self,
+		call: &'static str,
+	) -> Option<metrics::prometheus::prometheus::HistogramTimer> {
+		self.0
+			.as_ref()
+			.map(|metrics| metrics.runtime_api_duration.with_label_values(&[call]).start_timer())
+	}
 }
 
 impl metrics::Metrics for Metrics {
@@ -49,6 +74,20 @@ impl metrics::Metrics for Metrics {
 				)?,
 				registry,
 			)?,
+			signing_key_missing_total: prometheus::register(
+				prometheus::Counter::new(
+					"polkadot_parachain_bitfield_signing_key_missing_total",
+					"Number of bitfields not signed because the validator key disappeared from the keystore.",
+				)?,
+				registry,
+			)?,
+			availability_query_timeouts_total: prometheus::register(
+				prometheus::Counter::new(
+					"polkadot_parachain_bitfield_signing_availability_query_timeouts_total",
+					"Number of chunk availability queries that timed out and were assumed unavailable.",
+				)?,
+				registry,
+			)?,
 			run: prometheus::register(
 				prometheus::Histogram::with_opts(
 					prometheus::HistogramOpts::new(
@@ -62,6 +101,20 @@ impl metrics::Metrics for Metrics {
 				)?,
 				registry,
 			)?,
+			runtime_api_duration: prometheus::register(
+				prometheus::HistogramVec::new(
+					prometheus::HistogramOpts::new(
+						"polkadot_parachain_bitfield_signing_runtime_api_duration",
+						"Time spent waiting for runtime API calls made by bitfield signing",
+					)
+					.buckets(vec![
+						0.000625, 0.00125, 0.0025, 0.005, 0.0075, 0.01, 0.025, 0.05, 0.1, 0.25,
+						0.5, 1.0, 2.5, 5.0, 10.0,
+					]),
+					&["call"],
+				)?,
+				registry,
+			)?,
 		};
 		Ok(Metrics(Some(metrics)))
 	}
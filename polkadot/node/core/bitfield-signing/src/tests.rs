@@ -17,8 +17,11 @@
 use super::*;
 use futures::{executor::block_on, pin_mut, StreamExt};
 use polkadot_node_subsystem::messages::AllMessages;
+use polkadot_node_subsystem_test_helpers::mock::new_leaf;
+use polkadot_node_subsystem_util::metrics::{prometheus, Metrics as _};
 use polkadot_primitives::{CandidateHash, OccupiedCore};
-use test_helpers::dummy_candidate_descriptor;
+use sp_keystore::Keystore;
+use test_helpers::{dummy_candidate_descriptor, dummy_validator};
 
 fn occupied_core(para_id: u32, candidate_hash: CandidateHash) -> CoreState {
 	CoreState::Occupied(OccupiedCore {
@@ -44,6 +47,9 @@ fn construct_availability_bitfield_works() {
 			relay_parent,
 			&jaeger::Span::Disabled,
 			validator_index,
+			&Config::default(),
+			&Metrics::default(),
+			None,
 			&mut sender,
 		)
 		.fuse();
@@ -83,3 +89,537 @@ fn construct_availability_bitfield_works() {
 		}
 	});
 }
+
+/// Construct a bitfield for three cores where the availability query for the last one fails.
+fn construct_bitfield_with_failing_core(lenient: bool) -> Result<AvailabilityBitfield, Error> {
+	block_on(async move {
+		let relay_parent = Hash::default();
+		let validator_index = ValidatorIndex(1u32);
+
+		let (mut sender, mut receiver) = polkadot_node_subsystem_test_helpers::sender_receiver();
+		let future = construct_availability_bitfield(
+			relay_parent,
+			&jaeger::Span::Disabled,
+			validator_index,
+			&Config { lenient_core_availability: lenient, ..Default::default() },
+			&Metrics::default(),
+			None,
+			&mut sender,
+		)
+		.fuse();
+		pin_mut!(future);
+
+		let hash_a = CandidateHash(Hash::repeat_byte(1));
+		let hash_b = CandidateHash(Hash::repeat_byte(2));
+
+		loop {
+			futures::select! {
+				m = receiver.next() => match m.unwrap() {
+					AllMessages::RuntimeApi(
+						RuntimeApiMessage::Request(_, RuntimeApiRequest::AvailabilityCores(tx)),
+					) => {
+						tx.send(Ok(vec![CoreState::Free, occupied_core(1, hash_a), occupied_core(2, hash_b)])).unwrap();
+					}
+					AllMessages::AvailabilityStore(
+						AvailabilityStoreMessage::QueryChunkAvailability(c_hash, _, tx),
+					) => {
+						// Dropping the sender makes the query for `hash_b` fail.
+						if c_hash == hash_a {
+							tx.send(true).unwrap();
+						}
+					},
+					o => panic!("Unknown message: {:?}", o),
+				},
+				r = future => return r,
+			}
+		}
+	})
+}
+
+#[test]
+fn construct_availability_bitfield_fails_on_core_error_in_strict_mode() {
+	assert!(matches!(construct_bitfield_with_failing_core(false), Err(Error::Oneshot(_))));
+}
+
+#[test]
+fn construct_availability_bitfield_tolerates_core_error_in_lenient_mode() {
+	let bitfield = construct_bitfield_with_failing_core(true).unwrap();
+
+	assert!(!bitfield.0.get(0).unwrap());
+	assert!(bitfield.0.get(1).unwrap());
+	assert!(!bitfield.0.get(2).unwrap());
+}
+
+#[test]
+fn construct_availability_bitfield_times_out_on_unresponsive_store() {
+	block_on(async move {
+		let (mut sender, mut receiver) = polkadot_node_subsystem_test_helpers::sender_receiver();
+		let config =
+			Config { availability_query_timeout: Duration::from_millis(50), ..Default::default() };
+		let future = construct_availability_bitfield(
+			Hash::default(),
+			&jaeger::Span::Disabled,
+			ValidatorIndex(1u32),
+			&config,
+			&Metrics::default(),
+			None,
+			&mut sender,
+		)
+		.fuse();
+		pin_mut!(future);
+
+		let hash_a = CandidateHash(Hash::repeat_byte(1));
+		let hash_b = CandidateHash(Hash::repeat_byte(2));
+		// Hold on to the senders of unanswered queries, so they don't fail but hang.
+		let mut unanswered = Vec::new();
+
+		loop {
+			futures::select! {
+				m = receiver.next() => match m.unwrap() {
+					AllMessages::RuntimeApi(
+						RuntimeApiMessage::Request(_, RuntimeApiRequest::AvailabilityCores(tx)),
+					) => {
+						tx.send(Ok(vec![occupied_core(1, hash_a), occupied_core(2, hash_b)])).unwrap();
+					}
+					AllMessages::AvailabilityStore(
+						AvailabilityStoreMessage::QueryChunkAvailability(c_hash, _, tx),
+					) => {
+						if c_hash == hash_a {
+							tx.send(true).unwrap();
+						} else {
+							unanswered.push(tx);
+						}
+					},
+					o => panic!("Unknown message: {:?}", o),
+				},
+				r = future => {
+					let bitfield = r.unwrap();
+					assert!(bitfield.0.get(0).unwrap());
+					assert!(!bitfield.0.get(1).unwrap());
+					break
+				},
+			}
+		}
+	});
+}
+
+/// Construct a bitfield for a single core occupied by `candidate_hash`, with the store reporting
+/// the chunk as `available`.
+fn construct_bitfield_with_history(
+	history: &AvailabilityHistory,
+	candidate_hash: CandidateHash,
+	available: bool,
+) -> AvailabilityBitfield {
+	block_on(async move {
+		let (mut sender, mut receiver) = polkadot_node_subsystem_test_helpers::sender_receiver();
+		let future = construct_availability_bitfield(
+			Hash::default(),
+			&jaeger::Span::Disabled,
+			ValidatorIndex(1u32),
+			&Config { availability_self_check: true, ..Default::default() },
+			&Metrics::default(),
+			Some(history),
+			&mut sender,
+		)
+		.fuse();
+		pin_mut!(future);
+
+		loop {
+			futures::select! {
+				m = receiver.next() => match m.unwrap() {
+					AllMessages::RuntimeApi(
+						RuntimeApiMessage::Request(_, RuntimeApiRequest::AvailabilityCores(tx)),
+					) => {
+						tx.send(Ok(vec![occupied_core(1, candidate_hash)])).unwrap();
+					}
+					AllMessages::AvailabilityStore(
+						AvailabilityStoreMessage::QueryChunkAvailability(_, _, tx),
+					) => {
+						tx.send(available).unwrap();
+					},
+					o => panic!("Unknown message: {:?}", o),
+				},
+				r = future => return r.unwrap(),
+			}
+		}
+	})
+}
+
+#[test]
+fn availability_self_check_does_not_alter_bitfield() {
+	let history = AvailabilityHistory::default();
+	let hash = CandidateHash(Hash::repeat_byte(1));
+
+	assert!(construct_bitfield_with_history(&history, hash, true).0.get(0).unwrap());
+	assert!(!construct_bitfield_with_history(&history, hash, false).0.get(0).unwrap());
+}
+
+#[test]
+fn availability_self_check_reports_chunks_becoming_unavailable() {
+	block_on(async move {
+		let history = AvailabilityHistory::default();
+		let hash_a = CandidateHash(Hash::repeat_byte(1));
+		let hash_b = CandidateHash(Hash::repeat_byte(2));
+		let cores = vec![occupied_core(1, hash_a), occupied_core(2, hash_b), CoreState::Free];
+		let relay_parent = Hash::default();
+
+		assert!(history
+			.check_and_record(relay_parent, &cores, &[true, false, false])
+			.await
+			.is_empty());
+		assert_eq!(
+			history.check_and_record(relay_parent, &cores, &[false, false, false]).await,
+			vec![hash_a],
+		);
+		// The regression is only reported once.
+		assert!(history
+			.check_and_record(relay_parent, &cores, &[false, false, false])
+			.await
+			.is_empty());
+	});
+}
+
+#[test]
+fn drain_running_jobs_waits_for_in_flight_jobs() {
+	block_on(async move {
+		let (fut, abort_handle) = future::abortable(Delay::new(Duration::from_millis(50)));
+		let (done_tx, done) = oneshot::channel();
+		let job = fut.map(move |res| {
+			drop(done_tx);
+			res
+		});
+
+		let running = HashMap::from([(Hash::repeat_byte(1), RunningJob { abort_handle, done })]);
+
+		let (res, _) =
+			future::join(job, drain_running_jobs(running, Duration::from_secs(10))).await;
+		assert!(res.is_ok(), "job must complete without being aborted");
+	});
+}
+
+#[test]
+fn drain_running_jobs_aborts_after_timeout() {
+	block_on(async move {
+		let (fut, abort_handle) = future::abortable(future::pending::<()>());
+		let (done_tx, done) = oneshot::channel();
+		let job = fut.map(move |res| {
+			drop(done_tx);
+			res
+		});
+
+		let running = HashMap::from([(Hash::repeat_byte(1), RunningJob { abort_handle, done })]);
+
+		let (res, _) =
+			future::join(job, drain_running_jobs(running, Duration::from_millis(50))).await;
+		assert!(res.is_err(), "job must be aborted once the timeout elapses");
+	});
+}
+
+#[test]
+fn default_shutdown_timeout_outlasts_a_fresh_job() {
+	assert!(
+		Config::default().shutdown_timeout >
+			SPAWNED_TASK_DELAY + DEFAULT_AVAILABILITY_QUERY_TIMEOUT,
+		"draining must not abort jobs that are still waiting out the spawn delay",
+	);
+}
+
+#[test]
+fn availability_bitfield_for_rejects_out_of_bounds_validator_index() {
+	block_on(async move {
+		let (mut sender, _receiver) = polkadot_node_subsystem_test_helpers::sender_receiver();
+
+		let res = availability_bitfield_for(
+			Hash::default(),
+			ValidatorIndex(3),
+			3,
+			&Config::default(),
+			&mut sender,
+		)
+		.await;
+
+		assert!(matches!(
+			res,
+			Err(Error::ValidatorIndexOutOfBounds {
+				validator_index: ValidatorIndex(3),
+				validator_count: 3
+			})
+		));
+	});
+}
+
+#[test]
+fn runtime_api_duration_is_observed_for_validators_call() {
+	let registry = prometheus::Registry::new();
+	let metrics = Metrics::try_register(&registry).unwrap();
+
+	block_on(async {
+		let (sender, mut receiver) = polkadot_node_subsystem_test_helpers::sender_receiver();
+		let keystore: KeystorePtr = Arc::new(sp_keystore::testing::MemoryKeystore::new());
+		let future = handle_active_leaves_update(
+			sender,
+			new_leaf(Hash::repeat_byte(1), 1),
+			keystore,
+			Config::default(),
+			metrics.clone(),
+			None,
+		)
+		.fuse();
+		pin_mut!(future);
+
+		loop {
+			futures::select! {
+				m = receiver.next() => match m.unwrap() {
+					AllMessages::RuntimeApi(
+						RuntimeApiMessage::Request(_, RuntimeApiRequest::Validators(tx)),
+					) => {
+						tx.send(Ok(Vec::new())).unwrap();
+					},
+					AllMessages::RuntimeApi(
+						RuntimeApiMessage::Request(_, RuntimeApiRequest::SessionIndexForChild(tx)),
+					) => {
+						tx.send(Ok(1)).unwrap();
+					},
+					o => panic!("Unknown message: {:?}", o),
+				},
+				// Not a validator, so the job returns right after the initial runtime calls.
+				r = future => {
+					r.unwrap();
+					break
+				},
+			}
+		}
+	});
+
+	let runtime_api_duration = &metrics.0.as_ref().unwrap().runtime_api_duration;
+	assert_eq!(runtime_api_duration.with_label_values(&["validators"]).get_sample_count(), 1);
+}
+
+/// Records the fields of all events of this subsystem, formatted with `Debug`.
+#[derive(Clone, Default)]
+struct EventCollector(Arc<std::sync::Mutex<Vec<HashMap<&'static str, String>>>>);
+
+struct FieldRecorder<'a>(&'a mut HashMap<&'static str, String>);
+
+impl tracing::field::Visit for FieldRecorder<'_> {
+	fn record_debug(&mut self, field: &tracing::field::Field, value: &dyn std::fmt::Debug) {
+		self.0.insert(field.name(), format!("{value:?}"));
+	}
+}
+
+impl tracing::Subscriber for EventCollector {
+	fn enabled(&self, _: &tracing::Metadata<'_>) -> bool {
+		true
+	}
+
+	fn new_span(&self, _: &tracing::span::Attributes<'_>) -> tracing::span::Id {
+		tracing::span::Id::from_u64(1)
+	}
+
+	fn record(&self, _: &tracing::span::Id, _: &tracing::span::Record<'_>) {}
+
+	fn record_follows_from(&self, _: &tracing::span::Id, _: &tracing::span::Id) {}
+
+	fn event(&self, event: &tracing::Event<'_>) {
+		if event.metadata().target() == LOG_TARGET {
+			let mut fields = HashMap::new();
+			event.record(&mut FieldRecorder(&mut fields));
+			self.0.lock().unwrap().push(fields);
+		}
+	}
+
+	fn enter(&self, _: &tracing::span::Id) {}
+
+	fn exit(&self, _: &tracing::span::Id) {}
+}
+
+#[test]
+fn signed_bitfield_is_reported_when_enabled() {
+	let keystore: KeystorePtr = Arc::new(sp_keystore::testing::MemoryKeystore::new());
+	let validator = keystore
+		.sr25519_generate_new(polkadot_primitives::PARACHAIN_KEY_TYPE_ID, Some("//Alice"))
+		.unwrap();
+	let collector = EventCollector::default();
+	let _guard = tracing::subscriber::set_default(collector.clone());
+	let config = Config { report_signed_bitfields: true, ..Default::default() };
+
+	block_on(async {
+		let (sender, mut receiver) = polkadot_node_subsystem_test_helpers::sender_receiver();
+		let future = handle_active_leaves_update(
+			sender,
+			new_leaf(Hash::repeat_byte(1), 1),
+			keystore,
+			config,
+			Metrics::default(),
+			None,
+		)
+		.fuse();
+		pin_mut!(future);
+
+		loop {
+			futures::select! {
+				m = receiver.next() => match m.unwrap() {
+					AllMessages::RuntimeApi(
+						RuntimeApiMessage::Request(_, RuntimeApiRequest::Validators(tx)),
+					) => {
+						tx.send(Ok(vec![validator.into()])).unwrap();
+					},
+					AllMessages::RuntimeApi(
+						RuntimeApiMessage::Request(_, RuntimeApiRequest::SessionIndexForChild(tx)),
+					) => {
+						tx.send(Ok(3)).unwrap();
+					},
+					AllMessages::RuntimeApi(
+						RuntimeApiMessage::Request(_, RuntimeApiRequest::AvailabilityCores(tx)),
+					) => {
+						tx.send(Ok(vec![CoreState::Free])).unwrap();
+					},
+					AllMessages::BitfieldDistribution(
+						BitfieldDistributionMessage::DistributeBitfield(..),
+					) => {},
+					o => panic!("Unknown message: {:?}", o),
+				},
+				r = future => {
+					r.unwrap();
+					break
+				},
+			}
+		}
+	});
+
+	let events = collector.0.lock().unwrap();
+	let event = events
+		.iter()
+		.find(|fields| {
+			fields.get("message").map(String::as_str) == Some("Signed availability bitfield")
+		})
+		.unwrap();
+	assert_eq!(event["relay_parent"], format!("{:?}", Hash::repeat_byte(1)));
+	assert_eq!(event["validator_index"], "0");
+	assert_eq!(event["session_index"], "3");
+	assert_eq!(event["bitfield"], "00");
+}
+
+#[test]
+fn externally_signed_bitfield_is_distributed_only_if_it_matches() {
+	let relay_parent = Hash::repeat_byte(1);
+	let validator_index = ValidatorIndex(0);
+	let external_signer: KeystorePtr = Arc::new(sp_keystore::testing::MemoryKeystore::new());
+	let validator: ValidatorId = external_signer
+		.sr25519_generate_new(polkadot_primitives::PARACHAIN_KEY_TYPE_ID, Some("//Alice"))
+		.unwrap()
+		.into();
+	let signing_context = SigningContext { session_index: 3, parent_hash: relay_parent };
+	let external = ExternalBitfields::default();
+
+	block_on(async {
+		let (mut sender, mut receiver) = polkadot_node_subsystem_test_helpers::sender_receiver();
+		external.update_leaves(Some(relay_parent), &[]).await;
+
+		let future = external
+			.construct(
+				relay_parent,
+				validator_index,
+				&Config::default(),
+				&Metrics::default(),
+				&mut sender,
+			)
+			.fuse();
+		pin_mut!(future);
+
+		let bitfield = loop {
+			futures::select! {
+				m = receiver.next() => match m.unwrap() {
+					AllMessages::RuntimeApi(
+						RuntimeApiMessage::Request(_, RuntimeApiRequest::Validators(tx)),
+					) => {
+						tx.send(Ok(vec![validator.clone()])).unwrap();
+					},
+					AllMessages::RuntimeApi(
+						RuntimeApiMessage::Request(_, RuntimeApiRequest::SessionIndexForChild(tx)),
+					) => {
+						tx.send(Ok(3)).unwrap();
+					},
+					AllMessages::RuntimeApi(
+						RuntimeApiMessage::Request(_, RuntimeApiRequest::AvailabilityCores(tx)),
+					) => {
+						tx.send(Ok(vec![CoreState::Free])).unwrap();
+					},
+					o => panic!("Unknown message: {:?}", o),
+				},
+				r = future => break r.unwrap().unwrap(),
+			}
+		};
+		assert_eq!(bitfield, AvailabilityBitfield(FromIterator::from_iter([false])));
+
+		let sign = |bitfield| {
+			SignedAvailabilityBitfield::sign(
+				&external_signer,
+				bitfield,
+				&signing_context,
+				validator_index,
+				&validator,
+			)
+			.unwrap()
+			.unwrap()
+		};
+
+		let tampered = sign(AvailabilityBitfield(FromIterator::from_iter([true])));
+		assert!(!external.distribute(relay_parent, tampered, &mut sender).await);
+		assert!(receiver.try_next().is_err(), "tampered bitfield must not be distributed");
+
+		let signed = sign(bitfield);
+		assert!(external.distribute(relay_parent, signed.clone(), &mut sender).await);
+		match receiver.next().await.unwrap() {
+			AllMessages::BitfieldDistribution(BitfieldDistributionMessage::DistributeBitfield(
+				rp,
+				distributed,
+			)) => {
+				assert_eq!(rp, relay_parent);
+				assert_eq!(distributed, signed);
+			},
+			o => panic!("Unknown message: {:?}", o),
+		}
+
+		// Each constructed bitfield is distributed at most once.
+		assert!(!external.distribute(relay_parent, signed, &mut sender).await);
+	});
+}
+
+#[test]
+fn external_bitfields_are_only_kept_for_active_leaves() {
+	let relay_parent = Hash::repeat_byte(1);
+	let external = ExternalBitfields::default();
+	let pending = PendingExternalBitfield {
+		bitfield: AvailabilityBitfield(FromIterator::from_iter([false])),
+		signing_context: SigningContext { session_index: 3, parent_hash: relay_parent },
+		validator: dummy_validator(),
+	};
+
+	block_on(async {
+		let (mut sender, mut receiver) = polkadot_node_subsystem_test_helpers::sender_receiver();
+
+		// Not an active leaf, nothing is constructed.
+		let constructed = external
+			.construct(
+				relay_parent,
+				ValidatorIndex(0),
+				&Config::default(),
+				&Metrics::default(),
+				&mut sender,
+			)
+			.await
+			.unwrap();
+		assert_eq!(constructed, None);
+		assert!(receiver.try_next().is_err(), "no runtime API must be queried");
+
+		external.update_leaves(Some(relay_parent), &[]).await;
+		let key = (relay_parent, ValidatorIndex(0));
+		external.0.lock().await.pending.insert(key, pending);
+
+		external.update_leaves(None, &[relay_parent]).await;
+		let state = external.0.lock().await;
+		assert!(state.active_leaves.is_empty());
+		assert!(state.pending.is_empty());
+	});
+}
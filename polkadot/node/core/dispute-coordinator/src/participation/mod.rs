@@ -332,7 +332,9 @@ async fn participate(
 			send_result(&mut result_sender, req, ParticipationOutcome::Invalid).await;
 			return
 		},
-		Ok(Err(RecoveryError::Unavailable)) | Ok(Err(RecoveryError::ChannelClosed)) => {
+		Ok(Err(RecoveryError::Unavailable)) |
+		Ok(Err(RecoveryError::ChannelClosed)) |
+		Ok(Err(RecoveryError::Timeout { .. })) => {
 			gum::debug!(
 				target: LOG_TARGET,
 				candidate_hash = ?req.candidate_hash(),
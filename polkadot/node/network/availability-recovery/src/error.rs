@@ -17,20 +17,25 @@
 //! The `Error` and `Result` types used by the subsystem.
 
 use futures::channel::oneshot;
-use thiserror::Error;
 
 /// Error type used by the Availability Recovery subsystem.
-#[derive(Debug, Error)]
+///
+/// Fatal errors stop the subsystem, all others only fail the recovery request they occurred in.
+#[fatality::fatality]
 pub enum Error {
+	#[fatal]
 	#[error(transparent)]
 	Subsystem(#[from] polkadot_node_subsystem::SubsystemError),
 
+	// av-store will drop the sender on any error that happens.
 	#[error("failed to query full data from store")]
 	CanceledQueryFullData(#[source] oneshot::Canceled),
 
+	#[fatal]
 	#[error("failed to query session info")]
 	CanceledSessionInfo(#[source] oneshot::Canceled),
 
+	// The requester is no longer interested in the result.
 	#[error("failed to send response")]
 	CanceledResponseSender,
 
@@ -40,6 +45,20 @@ pub enum Error {
 	#[error(transparent)]
 	Erasure(#[from] polkadot_erasure_coding::Error),
 
+	// Reconstruction was attempted with fewer usable chunks than the recovery threshold. Unlike
+	// `Erasure`, this says nothing about the validity of the data that was fetched.
+	#[error("not enough chunks to reconstruct the data, have {have}, needed {needed}")]
+	InsufficientChunks { have: usize, needed: usize },
+
+	#[error(
+		"recovery of candidate {candidate:?} did not finish within the deadline, \
+		validators {unresponsive:?} never answered"
+	)]
+	RecoveryTimeout {
+		candidate: polkadot_primitives::CandidateHash,
+		unresponsive: Vec<polkadot_primitives::ValidatorIndex>,
+	},
+
 	#[error(transparent)]
 	Util(#[from] polkadot_node_subsystem_util::Error),
 }
@@ -23,6 +23,7 @@ use std::{
 	iter::Iterator,
 	num::NonZeroUsize,
 	pin::Pin,
+	time::Duration,
 };
 
 use futures::{
@@ -37,7 +38,7 @@ use futures::{
 use schnellru::{ByLength, LruMap};
 use task::{FetchChunks, FetchChunksParams, FetchFull, FetchFullParams};
 
-use fatality::Nested;
+use fatality::{Fatality, Nested};
 use polkadot_erasure_coding::{
 	branch_hash, branches, obtain_chunks_v1, recovery_threshold, Error as ErasureEncodingError,
 };
@@ -107,6 +108,8 @@ pub struct AvailabilityRecoverySubsystem {
 	metrics: Metrics,
 	/// The type of check to perform after available data was recovered.
 	post_recovery_check: PostRecoveryCheck,
+	/// Deadline for a whole recovery, if any.
+	recovery_timeout: Option<Duration>,
 }
 
 #[derive(Clone, PartialEq, Debug)]
@@ -124,7 +127,7 @@ pub enum ErasureTask {
 	Reconstruct(
 		usize,
 		HashMap<ValidatorIndex, ErasureChunk>,
-		oneshot::Sender<Result<AvailableData, ErasureEncodingError>>,
+		oneshot::Sender<error::Result<AvailableData>>,
 	),
 	/// Re-encode `AvailableData` into erasure chunks in order to verify the provided root hash of
 	/// the Merkle tree.
@@ -289,6 +292,7 @@ impl TryFrom<Result<AvailableData, RecoveryError>> for CachedRecovery {
 			// requested again we want to try again!
 			Err(RecoveryError::Unavailable) => Err(()),
 			Err(RecoveryError::ChannelClosed) => Err(()),
+			Err(RecoveryError::Timeout { .. }) => Err(()),
 		}
 	}
 }
@@ -356,6 +360,7 @@ async fn launch_recovery_task<Context>(
 	recovery_strategies: VecDeque<Box<dyn RecoveryStrategy<<Context as SubsystemContext>::Sender>>>,
 	bypass_availability_store: bool,
 	post_recovery_check: PostRecoveryCheck,
+	recovery_timeout: Option<Duration>,
 ) -> error::Result<()> {
 	let candidate_hash = receipt.hash();
 	let params = RecoveryParams {
@@ -368,6 +373,7 @@ async fn launch_recovery_task<Context>(
 		bypass_availability_store,
 		post_recovery_check,
 		pov_hash: receipt.descriptor.pov_hash,
+		recovery_timeout,
 	};
 
 	let recovery_task = RecoveryTask::new(ctx.sender().clone(), params, recovery_strategies);
@@ -405,6 +411,7 @@ async fn handle_recover<Context>(
 	recovery_strategy_kind: RecoveryStrategyKind,
 	bypass_availability_store: bool,
 	post_recovery_check: PostRecoveryCheck,
+	recovery_timeout: Option<Duration>,
 ) -> error::Result<()> {
 	let candidate_hash = receipt.hash();
 
@@ -502,6 +509,7 @@ async fn handle_recover<Context>(
 				recovery_strategies,
 				bypass_availability_store,
 				post_recovery_check,
+				recovery_timeout,
 			)
 			.await
 		},
@@ -556,6 +564,7 @@ impl AvailabilityRecoverySubsystem {
 			post_recovery_check: PostRecoveryCheck::PovHash,
 			req_receiver,
 			metrics,
+			recovery_timeout: None,
 		}
 	}
 
@@ -571,6 +580,7 @@ impl AvailabilityRecoverySubsystem {
 			post_recovery_check: PostRecoveryCheck::Reencode,
 			req_receiver,
 			metrics,
+			recovery_timeout: None,
 		}
 	}
 
@@ -585,6 +595,7 @@ impl AvailabilityRecoverySubsystem {
 			post_recovery_check: PostRecoveryCheck::Reencode,
 			req_receiver,
 			metrics,
+			recovery_timeout: None,
 		}
 	}
 
@@ -600,9 +611,17 @@ impl AvailabilityRecoverySubsystem {
 			post_recovery_check: PostRecoveryCheck::Reencode,
 			req_receiver,
 			metrics,
+			recovery_timeout: None,
 		}
 	}
 
+	/// Fail recoveries which have not concluded after `timeout`, instead of waiting for
+	/// unresponsive validators indefinitely.
+	pub fn with_recovery_timeout(mut self, timeout: Duration) -> Self {
+		self.recovery_timeout = Some(timeout);
+		self
+	}
+
 	/// Starts the inner subsystem loop.
 	pub async fn run<Context>(self, mut ctx: Context) -> SubsystemResult<()> {
 		let mut state = State::default();
@@ -612,6 +631,7 @@ impl AvailabilityRecoverySubsystem {
 			recovery_strategy_kind,
 			bypass_availability_store,
 			post_recovery_check,
+			recovery_timeout,
 		} = self;
 
 		let (erasure_task_tx, erasure_task_rx) = futures::channel::mpsc::channel(16);
@@ -704,8 +724,12 @@ impl AvailabilityRecoverySubsystem {
 										erasure_task_tx.clone(),
 										recovery_strategy_kind.clone(),
 										bypass_availability_store,
-										post_recovery_check.clone()
+										post_recovery_check.clone(),
+										recovery_timeout,
 									).await {
+										if e.is_fatal() {
+											return Err(SubsystemError::with_origin("availability-recovery", e))
+										}
 										gum::warn!(
 											target: LOG_TARGET,
 											err = ?e,
@@ -817,6 +841,27 @@ impl ThreadPoolBuilder {
 	}
 }
 
+/// Reconstruct `AvailableData` from the given chunks.
+///
+/// Running short of chunks is reported as [`error::Error::InsufficientChunks`], so that callers can
+/// tell it apart from chunks which were received but could not be decoded.
+fn reconstruct(
+	n_validators: usize,
+	chunks: &HashMap<ValidatorIndex, ErasureChunk>,
+) -> error::Result<AvailableData> {
+	polkadot_erasure_coding::reconstruct_v1(
+		n_validators,
+		chunks.values().map(|c| (&c.chunk[..], c.index.0 as usize)),
+	)
+	.or_else(|err| match err {
+		ErasureEncodingError::NotEnoughChunks => Err(error::Error::InsufficientChunks {
+			have: chunks.len(),
+			needed: recovery_threshold(n_validators)?,
+		}),
+		err => Err(err.into()),
+	})
+}
+
 // Handles CPU intensive operation on a dedicated blocking thread.
 async fn erasure_task_thread(
 	metrics: Metrics,
@@ -825,10 +870,7 @@ async fn erasure_task_thread(
 	loop {
 		match ingress.next().await {
 			Some(ErasureTask::Reconstruct(n_validators, chunks, sender)) => {
-				let _ = sender.send(polkadot_erasure_coding::reconstruct_v1(
-					n_validators,
-					chunks.values().map(|c| (&c.chunk[..], c.index.0 as usize)),
-				));
+				let _ = sender.send(reconstruct(n_validators, &chunks));
 			},
 			Some(ErasureTask::Reencode(n_validators, root, available_data, sender)) => {
 				let metrics = metrics.clone();
@@ -157,6 +157,13 @@ impl Metrics {
 		}
 	}
 
+	/// A full recovery failed (the recovery deadline passed).
+	pub fn on_recovery_timed_out(&self) {
+		if let Some(metrics) = &self.0 {
+			metrics.full_recoveries_finished.with_label_values(&["timeout"]).inc()
+		}
+	}
+
 	/// A recover was started.
 	pub fn on_recovery_started(&self) {
 		if let Some(metrics) = &self.0 {
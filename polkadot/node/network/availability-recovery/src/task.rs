@@ -19,8 +19,8 @@
 #![warn(missing_docs)]
 
 use crate::{
-	futures_undead::FuturesUndead, is_chunk_valid, is_unavailable, metrics::Metrics, ErasureTask,
-	PostRecoveryCheck, LOG_TARGET,
+	error::Error, futures_undead::FuturesUndead, is_chunk_valid, is_unavailable, metrics::Metrics,
+	ErasureTask, PostRecoveryCheck, LOG_TARGET,
 };
 use futures::{channel::oneshot, SinkExt};
 use parity_scale_codec::Encode;
@@ -34,11 +34,12 @@ use polkadot_node_subsystem::{
 	messages::{AvailabilityStoreMessage, NetworkBridgeTxMessage},
 	overseer, RecoveryError,
 };
+use polkadot_node_subsystem_util::TimeoutExt;
 use polkadot_primitives::{AuthorityDiscoveryId, CandidateHash, Hash, ValidatorIndex};
 use rand::seq::SliceRandom;
 use sc_network::{IfDisconnected, OutboundFailure, RequestFailure};
 use std::{
-	collections::{HashMap, VecDeque},
+	collections::{HashMap, HashSet, VecDeque},
 	time::Duration,
 };
 
@@ -102,18 +103,26 @@ pub struct RecoveryParams {
 
 	/// The blake2-256 hash of the PoV.
 	pub pov_hash: Hash,
+
+	/// Give up on the recovery if it did not conclude within this time.
+	pub recovery_timeout: Option<Duration>,
 }
 
+/// Response to a chunk request, tagged with the validator it was sent to.
+type ChunkResponse = (ValidatorIndex, Result<Option<ErasureChunk>, RequestError>);
+
 /// Intermediate/common data that must be passed between `RecoveryStrategy`s belonging to the
 /// same `RecoveryTask`.
 pub struct State {
 	/// Chunks received so far.
 	received_chunks: HashMap<ValidatorIndex, ErasureChunk>,
+	/// Validators we sent a request to and did not hear back from yet.
+	pending_requests: HashSet<ValidatorIndex>,
 }
 
 impl State {
 	fn new() -> Self {
-		Self { received_chunks: HashMap::new() }
+		Self { received_chunks: HashMap::new(), pending_requests: HashSet::new() }
 	}
 
 	fn insert_chunk(&mut self, validator: ValidatorIndex, chunk: ErasureChunk) {
@@ -179,9 +188,7 @@ impl State {
 		sender: &mut Sender,
 		desired_requests_count: usize,
 		validators: &mut VecDeque<ValidatorIndex>,
-		requesting_chunks: &mut FuturesUndead<
-			Result<Option<ErasureChunk>, (ValidatorIndex, RequestError)>,
-		>,
+		requesting_chunks: &mut FuturesUndead<ChunkResponse>,
 	) where
 		Sender: overseer::AvailabilityRecoverySenderTrait,
 	{
@@ -209,18 +216,19 @@ impl State {
 
 				let (req, res) = OutgoingRequest::new(Recipient::Authority(validator), raw_request);
 				requests.push(Requests::ChunkFetchingV1(req));
+				self.pending_requests.insert(validator_index);
 
 				params.metrics.on_chunk_request_issued();
 				let timer = params.metrics.time_chunk_request();
 
 				requesting_chunks.push(Box::pin(async move {
 					let _timer = timer;
-					match res.await {
-						Ok(req_res::v1::ChunkFetchingResponse::Chunk(chunk)) =>
-							Ok(Some(chunk.recombine_into_chunk(&raw_request))),
-						Ok(req_res::v1::ChunkFetchingResponse::NoSuchChunk) => Ok(None),
-						Err(e) => Err((validator_index, e)),
-					}
+					let response = res.await.map(|response| match response {
+						req_res::v1::ChunkFetchingResponse::Chunk(chunk) =>
+							Some(chunk.recombine_into_chunk(&raw_request)),
+						req_res::v1::ChunkFetchingResponse::NoSuchChunk => None,
+					});
+					(validator_index, response)
 				}));
 			} else {
 				break
@@ -240,9 +248,7 @@ impl State {
 		&mut self,
 		params: &RecoveryParams,
 		validators: &mut VecDeque<ValidatorIndex>,
-		requesting_chunks: &mut FuturesUndead<
-			Result<Option<ErasureChunk>, (ValidatorIndex, RequestError)>,
-		>,
+		requesting_chunks: &mut FuturesUndead<ChunkResponse>,
 		can_conclude: impl Fn(usize, usize, usize, &RecoveryParams, usize) -> bool,
 	) -> (usize, usize) {
 		let metrics = &params.metrics;
@@ -253,9 +259,10 @@ impl State {
 		// Wait for all current requests to conclude or time-out, or until we reach enough chunks.
 		// We also declare requests undead, once `TIMEOUT_START_NEW_REQUESTS` is reached and will
 		// return in that case for `launch_parallel_requests` to fill up slots again.
-		while let Some(request_result) =
+		while let Some((validator_index, request_result)) =
 			requesting_chunks.next_with_timeout(TIMEOUT_START_NEW_REQUESTS).await
 		{
+			self.pending_requests.remove(&validator_index);
 			total_received_responses += 1;
 
 			match request_result {
@@ -277,7 +284,7 @@ impl State {
 					metrics.on_chunk_request_no_such_chunk();
 					error_count += 1;
 				},
-				Err((validator_index, e)) => {
+				Err(e) => {
 					error_count += 1;
 
 					gum::trace!(
@@ -395,6 +402,9 @@ where
 
 	/// Run this recovery task to completion. It will loop through the configured strategies
 	/// in-order and return whenever the first one recovers the full `AvailableData`.
+	///
+	/// If a `recovery_timeout` is configured and passes first, the recovery is abandoned with
+	/// [`RecoveryError::Timeout`], listing the validators that never answered.
 	pub async fn run(mut self) -> Result<AvailableData, RecoveryError> {
 		if let Some(data) = self.in_availability_store().await {
 			return Ok(data)
@@ -404,6 +414,40 @@ where
 
 		let _timer = self.params.metrics.time_full_recovery();
 
+		let Some(recovery_timeout) = self.params.recovery_timeout else {
+			return self.run_strategies().await
+		};
+
+		match self.run_strategies().timeout(recovery_timeout).await {
+			Some(res) => res,
+			None => {
+				let mut unresponsive: Vec<_> =
+					self.state.pending_requests.iter().copied().collect();
+				unresponsive.sort();
+				let unresponsive_peers: Vec<_> = unresponsive
+					.iter()
+					.map(|index| &self.params.validator_authority_keys[index.0 as usize])
+					.collect();
+
+				let err = Error::RecoveryTimeout {
+					candidate: self.params.candidate_hash,
+					unresponsive: unresponsive.clone(),
+				};
+				gum::warn!(
+					target: LOG_TARGET,
+					?err,
+					?recovery_timeout,
+					?unresponsive_peers,
+					"Recovery of available data timed out",
+				);
+				self.params.metrics.on_recovery_timed_out();
+
+				Err(RecoveryError::Timeout { unresponsive })
+			},
+		}
+	}
+
+	async fn run_strategies(&mut self) -> Result<AvailableData, RecoveryError> {
 		while let Some(mut current_strategy) = self.strategies.pop_front() {
 			gum::debug!(
 				target: LOG_TARGET,
@@ -480,7 +524,7 @@ impl<Sender: overseer::AvailabilityRecoverySenderTrait> RecoveryStrategy<Sender>
 
 	async fn run(
 		&mut self,
-		_: &mut State,
+		state: &mut State,
 		sender: &mut Sender,
 		common_params: &RecoveryParams,
 	) -> Result<AvailableData, RecoveryError> {
@@ -506,7 +550,11 @@ impl<Sender: overseer::AvailabilityRecoverySenderTrait> RecoveryStrategy<Sender>
 				))
 				.await;
 
-			match response.await {
+			state.pending_requests.insert(validator_index);
+			let response = response.await;
+			state.pending_requests.remove(&validator_index);
+
+			match response {
 				Ok(req_res::v1::AvailableDataFetchingResponse::AvailableData(data)) => {
 					let maybe_data = match common_params.post_recovery_check {
 						PostRecoveryCheck::Reencode => {
@@ -571,7 +619,7 @@ pub struct FetchChunks {
 	/// Total number of responses that have been received, including failed ones.
 	total_received_responses: usize,
 	/// Collection of in-flight requests.
-	requesting_chunks: FuturesUndead<Result<Option<ErasureChunk>, (ValidatorIndex, RequestError)>>,
+	requesting_chunks: FuturesUndead<ChunkResponse>,
 	/// A random shuffling of the validators which indicates the order in which we connect to the
 	/// validators and request the chunk from them.
 	validators: VecDeque<ValidatorIndex>,
@@ -714,6 +762,20 @@ impl FetchChunks {
 					Err(RecoveryError::Invalid)
 				}
 			},
+			Err(err @ Error::InsufficientChunks { .. }) => {
+				recovery_duration.map(|rd| rd.stop_and_discard());
+				gum::debug!(
+					target: LOG_TARGET,
+					candidate_hash = ?common_params.candidate_hash,
+					erasure_root = ?common_params.erasure_root,
+					?err,
+					"Data recovery error - not enough chunks",
+				);
+
+				// Nothing is known to be wrong with the data, so don't cache this as invalid and
+				// let the requester try again.
+				Err(RecoveryError::Unavailable)
+			},
 			Err(err) => {
 				recovery_duration.map(|rd| rd.stop_and_discard());
 				gum::trace!(
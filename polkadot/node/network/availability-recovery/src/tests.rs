@@ -14,7 +14,7 @@
 // You should have received a copy of the GNU General Public License
 // along with Polkadot.  If not, see <http://www.gnu.org/licenses/>.
 
-use std::{sync::Arc, time::Duration};
+use std::{cell::RefCell, sync::Arc, time::Duration};
 
 use assert_matches::assert_matches;
 use futures::{executor, future};
@@ -85,6 +85,15 @@ fn test_harness_fast_path<T: Future<Output = (VirtualOverseer, RequestResponseCo
 
 fn test_harness_chunks_only<T: Future<Output = (VirtualOverseer, RequestResponseConfig)>>(
 	test: impl FnOnce(VirtualOverseer, RequestResponseConfig) -> T,
+) {
+	test_harness_chunks_only_with_recovery_timeout(None, test)
+}
+
+fn test_harness_chunks_only_with_recovery_timeout<
+	T: Future<Output = (VirtualOverseer, RequestResponseConfig)>,
+>(
+	recovery_timeout: Option<Duration>,
+	test: impl FnOnce(VirtualOverseer, RequestResponseConfig) -> T,
 ) {
 	let _ = env_logger::builder()
 		.is_test(true)
@@ -97,10 +106,13 @@ fn test_harness_chunks_only<T: Future<Output = (VirtualOverseer, RequestResponse
 
 	let (collation_req_receiver, req_cfg) =
 		IncomingRequest::get_config_receiver(&ReqProtocolNames::new(&GENESIS_HASH, None));
-	let subsystem = AvailabilityRecoverySubsystem::with_chunks_only(
+	let mut subsystem = AvailabilityRecoverySubsystem::with_chunks_only(
 		collation_req_receiver,
 		Metrics::new_dummy(),
 	);
+	if let Some(recovery_timeout) = recovery_timeout {
+		subsystem = subsystem.with_recovery_timeout(recovery_timeout);
+	}
 	let subsystem = subsystem.run(context);
 
 	let test_fut = test(virtual_overseer, req_cfg);
@@ -1383,6 +1395,68 @@ fn all_not_returning_requests_still_recovers_on_return() {
 	});
 }
 
+#[test]
+fn recovery_times_out_if_no_validator_responds() {
+	let test_state = TestState::default();
+	let recovery_timeout = Duration::from_millis(500);
+
+	test_harness_chunks_only_with_recovery_timeout(
+		Some(recovery_timeout),
+		|mut virtual_overseer, req_cfg| async move {
+			overseer_signal(
+				&mut virtual_overseer,
+				OverseerSignal::ActiveLeaves(ActiveLeavesUpdate::start_work(new_leaf(
+					test_state.current,
+					1,
+				))),
+			)
+			.await;
+
+			let (tx, rx) = oneshot::channel();
+
+			overseer_send(
+				&mut virtual_overseer,
+				AvailabilityRecoveryMessage::RecoverAvailableData(
+					test_state.candidate.clone(),
+					test_state.session_index,
+					None,
+					tx,
+				),
+			)
+			.await;
+
+			test_state.test_runtime_api(&mut virtual_overseer).await;
+
+			let candidate_hash = test_state.candidate.hash();
+
+			test_state.respond_to_available_data_query(&mut virtual_overseer, false).await;
+			test_state.respond_to_query_all_request(&mut virtual_overseer, |_| false).await;
+
+			// Keep the senders alive, so the requests neither succeed nor fail.
+			let asked = RefCell::new(Vec::new());
+			let _senders = test_state
+				.test_chunk_requests(
+					candidate_hash,
+					&mut virtual_overseer,
+					test_state.threshold(),
+					|i| {
+						asked.borrow_mut().push(ValidatorIndex(i as _));
+						Has::DoesNotReturn
+					},
+				)
+				.await;
+
+			// The deadline started before the chunk requests went out, `TIMEOUT` is only slack for
+			// scheduling.
+			let result = rx.timeout(recovery_timeout + TIMEOUT).await.expect("recovery timed out");
+			let mut unresponsive = asked.into_inner();
+			unresponsive.sort();
+			assert_eq!(result.unwrap().unwrap_err(), RecoveryError::Timeout { unresponsive });
+			(virtual_overseer, req_cfg)
+		},
+	);
+}
+
 #[test]
 fn returns_early_if_we_have_the_data() {
 	let test_state = TestState::default();
@@ -1523,3 +1597,68 @@ fn invalid_local_chunk_is_ignored() {
 		(virtual_overseer, req_cfg)
 	});
 }
+
+#[test]
+fn errors_are_classified_by_fatality() {
+	let canceled = || {
+		let (tx, rx) = oneshot::channel::<()>();
+		drop(tx);
+		executor::block_on(rx).unwrap_err()
+	};
+
+	let fatal = [
+		error::Error::Subsystem(SubsystemError::Context("test".into())),
+		error::Error::CanceledSessionInfo(canceled()),
+	];
+	for e in fatal {
+		assert!(e.is_fatal(), "{e:?} must be fatal");
+	}
+
+	let recoverable = [
+		error::Error::CanceledQueryFullData(canceled()),
+		error::Error::CanceledResponseSender,
+		error::Error::Runtime(polkadot_node_subsystem::errors::RuntimeApiError::NotSupported {
+			runtime_api_name: "test",
+		}),
+		error::Error::Erasure(polkadot_erasure_coding::Error::NotEnoughValidators),
+		error::Error::InsufficientChunks { have: 1, needed: 2 },
+		error::Error::RecoveryTimeout {
+			candidate: CandidateHash(Hash::repeat_byte(1)),
+			unresponsive: vec![ValidatorIndex(1)],
+		},
+		error::Error::Util(polkadot_node_subsystem_util::Error::NotAValidator),
+	];
+	for e in recoverable {
+		assert!(!e.is_fatal(), "{e:?} must not be fatal");
+	}
+}
+
+#[test]
+fn reconstruct_below_threshold_is_insufficient_chunks() {
+	let n_validators = 10;
+	let threshold = recovery_threshold(n_validators).unwrap();
+
+	let available_data = AvailableData {
+		validation_data: PersistedValidationData {
+			parent_head: HeadData(vec![7, 8, 9]),
+			relay_parent_number: Default::default(),
+			max_pov_size: 1024,
+			relay_parent_storage_root: Default::default(),
+		},
+		pov: Arc::new(PoV { block_data: BlockData(vec![42; 64]) }),
+	};
+	let (chunks, _) =
+		derive_erasure_chunks_with_proofs_and_root(n_validators, &available_data, |_, _| {});
+	let mut chunks: HashMap<_, _> = chunks.into_iter().map(|c| (c.index, c)).collect();
+
+	assert_eq!(reconstruct(n_validators, &chunks).unwrap(), available_data);
+
+	chunks.retain(|index, _| (index.0 as usize) < threshold - 1);
+	assert_matches!(
+		reconstruct(n_validators, &chunks),
+		Err(error::Error::InsufficientChunks { have, needed }) => {
+			assert_eq!(have, threshold - 1);
+			assert_eq!(needed, threshold);
+		}
+	);
+}
@@ -351,6 +351,10 @@ impl MessageSink for TestMessageSink {
 	) -> Result<(), sc_network::error::Error> {
 		unimplemented!();
 	}
+
+	fn is_connected(&self) -> bool {
+		unimplemented!();
+	}
 }
 
 #[async_trait::async_trait]
@@ -407,6 +411,14 @@ impl NotificationService for TestNotificationService {
 	fn message_sink(&self, peer: &PeerId) -> Option<Box<dyn MessageSink>> {
 		Some(Box::new(TestMessageSink::new(*peer, self.peer_set, self.action_tx.clone())))
 	}
+
+	fn last_sent(&self, _peer: &PeerId) -> Option<std::time::Instant> {
+		None
+	}
+
+	fn connected_peers(&self) -> Vec<PeerId> {
+		unimplemented!();
+	}
 }
 
 #[derive(Clone)]
@@ -108,6 +108,10 @@ impl MessageSink for TestMessageSink {
 	) -> Result<(), sc_network::error::Error> {
 		unimplemented!();
 	}
+
+	fn is_connected(&self) -> bool {
+		unimplemented!();
+	}
 }
 
 fn new_test_network(
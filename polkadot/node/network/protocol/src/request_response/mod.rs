@@ -198,6 +198,7 @@ impl Protocol {
 				// We are connected to all validators:
 				request_timeout: CHUNK_REQUEST_TIMEOUT,
 				inbound_queue: tx,
+				max_bandwidth_bps: None,
 			},
 			Protocol::CollationFetchingV1 | Protocol::CollationFetchingV2 =>
 				RequestResponseConfig {
@@ -208,6 +209,7 @@ impl Protocol {
 					// Taken from initial implementation in collator protocol:
 					request_timeout: POV_REQUEST_TIMEOUT_CONNECTED,
 					inbound_queue: tx,
+					max_bandwidth_bps: None,
 				},
 			Protocol::PoVFetchingV1 => RequestResponseConfig {
 				name,
@@ -216,6 +218,7 @@ impl Protocol {
 				max_response_size: POV_RESPONSE_SIZE,
 				request_timeout: POV_REQUEST_TIMEOUT_CONNECTED,
 				inbound_queue: tx,
+				max_bandwidth_bps: None,
 			},
 			Protocol::AvailableDataFetchingV1 => RequestResponseConfig {
 				name,
@@ -225,6 +228,7 @@ impl Protocol {
 				max_response_size: POV_RESPONSE_SIZE,
 				request_timeout: POV_REQUEST_TIMEOUT_CONNECTED,
 				inbound_queue: tx,
+				max_bandwidth_bps: None,
 			},
 			Protocol::StatementFetchingV1 => RequestResponseConfig {
 				name,
@@ -243,6 +247,7 @@ impl Protocol {
 				// also decrease its reputation.
 				request_timeout: Duration::from_secs(1),
 				inbound_queue: tx,
+				max_bandwidth_bps: None,
 			},
 			Protocol::DisputeSendingV1 => RequestResponseConfig {
 				name,
@@ -253,6 +258,7 @@ impl Protocol {
 				max_response_size: 100,
 				request_timeout: DISPUTE_REQUEST_TIMEOUT,
 				inbound_queue: tx,
+				max_bandwidth_bps: None,
 			},
 			Protocol::AttestedCandidateV2 => RequestResponseConfig {
 				name,
@@ -261,6 +267,7 @@ impl Protocol {
 				max_response_size: ATTESTED_CANDIDATE_RESPONSE_SIZE,
 				request_timeout: ATTESTED_CANDIDATE_TIMEOUT,
 				inbound_queue: tx,
+				max_bandwidth_bps: None,
 			},
 		}
 	}
@@ -910,6 +910,7 @@ pub fn new_full<OverseerGenerator: OverseerGen>(
 			import_queue,
 			block_announce_validator_builder: None,
 			warp_sync_params: Some(WarpSyncParams::WithProvider(warp_sync)),
+			warp_sync_options: Default::default(),
 			block_relay: None,
 		})?;
 
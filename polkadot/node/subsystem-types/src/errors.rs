@@ -78,6 +78,12 @@ pub enum RecoveryError {
 
 	/// Erasure task channel closed, usually means node is shutting down.
 	ChannelClosed,
+
+	/// The recovery did not conclude within its deadline.
+	Timeout {
+		/// Validators that were asked for a chunk and never answered.
+		unresponsive: Vec<polkadot_primitives::ValidatorIndex>,
+	},
 }
 
 impl std::fmt::Display for RecoveryError {
@@ -86,6 +92,7 @@ impl std::fmt::Display for RecoveryError {
 			RecoveryError::Invalid => "Invalid",
 			RecoveryError::Unavailable => "Unavailable",
 			RecoveryError::ChannelClosed => "ChannelClosed",
+			RecoveryError::Timeout { .. } => "Timeout",
 		};
 
 		write!(f, "{}", msg)
@@ -44,13 +44,14 @@ use polkadot_node_primitives::{
 use polkadot_primitives::{
 	async_backing, slashing,
 	vstaging::{ApprovalVotingParams, NodeFeatures},
-	AuthorityDiscoveryId, BackedCandidate, BlockNumber, CandidateEvent, CandidateHash,
-	CandidateIndex, CandidateReceipt, CollatorId, CommittedCandidateReceipt, CoreState,
-	DisputeState, ExecutorParams, GroupIndex, GroupRotationInfo, Hash, Header as BlockHeader,
-	Id as ParaId, InboundDownwardMessage, InboundHrmpMessage, MultiDisputeStatementSet,
-	OccupiedCoreAssumption, PersistedValidationData, PvfCheckStatement, PvfExecKind, SessionIndex,
-	SessionInfo, SignedAvailabilityBitfield, SignedAvailabilityBitfields, ValidationCode,
-	ValidationCodeHash, ValidatorId, ValidatorIndex, ValidatorSignature,
+	AuthorityDiscoveryId, AvailabilityBitfield, BackedCandidate, BlockNumber, CandidateEvent,
+	CandidateHash, CandidateIndex, CandidateReceipt, CollatorId, CommittedCandidateReceipt,
+	CoreState, DisputeState, ExecutorParams, GroupIndex, GroupRotationInfo, Hash,
+	Header as BlockHeader, Id as ParaId, InboundDownwardMessage, InboundHrmpMessage,
+	MultiDisputeStatementSet, OccupiedCoreAssumption, PersistedValidationData, PvfCheckStatement,
+	PvfExecKind, SessionIndex, SessionInfo, SignedAvailabilityBitfield,
+	SignedAvailabilityBitfields, ValidationCode, ValidationCodeHash, ValidatorId, ValidatorIndex,
+	ValidatorSignature,
 };
 use polkadot_statement_table::v2::Misbehavior;
 use std::{
@@ -488,6 +489,24 @@ pub enum BitfieldDistributionMessage {
 	NetworkBridgeUpdate(NetworkBridgeEvent<net_protocol::BitfieldDistributionMessage>),
 }
 
+/// Bitfield signing message.
+///
+/// Lets a signer outside of the node keystore sign the bitfields of a validator.
+#[derive(Debug)]
+pub enum BitfieldSigningMessage {
+	/// Construct the availability bitfield of a validator at a relay parent, without signing
+	/// it.
+	///
+	/// Responds with `None` if the bitfield cannot be constructed, e.g. because the validator is
+	/// not part of the active set.
+	ConstructUnsigned(Hash, ValidatorIndex, oneshot::Sender<Option<AvailabilityBitfield>>),
+	/// Distribute a bitfield previously returned by `ConstructUnsigned`, once signed by the
+	/// external signer.
+	///
+	/// Dropped unless it carries exactly the constructed payload and a valid signature.
+	DistributeExternallySigned(Hash, SignedAvailabilityBitfield),
+}
+
 /// Availability store subsystem message.
 #[derive(Debug)]
 pub enum AvailabilityStoreMessage {
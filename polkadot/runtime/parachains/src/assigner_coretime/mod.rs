@@ -395,6 +395,68 @@ impl<T: Config> Pallet<T> {
 		});
 	}
 
+	/// Assignments core `core_idx` is serving at `now`, together with their share of the core.
+	///
+	/// Read-only counterpart of `ensure_workload`: queued schedules which became active, but were
+	/// not yet picked up, are taken into account without being moved into the descriptor.
+	pub(crate) fn current_assignments(
+		now: BlockNumberFor<T>,
+		core_idx: CoreIndex,
+	) -> Vec<(CoreAssignment, PartsOf57600)> {
+		let descriptor = CoreDescriptors::<T>::get(core_idx);
+
+		let mut assignments = descriptor
+			.current_work
+			.filter(|w| w.end_hint.map_or(true, |e| e > now))
+			.map(|w| w.assignments.into_iter().map(|(a, state)| (a, state.ratio)).collect());
+
+		if let Some(queue) = descriptor.queue.filter(|q| q.first <= now) {
+			let mut next_scheduled = queue.first;
+			assignments = loop {
+				let Some(schedule) = CoreSchedules::<T>::get((next_scheduled, core_idx)) else {
+					break None
+				};
+				if schedule.end_hint.map_or(true, |e| e > now) {
+					break Some(schedule.assignments)
+				}
+				if let Some(n) = schedule.next_schedule {
+					next_scheduled = n;
+				} else {
+					break None
+				}
+			};
+		}
+
+		assignments.unwrap_or_default()
+	}
+
+	/// The `end_hint` of the last assignment of core `core_idx`, if any.
+	///
+	/// That is the last queued schedule, or the current work if nothing is queued.
+	pub(crate) fn last_end_hint(core_idx: CoreIndex) -> Option<BlockNumberFor<T>> {
+		let descriptor = CoreDescriptors::<T>::get(core_idx);
+		match descriptor.queue {
+			Some(queue) => CoreSchedules::<T>::get((queue.last, core_idx))?.end_hint,
+			None => descriptor.current_work?.end_hint,
+		}
+	}
+
+	/// Drop the current and all queued assignments of core `core_idx`.
+	///
+	/// Returns the number of queued schedules removed.
+	pub(crate) fn clear_core(core_idx: CoreIndex) -> u32 {
+		let descriptor = CoreDescriptors::<T>::take(core_idx);
+
+		let mut removed = 0;
+		let mut next_scheduled = descriptor.queue.map(|queue| queue.first);
+		while let Some(begin) = next_scheduled {
+			next_scheduled =
+				CoreSchedules::<T>::take((begin, core_idx)).and_then(|s| s.next_schedule);
+			removed += 1;
+		}
+		removed
+	}
+
 	/// Append another assignment for a core.
 	///
 	/// Important only appending is allowed. Meaning, all already existing assignments must have a
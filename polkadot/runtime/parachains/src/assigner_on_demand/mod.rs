@@ -45,7 +45,7 @@ use frame_support::{
 use frame_system::pallet_prelude::*;
 use primitives::{CoreIndex, Id as ParaId};
 use sp_runtime::{
-	traits::{One, SaturatedConversion},
+	traits::{One, SaturatedConversion, Zero},
 	FixedPointNumber, FixedPointOperand, FixedU128, Perbill, Saturating,
 };
 
@@ -139,6 +139,11 @@ pub mod pallet {
 		/// The default value for the spot traffic multiplier.
 		#[pallet::constant]
 		type TrafficDefaultValue: Get<FixedU128>;
+
+		/// The number of most recent blocks whose revenue is tracked individually. Revenue of
+		/// older blocks that has not been claimed yet is added up into the oldest entry.
+		#[pallet::constant]
+		type MaxHistoricalRevenue: Get<u32>;
 	}
 
 	/// Creates an empty spot traffic value if one isn't present in storage already.
@@ -172,6 +177,12 @@ pub mod pallet {
 	pub(super) type ParaIdAffinity<T: Config> =
 		StorageMap<_, Twox256, ParaId, CoreAffinityCount, OptionQuery>;
 
+	/// Revenue from on demand orders not yet claimed by the broker chain, most recent block
+	/// first.
+	#[pallet::storage]
+	pub(super) type Revenue<T: Config> =
+		StorageValue<_, BoundedVec<BalanceOf<T>, T::MaxHistoricalRevenue>, ValueQuery>;
+
 	#[pallet::event]
 	#[pallet::generate_deposit(pub(super) fn deposit_event)]
 	pub enum Event<T: Config> {
@@ -196,6 +207,17 @@ pub mod pallet {
 	#[pallet::hooks]
 	impl<T: Config> Hooks<BlockNumberFor<T>> for Pallet<T> {
 		fn on_initialize(_now: BlockNumberFor<T>) -> Weight {
+			Revenue::<T>::mutate(|revenue| {
+				if let Some(overdue) =
+					revenue.force_insert_keep_left(0, Zero::zero()).ok().flatten()
+				{
+					if let Some(oldest) = revenue.last_mut() {
+						*oldest = oldest.saturating_add(overdue);
+					}
+				}
+			});
+			let revenue_weight = T::DbWeight::get().reads_writes(1, 1);
+
 			let config = <configuration::Pallet<T>>::config();
 			// Calculate spot price multiplier and store it.
 			let old_traffic = SpotTraffic::<T>::get();
@@ -213,7 +235,7 @@ pub mod pallet {
 						Pallet::<T>::deposit_event(Event::<T>::SpotTrafficSet {
 							traffic: new_traffic,
 						});
-						return T::DbWeight::get().reads_writes(2, 1)
+						return T::DbWeight::get().reads_writes(2, 1).saturating_add(revenue_weight)
 					}
 				},
 				Err(SpotTrafficCalculationErr::QueueCapacityIsZero) => {
@@ -235,7 +257,7 @@ pub mod pallet {
 					);
 				},
 			};
-			T::DbWeight::get().reads_writes(2, 0)
+			T::DbWeight::get().reads_writes(2, 0).saturating_add(revenue_weight)
 		}
 	}
 
@@ -349,6 +371,7 @@ where
 		let res = Pallet::<T>::add_on_demand_order(order, QueuePushDirection::Back);
 
 		if res.is_ok() {
+			Pallet::<T>::note_revenue(spot_price);
 			Pallet::<T>::deposit_event(Event::<T>::OnDemandOrderPlaced { para_id, spot_price });
 		}
 
@@ -528,6 +551,31 @@ where
 }
 
 impl<T: Config> Pallet<T> {
+	/// Add `amount` to the revenue of the current block.
+	pub(crate) fn note_revenue(amount: BalanceOf<T>) {
+		Revenue::<T>::mutate(|revenue| {
+			if let Some(current) = revenue.first_mut() {
+				*current = current.saturating_add(amount);
+			}
+		});
+	}
+
+	/// Remove and return the revenue of all blocks before `until`.
+	pub fn claim_revenue_until(until: BlockNumberFor<T>) -> BalanceOf<T> {
+		let now = <frame_system::Pallet<T>>::block_number();
+		let mut amount: BalanceOf<T> = Zero::zero();
+		Revenue::<T>::mutate(|revenue| {
+			// The entry at `index` holds the revenue of block `now - index`.
+			while let Some(index) = revenue.len().checked_sub(1) {
+				if now.saturating_sub((index as u32).into()) >= until {
+					break
+				}
+				amount = amount.saturating_add(revenue.pop().unwrap_or_else(Zero::zero));
+			}
+		});
+		amount
+	}
+
 	/// Take the next queued entry that is available for a given core index.
 	/// Invalidates and removes orders with a `para_id` that is not `ParaLifecycle::Parathread`
 	/// but only in [0..P] range slice of the order queue, where P is the element that is
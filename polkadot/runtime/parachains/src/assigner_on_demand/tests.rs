@@ -635,3 +635,34 @@ fn on_demand_orders_cannot_be_popped_if_lifecycle_changes() {
 		assert_eq!(OnDemandAssigner::pop_assignment_for_core(core_index), None);
 	});
 }
+
+#[test]
+fn revenue_is_claimed_until_the_requested_block() {
+	new_test_ext(GenesisConfigBuilder::default().build()).execute_with(|| {
+		for n in 1..=3 {
+			System::set_block_number(n);
+			OnDemandAssigner::on_initialize(n);
+			OnDemandAssigner::note_revenue(n.into());
+		}
+
+		assert_eq!(OnDemandAssigner::claim_revenue_until(1), 0);
+		assert_eq!(OnDemandAssigner::claim_revenue_until(3), 1 + 2);
+		// Already claimed revenue is not returned again.
+		assert_eq!(OnDemandAssigner::claim_revenue_until(3), 0);
+		assert_eq!(OnDemandAssigner::claim_revenue_until(4), 3);
+	});
+}
+
+#[test]
+fn unclaimed_revenue_beyond_history_is_kept() {
+	new_test_ext(GenesisConfigBuilder::default().build()).execute_with(|| {
+		let history = <Test as Config>::MaxHistoricalRevenue::get();
+		for n in 1..=history + 5 {
+			System::set_block_number(n);
+			OnDemandAssigner::on_initialize(n);
+			OnDemandAssigner::note_revenue(1);
+		}
+
+		assert_eq!(OnDemandAssigner::claim_revenue_until(history + 6), (history + 5).into());
+	});
+}
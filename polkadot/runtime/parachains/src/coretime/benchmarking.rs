@@ -14,11 +14,12 @@
 // You should have received a copy of the GNU General Public License
 // along with Polkadot.  If not, see <http://www.gnu.org/licenses/>.
 
-//! On demand assigner pallet benchmarking.
+//! Coretime pallet benchmarking.
 
 #![cfg(feature = "runtime-benchmarks")]
 
 use super::*;
+use crate::paras;
 use frame_benchmarking::v2::*;
 use frame_support::traits::OriginTrait;
 use pallet_broker::CoreIndex as BrokerCoreIndex;
@@ -41,6 +42,36 @@ mod benchmarks {
 		)
 	}
 
+	#[benchmark]
+	fn request_revenue_info_at() {
+		// Setup
+		let root_origin = <T as frame_system::Config>::RuntimeOrigin::root();
+		let history = <T as assigner_on_demand::Config>::MaxHistoricalRevenue::get();
+		// Worst case: every tracked block has revenue to claim.
+		for n in 1..=history {
+			let n = BlockNumberFor::<T>::from(n);
+			frame_system::Pallet::<T>::set_block_number(n);
+			assigner_on_demand::Pallet::<T>::on_initialize(n);
+			assigner_on_demand::Pallet::<T>::note_revenue(1u32.into());
+		}
+		frame_system::Pallet::<T>::set_block_number((history + 1).into());
+		paras::Pallet::<T>::heads_insert(&ParaId::from(T::BrokerId::get()), vec![1, 2, 3].into());
+
+		#[extrinsic_call]
+		_(root_origin as <T as frame_system::Config>::RuntimeOrigin, (history + 1).into())
+	}
+
+	#[benchmark]
+	fn credit_account() {
+		// Setup
+		let root_origin = <T as frame_system::Config>::RuntimeOrigin::root();
+		let who: T::AccountId = whitelisted_caller();
+		let amount = T::Currency::minimum_balance();
+
+		#[extrinsic_call]
+		_(root_origin as <T as frame_system::Config>::RuntimeOrigin, who, amount)
+	}
+
 	#[benchmark]
 	fn assign_core(s: Linear<1, 100>) {
 		// Setup
@@ -58,7 +89,15 @@ mod benchmarks {
 		// it won't effect the weight and splitting up the parts into even groupings may not
 		// work for every value `s`.
 		assignments.push((CoreAssignment::Task(s as u32), PartsOf57600::FULL));
+		// Make the paras reachable, so notifying them is not cut short when enabled.
+		for (assignment, _) in &assignments {
+			if let CoreAssignment::Task(para) = assignment {
+				paras::Pallet::<T>::heads_insert(&ParaId::from(*para), vec![1, 2, 3].into());
+			}
+		}
 
+		// Worst case for the core mapping: the core is not remapped, so both `BrokerToRelayCore`
+		// and `RelayToBrokerCore` are read.
 		let core_index: BrokerCoreIndex = 0;
 
 		#[extrinsic_call]
@@ -70,4 +109,107 @@ mod benchmarks {
 			Some(BlockNumberFor::<T>::from(20u32)),
 		)
 	}
+
+	#[benchmark]
+	fn force_clear_core() {
+		// Setup
+		let root_origin = <T as frame_system::Config>::RuntimeOrigin::root();
+		let core_index: BrokerCoreIndex = 0;
+		for begin in [5u32, 10, 15] {
+			assigner_coretime::Pallet::<T>::assign_core(
+				CoreIndex(core_index.into()),
+				BlockNumberFor::<T>::from(begin),
+				vec![(CoreAssignment::Pool, PartsOf57600::FULL)],
+				Some(BlockNumberFor::<T>::from(begin + 5)),
+			)
+			.expect("Assignments are valid and appended in order; qed");
+		}
+
+		#[extrinsic_call]
+		_(root_origin as <T as frame_system::Config>::RuntimeOrigin, core_index)
+	}
+
+	#[benchmark]
+	fn set_paused() -> Result<(), BenchmarkError> {
+		// Setup
+		let origin =
+			T::PauseOrigin::try_successful_origin().map_err(|_| BenchmarkError::Weightless)?;
+
+		#[extrinsic_call]
+		_(origin as <T as frame_system::Config>::RuntimeOrigin, true);
+
+		Ok(())
+	}
+
+	#[benchmark]
+	fn migrate_para_cores(c: Linear<1, 100>) {
+		// Setup
+		let root_origin = <T as frame_system::Config>::RuntimeOrigin::root();
+		let (old, new) = (ParaId::from(1000), ParaId::from(2000));
+		configuration::ActiveConfig::<T>::mutate(|config| config.coretime_cores = c);
+		// Worst case: `old` is served by every core.
+		for core in 0..c {
+			assigner_coretime::Pallet::<T>::assign_core(
+				CoreIndex(core),
+				BlockNumberFor::<T>::from(0u32),
+				vec![(CoreAssignment::Task(old.into()), PartsOf57600::FULL)],
+				None,
+			)
+			.expect("Assignments are valid and appended in order; qed");
+		}
+
+		#[extrinsic_call]
+		_(
+			root_origin as <T as frame_system::Config>::RuntimeOrigin,
+			old,
+			new,
+			BlockNumberFor::<T>::from(5u32),
+		)
+	}
+
+	#[benchmark]
+	fn set_assignments_frozen() {
+		// Setup
+		let root_origin = <T as frame_system::Config>::RuntimeOrigin::root();
+
+		#[extrinsic_call]
+		_(root_origin as <T as frame_system::Config>::RuntimeOrigin, true)
+	}
+
+	#[benchmark]
+	fn notify_para() {
+		// Setup
+		let para = ParaId::from(1000);
+		paras::Pallet::<T>::heads_insert(&para, vec![1, 2, 3].into());
+		let notification = CoreAssignmentNotification {
+			core: CoreIndex(0),
+			begin: BlockNumberFor::<T>::from(5u32),
+			end_hint: Some(BlockNumberFor::<T>::from(20u32)),
+		};
+
+		#[block]
+		{
+			Pallet::<T>::notify_para(para.into(), &notification);
+		}
+	}
+
+	#[benchmark]
+	fn remap_core() {
+		// Setup
+		let root_origin = <T as frame_system::Config>::RuntimeOrigin::root();
+		// Worst case: both the broker core index and the relay chain core are already remapped.
+		BrokerToRelayCore::<T>::insert(0, CoreIndex(2));
+		RelayToBrokerCore::<T>::insert(CoreIndex(2), 0);
+		BrokerToRelayCore::<T>::insert(3, CoreIndex(1));
+		RelayToBrokerCore::<T>::insert(CoreIndex(1), 3);
+
+		#[extrinsic_call]
+		_(root_origin as <T as frame_system::Config>::RuntimeOrigin, 0, CoreIndex(1))
+	}
+
+	impl_benchmark_test_suite!(
+		Pallet,
+		crate::mock::new_test_ext(Default::default()),
+		crate::mock::Test
+	);
 }
@@ -16,8 +16,107 @@
 
 //! Migrations for the Coretime pallet.
 
+pub use v1::MigrateToV1;
 pub use v_coretime::{GetLegacyLease, MigrateToCoretime};
 
+mod v1 {
+	use crate::{
+		assigner_coretime, configuration,
+		coretime::{Config, LastAssignment, Pallet},
+	};
+	use frame_support::{
+		migrations::VersionedMigration, pallet_prelude::*, traits::OnRuntimeUpgrade,
+	};
+	use primitives::CoreIndex;
+	#[cfg(feature = "try-runtime")]
+	use sp_std::vec::Vec;
+
+	/// Backfill [`LastAssignment`] from the assignments already known to the coretime assigner.
+	pub type MigrateToV1<T> = VersionedMigration<
+		0,
+		1,
+		UncheckedMigrateToV1<T>,
+		Pallet<T>,
+		<T as frame_system::Config>::DbWeight,
+	>;
+
+	pub struct UncheckedMigrateToV1<T>(sp_std::marker::PhantomData<T>);
+
+	impl<T: Config> OnRuntimeUpgrade for UncheckedMigrateToV1<T> {
+		fn on_runtime_upgrade() -> Weight {
+			let core_count = configuration::ActiveConfig::<T>::get().coretime_cores;
+			let mut writes = 0u64;
+			for core in (0..core_count).map(CoreIndex) {
+				if let Some(end) = assigner_coretime::Pallet::<T>::last_end_hint(core) {
+					LastAssignment::<T>::insert(core, end);
+					writes += 1;
+				}
+			}
+
+			log::info!("Backfilled `LastAssignment` for {writes} cores.");
+			// A core descriptor and the last schedule of its queue per core.
+			let reads = u64::from(core_count).saturating_mul(2).saturating_add(1);
+			T::DbWeight::get().reads_writes(reads, writes)
+		}
+
+		#[cfg(feature = "try-runtime")]
+		fn post_upgrade(_state: Vec<u8>) -> Result<(), sp_runtime::TryRuntimeError> {
+			ensure!(
+				StorageVersion::get::<Pallet<T>>() >= 1,
+				"Storage version should be >= 1 after the migration"
+			);
+			Ok(())
+		}
+	}
+
+	#[cfg(test)]
+	mod tests {
+		use super::*;
+		use crate::{
+			assigner_coretime::PartsOf57600,
+			mock::{new_test_ext, CoretimeAssigner, System, Test},
+			scheduler::common::AssignmentProvider,
+		};
+		use pallet_broker::CoreAssignment;
+
+		#[test]
+		fn last_assignment_is_backfilled_from_assigner() {
+			new_test_ext(Default::default()).execute_with(|| {
+				configuration::ActiveConfig::<Test>::mutate(|c| c.coretime_cores = 4);
+				let full_pool = || vec![(CoreAssignment::Pool, PartsOf57600::FULL)];
+				let assign = |core, begin, end_hint| {
+					assigner_coretime::Pallet::<Test>::assign_core(
+						CoreIndex(core),
+						begin,
+						full_pool(),
+						end_hint,
+					)
+					.unwrap()
+				};
+
+				// Queued assignments: the last one counts.
+				assign(0, 10, Some(20));
+				assign(0, 20, Some(30));
+				// Open ended.
+				assign(1, 10, None);
+				// Already being worked on.
+				assign(2, 1, Some(50));
+				System::set_block_number(1);
+				assert!(CoretimeAssigner::pop_assignment_for_core(CoreIndex(2)).is_some());
+
+				StorageVersion::new(0).put::<Pallet<Test>>();
+				MigrateToV1::<Test>::on_runtime_upgrade();
+
+				assert_eq!(LastAssignment::<Test>::get(CoreIndex(0)), Some(30));
+				assert_eq!(LastAssignment::<Test>::get(CoreIndex(1)), None);
+				assert_eq!(LastAssignment::<Test>::get(CoreIndex(2)), Some(50));
+				assert_eq!(LastAssignment::<Test>::get(CoreIndex(3)), None);
+				assert_eq!(StorageVersion::get::<Pallet<Test>>(), 1);
+			});
+		}
+	}
+}
+
 mod v_coretime {
 	#[cfg(feature = "try-runtime")]
 	use crate::scheduler::common::AssignmentProvider;
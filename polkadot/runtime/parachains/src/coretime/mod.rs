@@ -18,32 +18,52 @@
 //!
 //! <https://github.com/polkadot-fellows/RFCs/blob/main/text/0005-coretime-interface.md>
 
-use sp_std::{prelude::*, result};
+use sp_std::{collections::btree_set::BTreeSet, prelude::*, result};
 
-use frame_support::{pallet_prelude::*, traits::Currency};
+use frame_support::{
+	pallet_prelude::*,
+	traits::{
+		fungible::{Inspect, Mutate},
+		tokens::{DepositConsequence, Provenance},
+	},
+};
 use frame_system::pallet_prelude::*;
 pub use pallet::*;
 use pallet_broker::{CoreAssignment, CoreIndex as BrokerCoreIndex};
-use primitives::{CoreIndex, Id as ParaId};
-use sp_arithmetic::traits::SaturatedConversion;
+use parity_scale_codec::Codec;
+use primitives::{Balance, BlockNumber, CoreIndex, Id as ParaId};
+use sp_arithmetic::traits::{SaturatedConversion, Saturating};
+use sp_runtime::traits::Convert;
 use xcm::v3::{
-	send_xcm, Instruction, Junction, Junctions, MultiLocation, OriginKind, SendXcm, Xcm,
+	send_xcm, Instruction, Junction, Junctions, MultiLocation, OriginKind, SendError, SendXcm, Xcm,
 };
 
 use crate::{
 	assigner_coretime::{self, PartsOf57600},
+	assigner_on_demand, configuration,
 	initializer::{OnNewSession, SessionChangeNotification},
 	origin::{ensure_parachain, Origin},
 };
 
 mod benchmarking;
 pub mod migration;
+#[cfg(test)]
+mod tests;
+
+pub type BalanceOf<T> =
+	<<T as Config>::Currency as Inspect<<T as frame_system::Config>::AccountId>>::Balance;
 
 pub trait WeightInfo {
 	fn request_core_count() -> Weight;
-	//fn request_revenue_info_at() -> Weight;
-	//fn credit_account() -> Weight;
+	fn request_revenue_info_at() -> Weight;
+	fn credit_account() -> Weight;
 	fn assign_core(s: u32) -> Weight;
+	fn force_clear_core() -> Weight;
+	fn set_paused() -> Weight;
+	fn set_assignments_frozen() -> Weight;
+	fn migrate_para_cores(c: u32) -> Weight;
+	fn remap_core() -> Weight;
+	fn notify_para() -> Weight;
 }
 
 /// A weight info that is only suitable for testing.
@@ -53,17 +73,103 @@ impl WeightInfo for TestWeightInfo {
 	fn request_core_count() -> Weight {
 		Weight::MAX
 	}
-	// TODO: Add real benchmarking functionality for each of these to
-	// benchmarking.rs, then uncomment here and in trait definition.
-	/*fn request_revenue_info_at() -> Weight {
+	fn request_revenue_info_at() -> Weight {
 		Weight::MAX
 	}
 	fn credit_account() -> Weight {
 		Weight::MAX
-	}*/
+	}
 	fn assign_core(_s: u32) -> Weight {
 		Weight::MAX
 	}
+	fn force_clear_core() -> Weight {
+		Weight::MAX
+	}
+	fn set_paused() -> Weight {
+		Weight::MAX
+	}
+	fn set_assignments_frozen() -> Weight {
+		Weight::MAX
+	}
+	fn migrate_para_cores(_c: u32) -> Weight {
+		Weight::MAX
+	}
+	fn remap_core() -> Weight {
+		Weight::MAX
+	}
+	fn notify_para() -> Weight {
+		Weight::MAX
+	}
+}
+
+/// Number of cores per kind of assignment they are currently serving.
+///
+/// See [`Pallet::core_summary`] for how cores are classified.
+#[derive(Encode, Decode, TypeInfo, Clone, Copy, Default, PartialEq, Eq, RuntimeDebug)]
+pub struct CoreSummary {
+	/// Cores mostly assigned to tasks (parachains).
+	pub task: u16,
+	/// Cores mostly assigned to the on-demand pool.
+	pub pool: u16,
+	/// Cores without any assignment, or mostly assigned `Idle`.
+	pub idle: u16,
+	/// Cores split evenly between several kinds of assignment.
+	pub other: u16,
+}
+
+/// Notification sent to a parachain when a core is assigned to it, see
+/// [`Config::NotifyAssignedParas`].
+///
+/// It is delivered as the arguments of the call built by [`Config::NotificationCall`], in a
+/// `Transact` instruction with the XCM origin of the relay chain.
+#[derive(Encode, Decode, TypeInfo, Clone, PartialEq, Eq, RuntimeDebug)]
+pub struct CoreAssignmentNotification<BlockNumber> {
+	/// The core assigned to the parachain.
+	pub core: CoreIndex,
+	/// The relay chain block at which the assignment starts.
+	pub begin: BlockNumber,
+	/// The relay chain block at which the assignment is expected to end, if known.
+	pub end_hint: Option<BlockNumber>,
+}
+
+/// Encode a [`CoreAssignmentNotification`] as the call `CallIndex` of the pallet `PalletIndex`,
+/// to be dispatched with at most `CallWeight`.
+///
+/// The receiving call must take the fields of the notification, in order, as its arguments.
+pub struct NotificationCallAt<PalletIndex, CallIndex, CallWeight>(
+	PhantomData<(PalletIndex, CallIndex, CallWeight)>,
+);
+impl<BlockNumber: Encode, PalletIndex: Get<u8>, CallIndex: Get<u8>, CallWeight: Get<Weight>>
+	Convert<CoreAssignmentNotification<BlockNumber>, (Vec<u8>, Weight)>
+	for NotificationCallAt<PalletIndex, CallIndex, CallWeight>
+{
+	fn convert(notification: CoreAssignmentNotification<BlockNumber>) -> (Vec<u8>, Weight) {
+		((PalletIndex::get(), CallIndex::get(), notification).encode(), CallWeight::get())
+	}
+}
+
+/// Ensure the origin is Root or the broker parachain, [`Config::BrokerId`].
+///
+/// Unlike the calls of this pallet, it does not reject the broker parachain while its calls are
+/// paused, see [`Pallet::set_paused`].
+pub struct EnsureBrokerOrRoot<T>(PhantomData<T>);
+impl<T: Config> EnsureOrigin<<T as frame_system::Config>::RuntimeOrigin> for EnsureBrokerOrRoot<T> {
+	type Success = ();
+
+	fn try_origin(
+		o: <T as frame_system::Config>::RuntimeOrigin,
+	) -> result::Result<Self::Success, <T as frame_system::Config>::RuntimeOrigin> {
+		match ensure_parachain(<T as Config>::RuntimeOrigin::from(o.clone())) {
+			Ok(id) if id == ParaId::from(T::BrokerId::get()) => Ok(()),
+			Ok(_) => Err(o),
+			Err(_) => ensure_root(o.clone()).map_err(|_| o),
+		}
+	}
+
+	#[cfg(feature = "runtime-benchmarks")]
+	fn try_successful_origin() -> result::Result<<T as frame_system::Config>::RuntimeOrigin, ()> {
+		Ok(frame_system::RawOrigin::Root.into())
+	}
 }
 
 /// Broker pallet index on the coretime chain. Used to
@@ -85,6 +191,8 @@ enum CoretimeCalls {
 	SetLease(pallet_broker::TaskId, pallet_broker::Timeslice),
 	#[codec(index = 19)]
 	NotifyCoreCount(u16),
+	#[codec(index = 20)]
+	NotifyRevenue((BlockNumber, Balance)),
 }
 
 #[frame_support::pallet]
@@ -93,7 +201,11 @@ pub mod pallet {
 
 	use super::*;
 
+	/// The in-code storage version.
+	const STORAGE_VERSION: StorageVersion = StorageVersion::new(1);
+
 	#[pallet::pallet]
+	#[pallet::storage_version(STORAGE_VERSION)]
 	#[pallet::without_storage_info]
 	pub struct Pallet<T>(_);
 
@@ -103,32 +215,131 @@ pub mod pallet {
 			+ Into<result::Result<Origin, <Self as Config>::RuntimeOrigin>>;
 		type RuntimeEvent: From<Event<Self>> + IsType<<Self as frame_system::Config>::RuntimeEvent>;
 		/// The runtime's definition of a Currency.
-		type Currency: Currency<Self::AccountId>;
+		type Currency: Mutate<Self::AccountId>;
+		/// The maximum amount the broker chain may credit to an account in a single block.
+		#[pallet::constant]
+		type MaxCreditPerBlock: Get<BalanceOf<Self>>;
+		/// The maximum number of assignments accepted by a single `assign_cores_batch` call.
+		#[pallet::constant]
+		type MaxCoreAssignmentsPerBatch: Get<u32>;
 		/// The ParaId of the broker system parachain.
 		#[pallet::constant]
 		type BrokerId: Get<u32>;
 		/// Something that provides the weight of this pallet.
 		type WeightInfo: WeightInfo;
+		/// The XCM sender used to reach the broker parachain.
 		type SendXcm: SendXcm;
+		/// Whether to notify parachains with a [`CoreAssignmentNotification`] when a core is
+		/// assigned to them.
+		#[pallet::constant]
+		type NotifyAssignedParas: Get<bool>;
+		/// The encoded call carrying a [`CoreAssignmentNotification`] on the parachains, together
+		/// with the weight it may use there, e.g. [`NotificationCallAt`].
+		///
+		/// Only used if [`Config::NotifyAssignedParas`] is set.
+		type NotificationCall: Convert<
+			CoreAssignmentNotification<BlockNumberFor<Self>>,
+			(Vec<u8>, Weight),
+		>;
+		/// The origin allowed to pause and unpause the calls of the broker chain.
+		///
+		/// Can be faster to act than root, e.g. a technical committee, to stop a misbehaving broker
+		/// chain in an emergency.
+		type PauseOrigin: EnsureOrigin<<Self as frame_system::Config>::RuntimeOrigin>;
 	}
 
 	#[pallet::event]
 	#[pallet::generate_deposit(pub(super) fn deposit_event)]
 	pub enum Event<T: Config> {
-		/// The broker chain has asked for revenue information for a specific block.
+		/// The broker chain has asked for revenue information for a specific block, which has been
+		/// sent back to it.
 		RevenueInfoRequested { when: BlockNumberFor<T> },
 		/// A core has received a new assignment from the broker chain.
 		CoreAssigned { core: CoreIndex },
+		/// Revenue reported by the broker chain has been credited to an account.
+		AccountCredited { who: T::AccountId, amount: BalanceOf<T> },
+		/// The last assignment of a core has reached its `end_hint`.
+		CoreFreed { core: CoreIndex },
+		/// All assignments of a core have been dropped by root.
+		CoreForciblyCleared { core: CoreIndex },
+		/// The calls of the broker chain have been paused or unpaused.
+		PausedSet { paused: bool },
+		/// Core assignments by the broker chain have been frozen or unfrozen.
+		AssignmentsFreezeChanged { frozen: bool },
+		/// All cores serving `old` have been reassigned to `new`.
+		ParaCoresMigrated { old: ParaId, new: ParaId, count: u32 },
+		/// The broker core index `broker` now refers to the relay chain core `relay`.
+		CoreRemapped { broker: BrokerCoreIndex, relay: CoreIndex },
 	}
 
 	#[pallet::error]
 	pub enum Error<T> {
 		/// The paraid making the call is not the coretime brokerage system parachain.
 		NotBroker,
+		/// The credit exceeds `MaxCreditPerBlock`.
+		CreditCapExceeded,
+		/// The credit would overflow the account balance or the total issuance.
+		CreditOverflow,
+		/// The account is frozen or otherwise blocked from receiving funds.
+		CreditAccountFrozen,
+		/// The credit is too small to create the account.
+		CreditBelowMinimum,
+		/// The `end_hint` of an assignment is not after its `begin`.
+		EndHintBeforeBegin,
+		/// Depositing the credit into the account failed.
+		CreditFailed,
+		/// Revenue information was requested for a block in the future.
+		RequestedFutureRevenue,
+		/// Sending a message to the broker chain failed.
+		XcmSendFailed,
+		/// The calls of the broker chain are paused, see [`Pallet::set_paused`].
+		Paused,
+		/// Core assignments are frozen, see [`Pallet::set_assignments_frozen`].
+		AssignmentsFrozen,
+		/// The parts of an assignment do not sum up to exactly one full core.
+		IncompleteAssignment,
+		/// The broker core index does not refer to any relay chain core, see
+		/// [`Pallet::broker_to_relay_core`].
+		CoreNotMapped,
 	}
 
+	/// Total amount credited to accounts in the current block.
+	#[pallet::storage]
+	pub(super) type CreditedInBlock<T: Config> = StorageValue<_, BalanceOf<T>, ValueQuery>;
+
+	/// The `end_hint` of the most recent assignment of each core, if it has one.
+	///
+	/// Entries are removed once the end is reached, see [`Event::CoreFreed`].
+	#[pallet::storage]
+	pub(super) type LastAssignment<T: Config> =
+		StorageMap<_, Twox64Concat, CoreIndex, BlockNumberFor<T>>;
+
+	/// Whether the calls of the broker chain are currently rejected.
+	#[pallet::storage]
+	pub type Paused<T: Config> = StorageValue<_, bool, ValueQuery>;
+
+	/// Whether core assignments are currently only accepted from root.
+	#[pallet::storage]
+	pub type AssignmentsFrozen<T: Config> = StorageValue<_, bool, ValueQuery>;
+
+	/// Broker core indices referring to another relay chain core than the one of the same index,
+	/// see [`Pallet::remap_core`].
+	#[pallet::storage]
+	pub type BrokerToRelayCore<T: Config> = StorageMap<_, Twox64Concat, BrokerCoreIndex, CoreIndex>;
+
+	/// The inverse of [`BrokerToRelayCore`].
+	#[pallet::storage]
+	pub type RelayToBrokerCore<T: Config> = StorageMap<_, Twox64Concat, CoreIndex, BrokerCoreIndex>;
+
 	#[pallet::hooks]
-	impl<T: Config> Hooks<BlockNumberFor<T>> for Pallet<T> {}
+	impl<T: Config> Hooks<BlockNumberFor<T>> for Pallet<T> {
+		fn on_initialize(n: BlockNumberFor<T>) -> Weight {
+			CreditedInBlock::<T>::kill();
+			let (reads, writes) = Self::free_expired_cores(n);
+
+			T::DbWeight::get().reads_writes(reads, writes.saturating_add(1))
+		}
+	}
 
 	#[pallet::call]
 	impl<T: Config> Pallet<T> {
@@ -136,35 +347,70 @@ pub mod pallet {
 		#[pallet::call_index(1)]
 		pub fn request_core_count(origin: OriginFor<T>, count: u16) -> DispatchResult {
 			// Ignore requests not coming from the broker parachain or root.
-			Self::ensure_root_or_para(origin, <T as Config>::BrokerId::get().into())?;
+			Self::ensure_broker(origin)?;
 
 			configuration::Pallet::<T>::set_coretime_cores_unchecked(u32::from(count))
 		}
 
-		//// TODO Impl me!
-		////#[pallet::weight(<T as Config>::WeightInfo::request_revenue_info_at())]
-		//#[pallet::call_index(2)]
-		//pub fn request_revenue_info_at(
-		//	origin: OriginFor<T>,
-		//	_when: BlockNumberFor<T>,
-		//) -> DispatchResult {
-		//	// Ignore requests not coming from the broker parachain or root.
-		//	Self::ensure_root_or_para(origin, <T as Config>::BrokerId::get().into())?;
-		//	Ok(())
-		//}
-
-		//// TODO Impl me!
-		////#[pallet::weight(<T as Config>::WeightInfo::credit_account())]
-		//#[pallet::call_index(3)]
-		//pub fn credit_account(
-		//	origin: OriginFor<T>,
-		//	_who: T::AccountId,
-		//	_amount: BalanceOf<T>,
-		//) -> DispatchResult {
-		//	// Ignore requests not coming from the broker parachain or root.
-		//	Self::ensure_root_or_para(origin, <T as Config>::BrokerId::get().into())?;
-		//	Ok(())
-		//}
+		/// Report the on demand revenue of all blocks before `when` to the broker chain.
+		///
+		/// The revenue is claimed, i.e. it will not be reported again by later requests, and sent
+		/// to the broker chain in a `notify_revenue` call.
+		///
+		/// Parameters:
+		/// -`origin`: The broker system parachain or root.
+		/// -`when`: The block until which revenue is reported. Must not be in the future.
+		#[pallet::weight(<T as Config>::WeightInfo::request_revenue_info_at())]
+		#[pallet::call_index(2)]
+		pub fn request_revenue_info_at(
+			origin: OriginFor<T>,
+			when: BlockNumberFor<T>,
+		) -> DispatchResult {
+			// Ignore requests not coming from the broker parachain or root.
+			Self::ensure_broker(origin)?;
+			ensure!(
+				when <= frame_system::Pallet::<T>::block_number(),
+				Error::<T>::RequestedFutureRevenue
+			);
+
+			let amount = <assigner_on_demand::Pallet<T>>::claim_revenue_until(when);
+			Self::send_to_broker(CoretimeCalls::NotifyRevenue((
+				when.saturated_into(),
+				amount.saturated_into(),
+			)))
+			.map_err(|err| {
+				log::error!("Sending `NotifyRevenue` to coretime chain failed: {:?}", err);
+				Error::<T>::XcmSendFailed
+			})?;
+
+			Self::deposit_event(Event::<T>::RevenueInfoRequested { when });
+			Ok(())
+		}
+
+		/// Credit revenue reported by the broker chain to an account on the relay chain.
+		///
+		/// Parameters:
+		/// -`origin`: The broker system parachain or root.
+		/// -`who`: The account to credit.
+		/// -`amount`: The amount minted into `who`, at most `MaxCreditPerBlock` across all
+		///  credits of a block.
+		#[pallet::weight(<T as Config>::WeightInfo::credit_account())]
+		#[pallet::call_index(3)]
+		pub fn credit_account(
+			origin: OriginFor<T>,
+			who: T::AccountId,
+			amount: BalanceOf<T>,
+		) -> DispatchResult {
+			// Ignore requests not coming from the broker parachain or root.
+			Self::ensure_broker(origin)?;
+			Self::can_credit(who.clone(), amount)?;
+
+			T::Currency::mint_into(&who, amount).map_err(|_| Error::<T>::CreditFailed)?;
+			CreditedInBlock::<T>::mutate(|credited| *credited = credited.saturating_add(amount));
+
+			Self::deposit_event(Event::<T>::AccountCredited { who, amount });
+			Ok(())
+		}
 
 		/// Receive instructions from the `ExternalBrokerOrigin`, detailing how a specific core is
 		/// to be used.
@@ -175,10 +421,11 @@ pub mod pallet {
 		/// -`begin`: The starting blockheight of the instruction.
 		/// -`assignment`: How the blockspace should be utilised.
 		/// -`end_hint`: An optional hint as to when this particular set of instructions will end.
+		///  Must be strictly greater than `begin` if given.
 		// The broker pallet's `CoreIndex` definition is `u16` but on the relay chain it's `struct
 		// CoreIndex(u32)`
 		#[pallet::call_index(4)]
-		#[pallet::weight(<T as Config>::WeightInfo::assign_core(assignment.len() as u32))]
+		#[pallet::weight(Pallet::<T>::assign_core_weight(assignment.len() as u32))]
 		pub fn assign_core(
 			origin: OriginFor<T>,
 			core: BrokerCoreIndex,
@@ -187,53 +434,431 @@ pub mod pallet {
 			end_hint: Option<BlockNumberFor<T>>,
 		) -> DispatchResult {
 			// Ignore requests not coming from the broker parachain or root.
-			Self::ensure_root_or_para(origin, T::BrokerId::get().into())?;
+			Self::ensure_can_assign(origin)?;
+
+			Self::do_assign_core(core, begin, assignment, end_hint, None)
+		}
+
+		/// Like [`Pallet::assign_core`], but for several cores at once.
+		///
+		/// The assignments are applied in order. If any of them fails, the whole batch is
+		/// reverted and the error returned.
+		///
+		/// Parameters:
+		/// -`origin`: The `ExternalBrokerOrigin`, assumed to be the Broker system parachain.
+		/// -`assignments`: At most `MaxCoreAssignmentsPerBatch` tuples of `core`, `begin`,
+		///  `assignment` and `end_hint`, as taken by [`Pallet::assign_core`].
+		#[pallet::call_index(5)]
+		#[pallet::weight(assignments.iter().fold(Weight::zero(), |weight, (_, _, assignment, _)| {
+			weight.saturating_add(Pallet::<T>::assign_core_weight(assignment.len() as u32))
+		}))]
+		pub fn assign_cores_batch(
+			origin: OriginFor<T>,
+			assignments: BoundedVec<
+				(
+					BrokerCoreIndex,
+					BlockNumberFor<T>,
+					Vec<(CoreAssignment, PartsOf57600)>,
+					Option<BlockNumberFor<T>>,
+				),
+				T::MaxCoreAssignmentsPerBatch,
+			>,
+		) -> DispatchResult {
+			// Ignore requests not coming from the broker parachain or root.
+			Self::ensure_can_assign(origin)?;
+
+			for (core, begin, assignment, end_hint) in assignments {
+				Self::do_assign_core(core, begin, assignment, end_hint, None)?;
+			}
+			Ok(())
+		}
+
+		/// Immediately drop the current and all scheduled assignments of a core.
+		///
+		/// Meant for emergencies, e.g. a core serving a compromised parachain. Unlike
+		/// [`Pallet::assign_core`] this is neither authorized by the broker chain nor aligned to
+		/// its schedule. No [`Event::CoreFreed`] is emitted for the dropped assignments.
+		///
+		/// Parameters:
+		/// -`origin`: Root.
+		/// -`core`: The core to clear.
+		#[pallet::call_index(6)]
+		#[pallet::weight(<T as Config>::WeightInfo::force_clear_core())]
+		pub fn force_clear_core(origin: OriginFor<T>, core: BrokerCoreIndex) -> DispatchResult {
+			ensure_root(origin)?;
+
+			let core = Self::broker_to_relay_core(core).ok_or(Error::<T>::CoreNotMapped)?;
+			<assigner_coretime::Pallet<T>>::clear_core(core);
+			LastAssignment::<T>::remove(core);
+
+			Self::deposit_event(Event::<T>::CoreForciblyCleared { core });
+			Ok(())
+		}
+
+		/// Pause or unpause the calls of the broker chain.
+		///
+		/// While paused, all calls that the broker chain may make are rejected with
+		/// [`Error::Paused`], including when made by root. The root-only `force_*` calls are not
+		/// affected.
+		///
+		/// Parameters:
+		/// -`origin`: The `PauseOrigin`.
+		/// -`paused`: Whether the broker chain calls should be rejected.
+		#[pallet::call_index(7)]
+		#[pallet::weight(<T as Config>::WeightInfo::set_paused())]
+		pub fn set_paused(origin: OriginFor<T>, paused: bool) -> DispatchResult {
+			T::PauseOrigin::ensure_origin(origin)?;
+
+			Paused::<T>::put(paused);
+
+			Self::deposit_event(Event::<T>::PausedSet { paused });
+			Ok(())
+		}
+
+		/// Freeze or unfreeze core assignments, e.g. for the duration of a maintenance window.
+		///
+		/// While frozen, [`Pallet::assign_core`] and [`Pallet::assign_cores_batch`] are rejected
+		/// with [`Error::AssignmentsFrozen`] unless made by root.
+		///
+		/// Parameters:
+		/// -`origin`: Root.
+		/// -`frozen`: Whether assignments by the broker chain should be rejected.
+		#[pallet::call_index(8)]
+		#[pallet::weight(<T as Config>::WeightInfo::set_assignments_frozen())]
+		pub fn set_assignments_frozen(origin: OriginFor<T>, frozen: bool) -> DispatchResult {
+			ensure_root(origin)?;
+
+			AssignmentsFrozen::<T>::put(frozen);
+
+			Self::deposit_event(Event::<T>::AssignmentsFreezeChanged { frozen });
+			Ok(())
+		}
+
+		/// Reassign all cores currently serving `old` to `new`, e.g. when a parachain moves to a
+		/// new para id.
+		///
+		/// Each such core gets a new assignment starting at `begin`, in which the share of `old`
+		/// goes to `new`. Its other assignments and its end are kept. Cores whose last assignment
+		/// ends at or before `begin` are skipped. If any of the cores cannot be reassigned, none
+		/// is and the error is returned.
+		///
+		/// Only the assignment serving the core right now is migrated: assignments queued after
+		/// it keep serving `old`. As assignments can only be appended, a core with an assignment
+		/// queued at or after `begin` cannot be reassigned.
+		///
+		/// Of the paras sharing a reassigned core, only `new` is notified, see
+		/// [`Config::NotifyAssignedParas`].
+		///
+		/// Parameters:
+		/// -`origin`: The `ExternalBrokerOrigin`, assumed to be the Broker system parachain.
+		/// -`old`: The para whose cores are reassigned.
+		/// -`new`: The para taking over the cores.
+		/// -`begin`: The block from which `new` is served.
+		#[pallet::call_index(9)]
+		#[pallet::weight({
+			let cores = configuration::ActiveConfig::<T>::get().coretime_cores;
+			<T as Config>::WeightInfo::migrate_para_cores(cores)
+				.saturating_add(Pallet::<T>::notification_weight(cores))
+		})]
+		pub fn migrate_para_cores(
+			origin: OriginFor<T>,
+			old: ParaId,
+			new: ParaId,
+			begin: BlockNumberFor<T>,
+		) -> DispatchResult {
+			// Ignore requests not coming from the broker parachain or root.
+			Self::ensure_can_assign(origin)?;
 
-			let core = u32::from(core).into();
+			let now = frame_system::Pallet::<T>::block_number();
+			let (old_task, new_task) =
+				(CoreAssignment::Task(old.into()), CoreAssignment::Task(new.into()));
+			// Cores beyond the range of the broker's core index cannot have been assigned by it.
+			let core_count =
+				BrokerCoreIndex::try_from(configuration::ActiveConfig::<T>::get().coretime_cores)
+					.unwrap_or(BrokerCoreIndex::MAX);
+
+			let mut count = 0;
+			for core in 0..core_count {
+				let Some(core_index) = Self::broker_to_relay_core(core) else { continue };
+				// The core stops serving `old` before `new` would take over.
+				let end_hint = LastAssignment::<T>::get(core_index);
+				if end_hint.map_or(false, |end| end <= begin) {
+					continue
+				}
+				let mut assignment =
+					assigner_coretime::Pallet::<T>::current_assignments(now, core_index);
+				let Some(position) = assignment.iter().position(|(task, _)| *task == old_task)
+				else {
+					continue
+				};
+				let (_, old_parts) = assignment.remove(position);
+				match assignment.iter_mut().find(|(task, _)| *task == new_task) {
+					Some((_, parts)) => *parts = parts.saturating_add(old_parts),
+					None => assignment.push((new_task.clone(), old_parts)),
+				}
+				assignment.sort_by(|(a, _), (b, _)| a.cmp(b));
+
+				Self::do_assign_core(core, begin, assignment, end_hint, Some(new.into()))?;
+				count += 1;
+			}
 
-			<assigner_coretime::Pallet<T>>::assign_core(core, begin, assignment, end_hint)?;
-			Self::deposit_event(Event::<T>::CoreAssigned { core });
+			Self::deposit_event(Event::<T>::ParaCoresMigrated { old, new, count });
+			Ok(())
+		}
+
+		/// Make the broker core index `broker` refer to the relay chain core `relay`.
+		///
+		/// Both lose their previous mapping: the broker core index that used to refer to `relay`
+		/// no longer refers to any core until remapped itself, and neither does the relay chain
+		/// core `broker` used to refer to. Mapping `broker` to the relay chain core of the same
+		/// index restores the default. Assignments already made are not moved.
+		///
+		/// Parameters:
+		/// -`origin`: Root.
+		/// -`broker`: The broker core index to remap.
+		/// -`relay`: The relay chain core it refers to from now on.
+		#[pallet::call_index(10)]
+		#[pallet::weight(<T as Config>::WeightInfo::remap_core())]
+		pub fn remap_core(
+			origin: OriginFor<T>,
+			broker: BrokerCoreIndex,
+			relay: CoreIndex,
+		) -> DispatchResult {
+			ensure_root(origin)?;
+
+			if let Some(previous) = BrokerToRelayCore::<T>::take(broker) {
+				RelayToBrokerCore::<T>::remove(previous);
+			}
+			if let Some(previous) = RelayToBrokerCore::<T>::take(relay) {
+				BrokerToRelayCore::<T>::remove(previous);
+			}
+			if relay != CoreIndex(broker.into()) {
+				BrokerToRelayCore::<T>::insert(broker, relay);
+				RelayToBrokerCore::<T>::insert(relay, broker);
+			}
+
+			Self::deposit_event(Event::<T>::CoreRemapped { broker, relay });
 			Ok(())
 		}
 	}
 }
 
 impl<T: Config> Pallet<T> {
-	/// Ensure the origin is one of Root or the `para` itself.
-	fn ensure_root_or_para(
-		origin: <T as frame_system::Config>::RuntimeOrigin,
-		id: ParaId,
-	) -> DispatchResult {
-		if let Ok(caller_id) = ensure_parachain(<T as Config>::RuntimeOrigin::from(origin.clone()))
-		{
-			// Check if matching para id...
-			ensure!(caller_id == id, Error::<T>::NotBroker);
+	/// The relay chain core the broker core index `broker` refers to.
+	///
+	/// This is the core of the same index, unless remapped with [`Pallet::remap_core`]. Returns
+	/// `None` if that core has been taken over by another broker core index.
+	pub fn broker_to_relay_core(broker: BrokerCoreIndex) -> Option<CoreIndex> {
+		if let Some(relay) = BrokerToRelayCore::<T>::get(broker) {
+			return Some(relay)
+		}
+		let relay = CoreIndex(broker.into());
+		(!RelayToBrokerCore::<T>::contains_key(relay)).then_some(relay)
+	}
+
+	/// The broker core index referring to the relay chain core `relay`, the inverse of
+	/// [`Self::broker_to_relay_core`].
+	pub fn relay_to_broker_core(relay: CoreIndex) -> Option<BrokerCoreIndex> {
+		if let Some(broker) = RelayToBrokerCore::<T>::get(relay) {
+			return Some(broker)
+		}
+		let broker = BrokerCoreIndex::try_from(relay.0).ok()?;
+		(!BrokerToRelayCore::<T>::contains_key(broker)).then_some(broker)
+	}
+
+	/// Ensure the origin is one of Root or the broker parachain, and its calls are not paused.
+	fn ensure_broker(origin: <T as frame_system::Config>::RuntimeOrigin) -> DispatchResult {
+		EnsureBrokerOrRoot::<T>::try_origin(origin).map_err(|origin| {
+			// Tell other parachains apart from origins that are no parachain at all.
+			match ensure_parachain(<T as Config>::RuntimeOrigin::from(origin)) {
+				Ok(_) => Error::<T>::NotBroker.into(),
+				Err(_) => DispatchError::BadOrigin,
+			}
+		})?;
+		ensure!(!Paused::<T>::get(), Error::<T>::Paused);
+		Ok(())
+	}
+
+	/// Like [`Self::ensure_broker`], but also reject the broker parachain while assignments are
+	/// frozen.
+	fn ensure_can_assign(origin: <T as frame_system::Config>::RuntimeOrigin) -> DispatchResult {
+		let is_root = ensure_root(origin.clone()).is_ok();
+		Self::ensure_broker(origin)?;
+		ensure!(is_root || !AssignmentsFrozen::<T>::get(), Error::<T>::AssignmentsFrozen);
+		Ok(())
+	}
+
+	/// Weight of notifying `paras` paras of their assignment, zero unless
+	/// [`Config::NotifyAssignedParas`] is set.
+	fn notification_weight(paras: u32) -> Weight {
+		if T::NotifyAssignedParas::get() {
+			<T as Config>::WeightInfo::notify_para().saturating_mul(paras.into())
 		} else {
-			// Check if root...
-			ensure_root(origin.clone())?;
+			Weight::zero()
 		}
+	}
+
+	/// Weight of [`Pallet::assign_core`] with `s` assignments, including the notifications.
+	fn assign_core_weight(s: u32) -> Weight {
+		<T as Config>::WeightInfo::assign_core(s).saturating_add(Self::notification_weight(s))
+	}
+
+	/// Forward an assignment of `core` to the assigner and record its end.
+	///
+	/// The paras of `assignment` are notified of it, or only `notified` if given.
+	fn do_assign_core(
+		core: BrokerCoreIndex,
+		begin: BlockNumberFor<T>,
+		assignment: Vec<(CoreAssignment, PartsOf57600)>,
+		end_hint: Option<BlockNumberFor<T>>,
+		notified: Option<u32>,
+	) -> DispatchResult {
+		ensure!(end_hint.map_or(true, |end| end > begin), Error::<T>::EndHintBeforeBegin);
+		let parts = assignment
+			.iter()
+			.try_fold(PartsOf57600::ZERO, |sum, (_, parts)| sum.checked_add(*parts));
+		ensure!(parts.map_or(false, |parts| parts.is_full()), Error::<T>::IncompleteAssignment);
+
+		let core = Self::broker_to_relay_core(core).ok_or(Error::<T>::CoreNotMapped)?;
+		let paras: BTreeSet<_> = match notified {
+			Some(para) => [para].into_iter().collect(),
+			None => assignment
+				.iter()
+				.filter_map(|(assignment, _)| match assignment {
+					CoreAssignment::Task(para) => Some(*para),
+					CoreAssignment::Pool | CoreAssignment::Idle => None,
+				})
+				.collect(),
+		};
+
+		<assigner_coretime::Pallet<T>>::assign_core(core, begin, assignment, end_hint)?;
+		match end_hint {
+			Some(end) => LastAssignment::<T>::insert(core, end),
+			None => LastAssignment::<T>::remove(core),
+		}
+		if T::NotifyAssignedParas::get() {
+			let notification = CoreAssignmentNotification { core, begin, end_hint };
+			for para in paras {
+				Self::notify_para(para, &notification);
+			}
+		}
+		Self::deposit_event(Event::<T>::CoreAssigned { core });
 		Ok(())
 	}
 
+	/// Send `notification` to `para`.
+	///
+	/// Failing to send does not affect the assignment, the parachain only misses the notice.
+	fn notify_para(para: u32, notification: &CoreAssignmentNotification<BlockNumberFor<T>>) {
+		let (call, require_weight_at_most) = T::NotificationCall::convert(notification.clone());
+		let message = Xcm(vec![Instruction::Transact {
+			origin_kind: OriginKind::Xcm,
+			require_weight_at_most,
+			call: call.into(),
+		}]);
+		let dest = MultiLocation { parents: 0, interior: Junctions::X1(Junction::Parachain(para)) };
+		if let Err(err) = send_xcm::<T::SendXcm>(dest, message) {
+			log::error!("Notifying para {para} of its core assignment failed: {:?}", err);
+		}
+	}
+
+	/// Check whether `amount` can be credited to `who` without changing any state.
+	///
+	/// Runs the same checks that `credit_account` does: the per-block cap, balance and issuance
+	/// overflow, and whether the account is able to receive funds at all.
+	pub fn can_credit(who: T::AccountId, amount: BalanceOf<T>) -> Result<(), Error<T>> {
+		let credited = CreditedInBlock::<T>::get().saturating_add(amount);
+		ensure!(credited <= T::MaxCreditPerBlock::get(), Error::<T>::CreditCapExceeded);
+
+		match T::Currency::can_deposit(&who, amount, Provenance::Minted) {
+			DepositConsequence::Success => Ok(()),
+			DepositConsequence::Overflow => Err(Error::<T>::CreditOverflow),
+			DepositConsequence::Blocked => Err(Error::<T>::CreditAccountFrozen),
+			DepositConsequence::BelowMinimum |
+			DepositConsequence::CannotCreate |
+			DepositConsequence::UnknownAsset => Err(Error::<T>::CreditBelowMinimum),
+		}
+	}
+
+	/// Summarize the cores of the active configuration by the kind of assignment they serve.
+	///
+	/// A core split between several assignments is classified by the kind holding the largest
+	/// share of it, where the shares of all tasks are added up. Cores with a tie for the largest
+	/// share are counted as `other`, cores without any assignment are counted as `idle`.
+	pub fn core_summary() -> CoreSummary {
+		let now = frame_system::Pallet::<T>::block_number();
+		let core_count = configuration::ActiveConfig::<T>::get().coretime_cores;
+
+		let mut summary = CoreSummary::default();
+		for core in 0..core_count {
+			let (mut task, mut pool, mut idle) =
+				(PartsOf57600::ZERO, PartsOf57600::ZERO, PartsOf57600::ZERO);
+			for (assignment, parts) in
+				assigner_coretime::Pallet::<T>::current_assignments(now, CoreIndex(core))
+			{
+				let share = match assignment {
+					CoreAssignment::Task(_) => &mut task,
+					CoreAssignment::Pool => &mut pool,
+					CoreAssignment::Idle => &mut idle,
+				};
+				*share = share.saturating_add(parts);
+			}
+
+			let kind = if task > pool.max(idle) {
+				&mut summary.task
+			} else if pool > task.max(idle) {
+				&mut summary.pool
+			} else if idle > task.max(pool) || (task == PartsOf57600::ZERO && pool == task) {
+				&mut summary.idle
+			} else {
+				&mut summary.other
+			};
+			*kind = kind.saturating_add(1);
+		}
+
+		summary
+	}
+
+	/// Emit [`Event::CoreFreed`] for all cores whose last assignment ended at or before `now`.
+	///
+	/// Returns the number of storage reads and writes performed.
+	fn free_expired_cores(now: BlockNumberFor<T>) -> (u64, u64) {
+		let mut reads = 0u64;
+		let expired: Vec<CoreIndex> = LastAssignment::<T>::iter()
+			.inspect(|_| reads += 1)
+			.filter_map(|(core, end)| (end <= now).then_some(core))
+			.collect();
+
+		for core in &expired {
+			LastAssignment::<T>::remove(core);
+			Self::deposit_event(Event::<T>::CoreFreed { core: *core });
+		}
+
+		(reads, expired.len() as u64)
+	}
+
 	pub fn initializer_on_new_session(notification: &SessionChangeNotification<BlockNumberFor<T>>) {
 		let old_core_count = notification.prev_config.coretime_cores;
 		let new_core_count = notification.new_config.coretime_cores;
 		if new_core_count != old_core_count {
 			let core_count: u16 = new_core_count.saturated_into();
-			let message = Xcm(vec![mk_coretime_call(
-				crate::coretime::CoretimeCalls::NotifyCoreCount(core_count),
-			)]);
-			if let Err(err) = send_xcm::<T::SendXcm>(
-				MultiLocation {
-					parents: 0,
-					interior: Junctions::X1(Junction::Parachain(T::BrokerId::get())),
-				},
-				message,
-			) {
+			if let Err(err) = Self::send_to_broker(CoretimeCalls::NotifyCoreCount(core_count)) {
 				log::error!("Sending `NotifyCoreCount` to coretime chain failed: {:?}", err);
 			}
 		}
 	}
+
+	/// Send `call` to the broker pallet of the coretime chain.
+	fn send_to_broker(call: CoretimeCalls) -> Result<(), SendError> {
+		send_xcm::<T::SendXcm>(
+			MultiLocation {
+				parents: 0,
+				interior: Junctions::X1(Junction::Parachain(T::BrokerId::get())),
+			},
+			Xcm(vec![mk_coretime_call(call)]),
+		)
+		.map(|_| ())
+	}
 }
 
 impl<T: Config> OnNewSession<BlockNumberFor<T>> for Pallet<T> {
@@ -249,3 +874,17 @@ fn mk_coretime_call(call: crate::coretime::CoretimeCalls) -> Instruction<()> {
 		call: BrokerRuntimePallets::Broker(call).encode().into(),
 	}
 }
+
+sp_api::decl_runtime_apis! {
+	/// Read-only queries against the relay chain side of the Coretime interface.
+	pub trait CoretimeApi<AccountId, Balance>
+	where
+		AccountId: Codec,
+		Balance: Codec,
+	{
+		/// Check whether `amount` can be credited to `who` by [`Pallet::credit_account`].
+		///
+		/// Returns the error `credit_account` would fail with, without changing any state.
+		fn can_credit(who: AccountId, amount: Balance) -> Result<(), DispatchError>;
+	}
+}
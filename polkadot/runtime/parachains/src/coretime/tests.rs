@@ -0,0 +1,848 @@
+// Copyright (C) Parity Technologies (UK) Ltd.
+// This file is part of Polkadot.
+
+// Polkadot is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Polkadot is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Polkadot.  If not, see <http://www.gnu.org/licenses/>.
+
+use super::*;
+
+use crate::{
+	mock::{
+		assert_last_event, new_test_ext, Balances, BrokerId, Coretime, CoretimeAssigner,
+		CoretimePauser, MaxCreditPerBlock, NotificationCallIndex, NotificationCallWeight,
+		NotificationPalletIndex, NotifyAssignedParas, OnDemandAssigner, RuntimeEvent,
+		RuntimeOrigin, SentXcm, System, Test,
+	},
+	scheduler::common::{Assignment, AssignmentProvider},
+};
+use frame_support::{assert_noop, assert_ok};
+use primitives::Balance;
+use sp_runtime::DispatchError;
+
+fn para_origin(id: u32) -> RuntimeOrigin {
+	Origin::Parachain(ParaId::from(id)).into()
+}
+
+#[test]
+fn parachain_origin_converts_to_coretime_origin() {
+	new_test_ext(Default::default()).execute_with(|| {
+		assert_eq!(
+			ensure_parachain(para_origin(BrokerId::get())),
+			Ok(ParaId::from(BrokerId::get()))
+		);
+		assert_eq!(ensure_parachain(para_origin(7)), Ok(ParaId::from(7)));
+		assert!(ensure_parachain(RuntimeOrigin::root()).is_err());
+		assert!(ensure_parachain(RuntimeOrigin::signed(1)).is_err());
+	});
+}
+
+#[test]
+fn broker_and_root_are_accepted() {
+	new_test_ext(Default::default()).execute_with(|| {
+		assert_ok!(EnsureBrokerOrRoot::<Test>::try_origin(para_origin(BrokerId::get())));
+		assert_ok!(EnsureBrokerOrRoot::<Test>::try_origin(RuntimeOrigin::root()));
+		assert_ok!(Coretime::ensure_broker(para_origin(BrokerId::get())));
+		assert_ok!(Coretime::ensure_broker(RuntimeOrigin::root()));
+	});
+}
+
+#[test]
+fn non_broker_origins_are_rejected() {
+	new_test_ext(Default::default()).execute_with(|| {
+		// The rejected origin is handed back unchanged.
+		let rejected = EnsureBrokerOrRoot::<Test>::try_origin(para_origin(BrokerId::get() + 1));
+		assert_eq!(rejected.map_err(ensure_parachain), Err(Ok(ParaId::from(BrokerId::get() + 1))));
+		assert!(EnsureBrokerOrRoot::<Test>::try_origin(RuntimeOrigin::signed(1)).is_err());
+
+		assert_noop!(
+			Coretime::ensure_broker(para_origin(BrokerId::get() + 1)),
+			Error::<Test>::NotBroker
+		);
+		assert_noop!(Coretime::ensure_broker(RuntimeOrigin::signed(1)), DispatchError::BadOrigin);
+	});
+}
+
+#[test]
+fn request_core_count_schedules_config_update() {
+	new_test_ext(Default::default()).execute_with(|| {
+		assert_ok!(Coretime::request_core_count(para_origin(BrokerId::get()), 3));
+		let pending = configuration::PendingConfigs::<Test>::get();
+		assert_eq!(pending.last().map(|(_, config)| config.coretime_cores), Some(3));
+
+		assert_ok!(Coretime::request_core_count(RuntimeOrigin::root(), 5));
+		let pending = configuration::PendingConfigs::<Test>::get();
+		assert_eq!(pending.last().map(|(_, config)| config.coretime_cores), Some(5));
+	});
+}
+
+#[test]
+fn request_core_count_rejects_non_broker_origins() {
+	new_test_ext(Default::default()).execute_with(|| {
+		assert_noop!(
+			Coretime::request_core_count(para_origin(BrokerId::get() + 1), 3),
+			Error::<Test>::NotBroker
+		);
+		assert_noop!(
+			Coretime::request_core_count(RuntimeOrigin::signed(1), 3),
+			DispatchError::BadOrigin
+		);
+		assert!(configuration::PendingConfigs::<Test>::get().is_empty());
+	});
+}
+
+#[test]
+fn assign_core_reaches_assigner() {
+	new_test_ext(Default::default()).execute_with(|| {
+		System::set_block_number(1);
+
+		assert_ok!(Coretime::assign_core(
+			para_origin(BrokerId::get()),
+			0,
+			2,
+			vec![(CoreAssignment::Task(1), PartsOf57600::FULL)],
+			None,
+		));
+		assert_last_event(Event::CoreAssigned { core: CoreIndex(0) }.into());
+		assert_eq!(CoretimeAssigner::pop_assignment_for_core(CoreIndex(0)), None);
+
+		System::set_block_number(2);
+		assert_eq!(
+			CoretimeAssigner::pop_assignment_for_core(CoreIndex(0)),
+			Some(Assignment::Bulk(1.into()))
+		);
+	});
+}
+
+#[test]
+fn assign_core_rejects_non_broker_origins() {
+	new_test_ext(Default::default()).execute_with(|| {
+		let assignment = vec![(CoreAssignment::Task(1), PartsOf57600::FULL)];
+
+		assert_noop!(
+			Coretime::assign_core(para_origin(BrokerId::get() + 1), 0, 2, assignment.clone(), None),
+			Error::<Test>::NotBroker
+		);
+		assert_noop!(
+			Coretime::assign_core(RuntimeOrigin::signed(1), 0, 2, assignment, None),
+			DispatchError::BadOrigin
+		);
+	});
+}
+
+#[test]
+fn can_credit_accepts_eligible_account() {
+	new_test_ext(Default::default()).execute_with(|| {
+		assert_ok!(Coretime::can_credit(1, 1_000));
+		assert_ok!(Coretime::can_credit(1, MaxCreditPerBlock::get()));
+		// Nothing was actually credited.
+		assert_eq!(Balances::balance(&1), 0);
+	});
+}
+
+#[test]
+fn can_credit_rejects_amount_above_cap() {
+	new_test_ext(Default::default()).execute_with(|| {
+		assert_noop!(
+			Coretime::can_credit(1, MaxCreditPerBlock::get() + 1),
+			Error::<Test>::CreditCapExceeded
+		);
+	});
+}
+
+#[test]
+fn can_credit_rejects_overflow() {
+	new_test_ext(Default::default()).execute_with(|| {
+		assert_ok!(<Balances as Mutate<_>>::set_balance(&1, Balance::MAX - 10));
+
+		assert_noop!(Coretime::can_credit(1, 100), Error::<Test>::CreditOverflow);
+		assert_noop!(Coretime::can_credit(2, 100), Error::<Test>::CreditOverflow);
+	});
+}
+
+fn assign_full_core(begin: u32, end_hint: Option<u32>) -> DispatchResult {
+	Coretime::assign_core(
+		RuntimeOrigin::root(),
+		0,
+		begin,
+		vec![(CoreAssignment::Pool, PartsOf57600::FULL)],
+		end_hint,
+	)
+}
+
+#[test]
+fn assign_core_rejects_end_hint_equal_to_begin() {
+	new_test_ext(Default::default()).execute_with(|| {
+		assert_noop!(assign_full_core(10, Some(10)), Error::<Test>::EndHintBeforeBegin);
+	});
+}
+
+#[test]
+fn assign_core_rejects_end_hint_before_begin() {
+	new_test_ext(Default::default()).execute_with(|| {
+		assert_noop!(assign_full_core(10, Some(9)), Error::<Test>::EndHintBeforeBegin);
+	});
+}
+
+#[test]
+fn assign_core_rejects_parts_not_summing_to_full_core() {
+	new_test_ext(Default::default()).execute_with(|| {
+		let under = vec![(CoreAssignment::Pool, PartsOf57600::new_saturating(57599))];
+		let over = vec![
+			(CoreAssignment::Pool, PartsOf57600::FULL),
+			(CoreAssignment::Task(1), PartsOf57600::new_saturating(1)),
+		];
+
+		for assignment in [under, over] {
+			assert_noop!(
+				Coretime::assign_core(RuntimeOrigin::root(), 0, 10, assignment, None),
+				Error::<Test>::IncompleteAssignment
+			);
+		}
+	});
+}
+
+#[test]
+fn assign_core_accepts_end_hint_after_begin() {
+	new_test_ext(Default::default()).execute_with(|| {
+		System::set_block_number(1);
+
+		assert_ok!(assign_full_core(10, Some(11)));
+		assert_last_event(RuntimeEvent::Coretime(Event::CoreAssigned { core: CoreIndex(0) }));
+
+		assert_ok!(assign_full_core(20, None));
+	});
+}
+
+#[test]
+fn assign_core_emits_no_event_when_assigner_fails() {
+	new_test_ext(Default::default()).execute_with(|| {
+		System::set_block_number(1);
+
+		assert_noop!(
+			Coretime::assign_core(RuntimeOrigin::root(), 0, 10, Vec::new(), None),
+			Error::<Test>::IncompleteAssignment
+		);
+		assert!(System::events().is_empty());
+
+		assert_ok!(assign_full_core(10, None));
+		System::reset_events();
+		// The assigner only appends, an earlier begin is rejected.
+		assert_noop!(assign_full_core(5, None), assigner_coretime::Error::<Test>::DisallowedInsert);
+		assert!(System::events().is_empty());
+	});
+}
+
+#[test]
+fn assigned_paras_are_notified_when_enabled() {
+	new_test_ext(Default::default()).execute_with(|| {
+		let half = PartsOf57600::new_saturating(57600 / 2);
+		let assign_shared_core = |begin| {
+			Coretime::assign_core(
+				RuntimeOrigin::root(),
+				1,
+				begin,
+				vec![(CoreAssignment::Task(5), half), (CoreAssignment::Pool, half)],
+				Some(begin + 10),
+			)
+		};
+
+		// Disabled by default.
+		assert_ok!(assign_shared_core(10));
+		assert!(SentXcm::take().is_empty());
+
+		NotifyAssignedParas::set(true);
+		// Pool assignments are not notified.
+		assert_ok!(assign_full_core(10, None));
+		assert!(SentXcm::take().is_empty());
+
+		assert_ok!(assign_shared_core(20));
+		let para = MultiLocation { parents: 0, interior: Junctions::X1(Junction::Parachain(5)) };
+		let notification =
+			CoreAssignmentNotification { core: CoreIndex(1), begin: 20, end_hint: Some(30) };
+		let call =
+			(NotificationPalletIndex::get(), NotificationCallIndex::get(), notification).encode();
+		assert_eq!(
+			SentXcm::take(),
+			vec![(
+				para,
+				Xcm(vec![Instruction::Transact {
+					origin_kind: OriginKind::Xcm,
+					require_weight_at_most: NotificationCallWeight::get(),
+					call: call.into(),
+				}])
+			)]
+		);
+	});
+}
+
+#[test]
+fn assign_cores_batch_assigns_all_cores() {
+	new_test_ext(Default::default()).execute_with(|| {
+		System::set_block_number(1);
+		let full_pool = vec![(CoreAssignment::Pool, PartsOf57600::FULL)];
+
+		assert_ok!(Coretime::assign_cores_batch(
+			RuntimeOrigin::root(),
+			BoundedVec::truncate_from(vec![
+				(0, 10, full_pool.clone(), None),
+				(1, 10, full_pool, Some(20))
+			]),
+		));
+
+		let assigned: Vec<_> = System::events()
+			.into_iter()
+			.filter_map(|record| match record.event {
+				RuntimeEvent::Coretime(Event::CoreAssigned { core }) => Some(core),
+				_ => None,
+			})
+			.collect();
+		assert_eq!(assigned, vec![CoreIndex(0), CoreIndex(1)]);
+		assert_eq!(LastAssignment::<Test>::get(CoreIndex(1)), Some(20));
+	});
+}
+
+#[test]
+fn assign_cores_batch_reverts_on_any_failure() {
+	new_test_ext(Default::default()).execute_with(|| {
+		System::set_block_number(1);
+		let full_pool = vec![(CoreAssignment::Pool, PartsOf57600::FULL)];
+
+		assert_noop!(
+			Coretime::assign_cores_batch(
+				RuntimeOrigin::root(),
+				BoundedVec::truncate_from(vec![
+					(0, 10, full_pool.clone(), None),
+					(1, 10, full_pool.clone(), Some(10)),
+					(2, 10, full_pool.clone(), None),
+				]),
+			),
+			Error::<Test>::EndHintBeforeBegin
+		);
+		assert!(System::events().is_empty());
+
+		assert_noop!(
+			Coretime::assign_cores_batch(
+				RuntimeOrigin::root(),
+				BoundedVec::truncate_from(vec![(0, 10, Vec::new(), None)]),
+			),
+			Error::<Test>::IncompleteAssignment
+		);
+
+		// The assigner rejects the second assignment of core 0, as it does not come after the
+		// first one.
+		assert_noop!(
+			Coretime::assign_cores_batch(
+				RuntimeOrigin::root(),
+				BoundedVec::truncate_from(vec![
+					(0, 10, full_pool.clone(), None),
+					(1, 10, full_pool.clone(), None),
+					(0, 5, full_pool, None),
+				]),
+			),
+			assigner_coretime::Error::<Test>::DisallowedInsert
+		);
+		assert!(System::events().is_empty());
+	});
+}
+
+#[test]
+fn assign_cores_batch_rejects_non_broker_origins() {
+	new_test_ext(Default::default()).execute_with(|| {
+		assert_noop!(
+			Coretime::assign_cores_batch(para_origin(BrokerId::get() + 1), Default::default()),
+			Error::<Test>::NotBroker
+		);
+	});
+}
+
+#[test]
+fn credit_account_mints_into_account() {
+	new_test_ext(Default::default()).execute_with(|| {
+		System::set_block_number(1);
+
+		assert_ok!(Coretime::credit_account(para_origin(BrokerId::get()), 1, 1_000));
+		assert_eq!(Balances::balance(&1), 1_000);
+		assert_eq!(Balances::total_issuance(), 1_000);
+		assert_last_event(RuntimeEvent::Coretime(Event::AccountCredited { who: 1, amount: 1_000 }));
+
+		assert_ok!(Coretime::credit_account(RuntimeOrigin::root(), 1, 500));
+		assert_eq!(Balances::balance(&1), 1_500);
+	});
+}
+
+#[test]
+fn credit_account_rejects_non_broker_origins() {
+	new_test_ext(Default::default()).execute_with(|| {
+		assert_noop!(
+			Coretime::credit_account(para_origin(BrokerId::get() + 1), 1, 1_000),
+			Error::<Test>::NotBroker
+		);
+		assert_noop!(
+			Coretime::credit_account(RuntimeOrigin::signed(1), 1, 1_000),
+			DispatchError::BadOrigin
+		);
+		assert_eq!(Balances::balance(&1), 0);
+	});
+}
+
+#[test]
+fn credit_account_cap_applies_per_block() {
+	new_test_ext(Default::default()).execute_with(|| {
+		let cap = MaxCreditPerBlock::get();
+
+		assert_ok!(Coretime::credit_account(RuntimeOrigin::root(), 1, cap - 10));
+		assert_noop!(
+			Coretime::credit_account(RuntimeOrigin::root(), 2, 11),
+			Error::<Test>::CreditCapExceeded
+		);
+		assert_ok!(Coretime::credit_account(RuntimeOrigin::root(), 2, 10));
+
+		Coretime::on_initialize(2);
+		assert_ok!(Coretime::credit_account(RuntimeOrigin::root(), 2, cap));
+		assert_eq!(Balances::balance(&2), cap + 10);
+	});
+}
+
+#[test]
+fn core_freed_is_emitted_once_when_assignment_ends() {
+	new_test_ext(Default::default()).execute_with(|| {
+		System::set_block_number(1);
+		assert_ok!(assign_full_core(2, Some(5)));
+
+		for n in 2..10 {
+			System::set_block_number(n);
+			Coretime::on_initialize(n);
+		}
+
+		let freed: Vec<_> = System::events()
+			.into_iter()
+			.filter_map(|record| match record.event {
+				RuntimeEvent::Coretime(Event::CoreFreed { core }) => Some(core),
+				_ => None,
+			})
+			.collect();
+		assert_eq!(freed, vec![CoreIndex(0)]);
+		assert!(LastAssignment::<Test>::get(CoreIndex(0)).is_none());
+	});
+}
+
+#[test]
+fn open_ended_assignment_is_never_freed() {
+	new_test_ext(Default::default()).execute_with(|| {
+		System::set_block_number(1);
+		assert_ok!(assign_full_core(2, Some(5)));
+		assert_ok!(assign_full_core(3, None));
+
+		for n in 2..10 {
+			System::set_block_number(n);
+			Coretime::on_initialize(n);
+		}
+
+		assert!(!System::events()
+			.into_iter()
+			.any(|record| matches!(record.event, RuntimeEvent::Coretime(Event::CoreFreed { .. }))));
+	});
+}
+
+#[test]
+fn core_summary_classifies_cores_by_dominant_assignment() {
+	new_test_ext(Default::default()).execute_with(|| {
+		configuration::ActiveConfig::<Test>::mutate(|c| c.coretime_cores = 5);
+		let parts = PartsOf57600::new_saturating;
+		let assign = |core, begin, assignments| {
+			Coretime::assign_core(RuntimeOrigin::root(), core, begin, assignments, None)
+		};
+
+		assert_ok!(assign(0, 1, vec![(CoreAssignment::Task(1), PartsOf57600::FULL)]));
+		assert_ok!(assign(1, 1, vec![(CoreAssignment::Pool, PartsOf57600::FULL)]));
+		// Tasks together hold the largest share.
+		assert_ok!(assign(
+			2,
+			1,
+			vec![
+				(CoreAssignment::Pool, parts(14400)),
+				(CoreAssignment::Task(1), parts(28800)),
+				(CoreAssignment::Task(2), parts(14400)),
+			]
+		));
+		// Evenly split.
+		assert_ok!(assign(
+			3,
+			1,
+			vec![(CoreAssignment::Pool, parts(28800)), (CoreAssignment::Task(1), parts(28800))]
+		));
+		// Not yet active.
+		assert_ok!(assign(4, 10, vec![(CoreAssignment::Task(1), PartsOf57600::FULL)]));
+		// Beyond the configured core count.
+		assert_ok!(assign(5, 1, vec![(CoreAssignment::Pool, PartsOf57600::FULL)]));
+
+		System::set_block_number(2);
+		assert_eq!(Coretime::core_summary(), CoreSummary { task: 2, pool: 1, idle: 1, other: 1 });
+
+		System::set_block_number(10);
+		assert_eq!(Coretime::core_summary(), CoreSummary { task: 3, pool: 1, idle: 0, other: 1 });
+	});
+}
+
+#[test]
+fn force_clear_core_drops_active_and_scheduled_assignments() {
+	new_test_ext(Default::default()).execute_with(|| {
+		configuration::ActiveConfig::<Test>::mutate(|c| c.coretime_cores = 1);
+		System::set_block_number(1);
+		assert_ok!(assign_full_core(1, Some(10)));
+		assert_ok!(assign_full_core(10, Some(20)));
+
+		System::set_block_number(2);
+		assert_eq!(Coretime::core_summary(), CoreSummary { pool: 1, ..Default::default() });
+
+		assert_noop!(
+			Coretime::force_clear_core(para_origin(BrokerId::get()), 0),
+			DispatchError::BadOrigin
+		);
+		assert_ok!(Coretime::force_clear_core(RuntimeOrigin::root(), 0));
+		assert_last_event(Event::CoreForciblyCleared { core: CoreIndex(0) }.into());
+
+		assert_eq!(Coretime::core_summary(), CoreSummary { idle: 1, ..Default::default() });
+		assert!(LastAssignment::<Test>::get(CoreIndex(0)).is_none());
+
+		// The previously scheduled assignment never becomes active.
+		System::set_block_number(10);
+		assert_eq!(Coretime::core_summary(), CoreSummary { idle: 1, ..Default::default() });
+	});
+}
+
+#[test]
+fn request_revenue_info_at_sends_revenue_to_broker() {
+	new_test_ext(Default::default()).execute_with(|| {
+		for n in 1..=3 {
+			System::set_block_number(n);
+			OnDemandAssigner::on_initialize(n);
+			OnDemandAssigner::note_revenue(100 * Balance::from(n));
+		}
+		let broker = MultiLocation {
+			parents: 0,
+			interior: Junctions::X1(Junction::Parachain(BrokerId::get())),
+		};
+		let notify_revenue = |until, amount| {
+			Xcm(vec![mk_coretime_call(CoretimeCalls::NotifyRevenue((until, amount)))])
+		};
+
+		assert_noop!(
+			Coretime::request_revenue_info_at(para_origin(BrokerId::get()), 4),
+			Error::<Test>::RequestedFutureRevenue
+		);
+		assert_ok!(Coretime::request_revenue_info_at(para_origin(BrokerId::get()), 3));
+		assert_last_event(Event::RevenueInfoRequested { when: 3 }.into());
+		// The revenue of the current block is not included.
+		assert_eq!(SentXcm::take(), vec![(broker, notify_revenue(3, 100 + 200))]);
+
+		// Claimed revenue is not reported again.
+		assert_ok!(Coretime::request_revenue_info_at(RuntimeOrigin::root(), 3));
+		assert_eq!(SentXcm::take(), vec![(broker, notify_revenue(3, 0))]);
+	});
+}
+
+#[test]
+fn pause_origin_can_pause_and_unpause_broker_calls() {
+	new_test_ext(Default::default()).execute_with(|| {
+		let pauser = RuntimeOrigin::signed(CoretimePauser::get());
+
+		assert_ok!(Coretime::set_paused(pauser.clone(), true));
+		assert_last_event(Event::PausedSet { paused: true }.into());
+		assert_noop!(
+			Coretime::request_core_count(para_origin(BrokerId::get()), 2),
+			Error::<Test>::Paused
+		);
+		assert_noop!(Coretime::request_core_count(RuntimeOrigin::root(), 2), Error::<Test>::Paused);
+		// Root-only calls keep working.
+		assert_ok!(Coretime::force_clear_core(RuntimeOrigin::root(), 0));
+
+		assert_ok!(Coretime::set_paused(pauser, false));
+		assert_last_event(Event::PausedSet { paused: false }.into());
+		assert_ok!(Coretime::request_core_count(para_origin(BrokerId::get()), 2));
+	});
+}
+
+#[test]
+fn only_pause_origin_can_pause() {
+	new_test_ext(Default::default()).execute_with(|| {
+		assert_noop!(
+			Coretime::set_paused(RuntimeOrigin::signed(1), true),
+			DispatchError::BadOrigin
+		);
+		assert_noop!(
+			Coretime::set_paused(para_origin(BrokerId::get()), true),
+			DispatchError::BadOrigin
+		);
+		assert!(!Paused::<Test>::get());
+
+		assert_ok!(Coretime::set_paused(RuntimeOrigin::root(), true));
+		assert!(Paused::<Test>::get());
+	});
+}
+
+#[test]
+fn frozen_assignments_are_only_accepted_from_root() {
+	new_test_ext(Default::default()).execute_with(|| {
+		System::set_block_number(1);
+		let full_pool = vec![(CoreAssignment::Pool, PartsOf57600::FULL)];
+
+		assert_ok!(Coretime::set_assignments_frozen(RuntimeOrigin::root(), true));
+		assert_last_event(Event::AssignmentsFreezeChanged { frozen: true }.into());
+		assert_noop!(
+			Coretime::assign_core(para_origin(BrokerId::get()), 0, 10, full_pool.clone(), None),
+			Error::<Test>::AssignmentsFrozen
+		);
+		assert_noop!(
+			Coretime::assign_cores_batch(
+				para_origin(BrokerId::get()),
+				BoundedVec::truncate_from(vec![(1, 10, full_pool.clone(), None)]),
+			),
+			Error::<Test>::AssignmentsFrozen
+		);
+		// Other broker calls are not affected.
+		assert_ok!(Coretime::request_core_count(para_origin(BrokerId::get()), 2));
+
+		assert_ok!(Coretime::assign_core(RuntimeOrigin::root(), 0, 10, full_pool.clone(), None));
+		assert_ok!(Coretime::assign_cores_batch(
+			RuntimeOrigin::root(),
+			BoundedVec::truncate_from(vec![(1, 10, full_pool.clone(), None)]),
+		));
+
+		assert_ok!(Coretime::set_assignments_frozen(RuntimeOrigin::root(), false));
+		assert_last_event(Event::AssignmentsFreezeChanged { frozen: false }.into());
+		assert_ok!(Coretime::assign_core(para_origin(BrokerId::get()), 2, 10, full_pool, None));
+	});
+}
+
+#[test]
+fn only_root_can_freeze_assignments() {
+	new_test_ext(Default::default()).execute_with(|| {
+		assert_noop!(
+			Coretime::set_assignments_frozen(para_origin(BrokerId::get()), true),
+			DispatchError::BadOrigin
+		);
+		assert_noop!(
+			Coretime::set_assignments_frozen(RuntimeOrigin::signed(CoretimePauser::get()), true),
+			DispatchError::BadOrigin
+		);
+		assert!(!AssignmentsFrozen::<Test>::get());
+	});
+}
+
+#[test]
+fn migrate_para_cores_reassigns_all_cores_of_para() {
+	new_test_ext(Default::default()).execute_with(|| {
+		configuration::ActiveConfig::<Test>::mutate(|c| c.coretime_cores = 4);
+		System::set_block_number(1);
+		let parts = PartsOf57600::new_saturating;
+		let assign = |core, assignments, end_hint| {
+			Coretime::assign_core(RuntimeOrigin::root(), core, 1, assignments, end_hint)
+		};
+		assert_ok!(assign(0, vec![(CoreAssignment::Task(1), PartsOf57600::FULL)], None));
+		assert_ok!(assign(
+			1,
+			vec![(CoreAssignment::Pool, parts(28800)), (CoreAssignment::Task(1), parts(28800))],
+			Some(50)
+		));
+		assert_ok!(assign(2, vec![(CoreAssignment::Task(2), PartsOf57600::FULL)], None));
+		// The new para already has a share of this core.
+		assert_ok!(assign(
+			3,
+			vec![(CoreAssignment::Task(1), parts(14400)), (CoreAssignment::Task(5), parts(43200))],
+			None
+		));
+		System::set_block_number(2);
+		System::reset_events();
+
+		assert_ok!(Coretime::migrate_para_cores(
+			para_origin(BrokerId::get()),
+			1.into(),
+			5.into(),
+			10
+		));
+
+		let events: Vec<_> = System::events().into_iter().map(|record| record.event).collect();
+		assert_eq!(
+			events,
+			vec![
+				Event::<Test>::CoreAssigned { core: CoreIndex(0) }.into(),
+				Event::<Test>::CoreAssigned { core: CoreIndex(1) }.into(),
+				Event::<Test>::CoreAssigned { core: CoreIndex(3) }.into(),
+				Event::<Test>::ParaCoresMigrated { old: 1.into(), new: 5.into(), count: 3 }.into(),
+			]
+		);
+
+		let current = |core| CoretimeAssigner::current_assignments(10, CoreIndex(core));
+		assert_eq!(current(0), vec![(CoreAssignment::Task(5), PartsOf57600::FULL)]);
+		assert_eq!(
+			current(1),
+			vec![(CoreAssignment::Pool, parts(28800)), (CoreAssignment::Task(5), parts(28800))]
+		);
+		assert_eq!(current(2), vec![(CoreAssignment::Task(2), PartsOf57600::FULL)]);
+		assert_eq!(current(3), vec![(CoreAssignment::Task(5), PartsOf57600::FULL)]);
+		assert_eq!(LastAssignment::<Test>::get(CoreIndex(1)), Some(50));
+	});
+}
+
+#[test]
+fn migrate_para_cores_only_notifies_new_para() {
+	new_test_ext(Default::default()).execute_with(|| {
+		configuration::ActiveConfig::<Test>::mutate(|c| c.coretime_cores = 1);
+		System::set_block_number(1);
+		let half = PartsOf57600::new_saturating(28800);
+		assert_ok!(Coretime::assign_core(
+			RuntimeOrigin::root(),
+			0,
+			1,
+			vec![(CoreAssignment::Task(1), half), (CoreAssignment::Task(2), half)],
+			None
+		));
+		System::set_block_number(2);
+		NotifyAssignedParas::set(true);
+
+		assert_ok!(Coretime::migrate_para_cores(RuntimeOrigin::root(), 1.into(), 5.into(), 10));
+
+		let notified: Vec<_> = SentXcm::take().into_iter().map(|(dest, _)| dest).collect();
+		assert_eq!(
+			notified,
+			vec![MultiLocation { parents: 0, interior: Junctions::X1(Junction::Parachain(5)) }]
+		);
+	});
+}
+
+#[test]
+fn migrate_para_cores_without_cores_is_noop() {
+	new_test_ext(Default::default()).execute_with(|| {
+		configuration::ActiveConfig::<Test>::mutate(|c| c.coretime_cores = 1);
+		System::set_block_number(1);
+		assert_ok!(assign_full_core(1, None));
+		System::reset_events();
+
+		assert_ok!(Coretime::migrate_para_cores(RuntimeOrigin::root(), 1.into(), 5.into(), 10));
+
+		let events: Vec<_> = System::events().into_iter().map(|record| record.event).collect();
+		assert_eq!(
+			events,
+			vec![Event::<Test>::ParaCoresMigrated { old: 1.into(), new: 5.into(), count: 0 }.into()]
+		);
+		assert_eq!(
+			CoretimeAssigner::current_assignments(10, CoreIndex(0)),
+			vec![(CoreAssignment::Pool, PartsOf57600::FULL)]
+		);
+	});
+}
+
+#[test]
+fn migrate_para_cores_is_reverted_on_any_failure() {
+	new_test_ext(Default::default()).execute_with(|| {
+		configuration::ActiveConfig::<Test>::mutate(|c| c.coretime_cores = 2);
+		System::set_block_number(1);
+		let full_task = |para| vec![(CoreAssignment::Task(para), PartsOf57600::FULL)];
+		assert_ok!(Coretime::assign_core(RuntimeOrigin::root(), 0, 1, full_task(1), Some(20)));
+		assert_ok!(Coretime::assign_core(RuntimeOrigin::root(), 1, 1, full_task(1), None));
+		// Queued after the migration would begin.
+		assert_ok!(Coretime::assign_core(RuntimeOrigin::root(), 1, 15, full_task(2), None));
+		System::set_block_number(2);
+
+		assert_noop!(
+			Coretime::migrate_para_cores(para_origin(BrokerId::get()), 1.into(), 5.into(), 10),
+			assigner_coretime::Error::<Test>::DisallowedInsert
+		);
+	});
+}
+
+#[test]
+fn migrate_para_cores_skips_cores_ending_before_begin() {
+	new_test_ext(Default::default()).execute_with(|| {
+		configuration::ActiveConfig::<Test>::mutate(|c| c.coretime_cores = 2);
+		System::set_block_number(1);
+		let full_task = vec![(CoreAssignment::Task(1), PartsOf57600::FULL)];
+		assert_ok!(Coretime::assign_core(RuntimeOrigin::root(), 0, 1, full_task.clone(), Some(20)));
+		// Ends before the migration would begin.
+		assert_ok!(Coretime::assign_core(RuntimeOrigin::root(), 1, 1, full_task.clone(), Some(5)));
+		System::set_block_number(2);
+		System::reset_events();
+
+		assert_ok!(Coretime::migrate_para_cores(
+			para_origin(BrokerId::get()),
+			1.into(),
+			5.into(),
+			10
+		));
+
+		let events: Vec<_> = System::events().into_iter().map(|record| record.event).collect();
+		assert_eq!(
+			events,
+			vec![
+				Event::<Test>::CoreAssigned { core: CoreIndex(0) }.into(),
+				Event::<Test>::ParaCoresMigrated { old: 1.into(), new: 5.into(), count: 1 }.into(),
+			]
+		);
+		assert_eq!(
+			CoretimeAssigner::current_assignments(10, CoreIndex(0)),
+			vec![(CoreAssignment::Task(5), PartsOf57600::FULL)]
+		);
+		assert_eq!(CoretimeAssigner::current_assignments(4, CoreIndex(1)), full_task);
+		assert_eq!(LastAssignment::<Test>::get(CoreIndex(1)), Some(5));
+	});
+}
+
+#[test]
+fn cores_are_mapped_to_the_same_index_by_default() {
+	new_test_ext(Default::default()).execute_with(|| {
+		assert_eq!(Coretime::broker_to_relay_core(0), Some(CoreIndex(0)));
+		assert_eq!(Coretime::broker_to_relay_core(7), Some(CoreIndex(7)));
+		assert_eq!(Coretime::relay_to_broker_core(CoreIndex(7)), Some(7));
+		// Beyond the range of the broker's core index.
+		assert_eq!(Coretime::relay_to_broker_core(CoreIndex(u16::MAX as u32 + 1)), None);
+	});
+}
+
+#[test]
+fn remapped_core_receives_assignments_of_its_broker_index() {
+	new_test_ext(Default::default()).execute_with(|| {
+		System::set_block_number(1);
+		assert_noop!(
+			Coretime::remap_core(para_origin(BrokerId::get()), 0, CoreIndex(2)),
+			DispatchError::BadOrigin
+		);
+
+		assert_ok!(Coretime::remap_core(RuntimeOrigin::root(), 0, CoreIndex(2)));
+		System::assert_last_event(
+			Event::<Test>::CoreRemapped { broker: 0, relay: CoreIndex(2) }.into(),
+		);
+		assert_eq!(Coretime::broker_to_relay_core(0), Some(CoreIndex(2)));
+		assert_eq!(Coretime::relay_to_broker_core(CoreIndex(2)), Some(0));
+		// Neither the relay chain core `0` used to refer to, nor the broker core index that used
+		// to refer to the new relay chain core are mapped anymore.
+		assert_eq!(Coretime::relay_to_broker_core(CoreIndex(0)), None);
+		assert_eq!(Coretime::broker_to_relay_core(2), None);
+
+		let full_task = vec![(CoreAssignment::Task(1), PartsOf57600::FULL)];
+		assert_ok!(Coretime::assign_core(RuntimeOrigin::root(), 0, 1, full_task.clone(), None));
+		System::assert_last_event(Event::<Test>::CoreAssigned { core: CoreIndex(2) }.into());
+		assert_eq!(CoretimeAssigner::current_assignments(1, CoreIndex(2)), full_task.clone());
+		assert_noop!(
+			Coretime::assign_core(RuntimeOrigin::root(), 2, 1, full_task, None),
+			Error::<Test>::CoreNotMapped
+		);
+
+		// Mapping back to the same index restores the default.
+		assert_ok!(Coretime::remap_core(RuntimeOrigin::root(), 0, CoreIndex(0)));
+		assert_eq!(Coretime::broker_to_relay_core(0), Some(CoreIndex(0)));
+		assert_eq!(Coretime::broker_to_relay_core(2), Some(CoreIndex(2)));
+		assert_eq!(BrokerToRelayCore::<Test>::iter().count(), 0);
+		assert_eq!(RelayToBrokerCore::<Test>::iter().count(), 0);
+	});
+}
@@ -30,14 +30,15 @@ use frame_support::pallet_prelude::*;
 use primitives::CoreIndex;
 
 use frame_support::{
-	assert_ok, derive_impl, parameter_types,
+	assert_ok, derive_impl, ord_parameter_types, parameter_types,
 	traits::{
-		Currency, ProcessMessage, ProcessMessageError, ValidatorSet, ValidatorSetWithIdentification,
+		Currency, EitherOfDiverse, ProcessMessage, ProcessMessageError, ValidatorSet,
+		ValidatorSetWithIdentification,
 	},
 	weights::{Weight, WeightMeter},
 };
 use frame_support_test::TestRandomness;
-use frame_system::limits;
+use frame_system::{limits, EnsureRoot, EnsureSignedBy};
 use parity_scale_codec::Decode;
 use primitives::{
 	AuthorityDiscoveryId, Balance, BlockNumber, CandidateHash, Moment, SessionIndex, UpwardMessage,
@@ -355,6 +356,7 @@ impl pallet_message_queue::Config for Test {
 
 parameter_types! {
 	pub const OnDemandTrafficDefaultValue: FixedU128 = FixedU128::from_u32(1);
+	pub const MaxHistoricalRevenue: u32 = 10;
 }
 
 impl assigner_parachains::Config for Test {}
@@ -363,6 +365,7 @@ impl assigner_on_demand::Config for Test {
 	type RuntimeEvent = RuntimeEvent;
 	type Currency = Balances;
 	type TrafficDefaultValue = OnDemandTrafficDefaultValue;
+	type MaxHistoricalRevenue = MaxHistoricalRevenue;
 	type WeightInfo = crate::assigner_on_demand::TestWeightInfo;
 }
 
@@ -370,6 +373,19 @@ impl assigner_coretime::Config for Test {}
 
 parameter_types! {
 	pub const BrokerId: u32 = 10u32;
+	pub const MaxCreditPerBlock: Balance = 1_000_000;
+	pub const MaxCoreAssignmentsPerBatch: u32 = 4;
+	pub static NotifyAssignedParas: bool = false;
+	pub const NotificationPalletIndex: u8 = 42;
+	pub const NotificationCallIndex: u8 = 3;
+	pub const NotificationCallWeight: Weight = Weight::from_parts(1_000_000, 1_000);
+	/// Messages delivered by [`DummyXcmSender`], oldest first.
+	pub static SentXcm: Vec<(MultiLocation, Xcm<()>)> = Vec::new();
+}
+
+ord_parameter_types! {
+	/// The account allowed to pause the broker chain calls, besides root.
+	pub const CoretimePauser: u64 = 100;
 }
 
 impl coretime::Config for Test {
@@ -377,22 +393,36 @@ impl coretime::Config for Test {
 	type RuntimeEvent = RuntimeEvent;
 	type Currency = pallet_balances::Pallet<Test>;
 	type BrokerId = BrokerId;
+	type MaxCreditPerBlock = MaxCreditPerBlock;
+	type MaxCoreAssignmentsPerBatch = MaxCoreAssignmentsPerBatch;
 	type WeightInfo = crate::coretime::TestWeightInfo;
 	type SendXcm = DummyXcmSender;
+	type NotifyAssignedParas = NotifyAssignedParas;
+	type NotificationCall = coretime::NotificationCallAt<
+		NotificationPalletIndex,
+		NotificationCallIndex,
+		NotificationCallWeight,
+	>;
+	type PauseOrigin = EitherOfDiverse<EnsureRoot<u64>, EnsureSignedBy<CoretimePauser, u64>>;
 }
 
 pub struct DummyXcmSender;
 impl SendXcm for DummyXcmSender {
-	type Ticket = ();
+	type Ticket = (MultiLocation, Xcm<()>);
 	fn validate(
-		_: &mut Option<MultiLocation>,
-		_: &mut Option<Xcm<()>>,
+		dest: &mut Option<MultiLocation>,
+		msg: &mut Option<Xcm<()>>,
 	) -> SendResult<Self::Ticket> {
-		Ok(((), MultiAssets::new()))
+		let ticket = (
+			dest.take().ok_or(SendError::MissingArgument)?,
+			msg.take().ok_or(SendError::MissingArgument)?,
+		);
+		Ok((ticket, MultiAssets::new()))
 	}
 
 	/// Actually carry out the delivery operation for a previously validated message sending.
-	fn deliver(_ticket: Self::Ticket) -> Result<XcmHash, SendError> {
+	fn deliver(ticket: Self::Ticket) -> Result<XcmHash, SendError> {
+		SentXcm::mutate(|sent| sent.push(ticket));
 		Ok([0u8; 32])
 	}
 }
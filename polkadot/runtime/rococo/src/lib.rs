@@ -91,8 +91,8 @@ use sp_runtime::{
 		IdentityLookup, Keccak256, OpaqueKeys, SaturatedConversion, Verify,
 	},
 	transaction_validity::{TransactionPriority, TransactionSource, TransactionValidity},
-	ApplyExtrinsicResult, BoundToRuntimeAppPublic, FixedU128, KeyTypeId, Perbill, Percent, Permill,
-	RuntimeAppPublic, RuntimeDebug,
+	ApplyExtrinsicResult, BoundToRuntimeAppPublic, DispatchError, FixedU128, KeyTypeId, Perbill,
+	Percent, Permill, RuntimeAppPublic, RuntimeDebug,
 };
 use sp_staking::SessionIndex;
 #[cfg(any(feature = "std", test))]
@@ -1011,6 +1011,9 @@ impl parachains_scheduler::Config for Runtime {
 
 parameter_types! {
 	pub const BrokerId: u32 = BROKER_ID;
+	pub const MaxCreditPerBlock: Balance = 100_000 * UNITS;
+	pub const MaxCoreAssignmentsPerBatch: u32 = 100;
+	pub const NotifyAssignedParas: bool = false;
 }
 
 impl coretime::Config for Runtime {
@@ -1018,18 +1021,27 @@ impl coretime::Config for Runtime {
 	type RuntimeEvent = RuntimeEvent;
 	type Currency = Balances;
 	type BrokerId = BrokerId;
+	type MaxCreditPerBlock = MaxCreditPerBlock;
+	type MaxCoreAssignmentsPerBatch = MaxCoreAssignmentsPerBatch;
 	type WeightInfo = weights::runtime_parachains_coretime::WeightInfo<Runtime>;
 	type SendXcm = crate::xcm_config::XcmRouter;
+	type NotifyAssignedParas = NotifyAssignedParas;
+	// Parachains are not notified, no receiving call exists yet.
+	type NotificationCall = ();
+	type PauseOrigin = EitherOf<EnsureRoot<AccountId>, GeneralAdmin>;
 }
 
 parameter_types! {
 	pub const OnDemandTrafficDefaultValue: FixedU128 = FixedU128::from_u32(1);
+	// Keep the revenue of two broker timeslices of 80 blocks each.
+	pub const MaxHistoricalRevenue: u32 = 2 * 80;
 }
 
 impl parachains_assigner_on_demand::Config for Runtime {
 	type RuntimeEvent = RuntimeEvent;
 	type Currency = Balances;
 	type TrafficDefaultValue = OnDemandTrafficDefaultValue;
+	type MaxHistoricalRevenue = MaxHistoricalRevenue;
 	type WeightInfo = weights::runtime_parachains_assigner_on_demand::WeightInfo<Runtime>;
 }
 
@@ -1682,6 +1694,7 @@ pub mod migrations {
 		parachains_configuration::migration::v11::MigrateToV11<Runtime>,
 		// This needs to come after the `parachains_configuration` above as we are reading the configuration.
 		coretime::migration::MigrateToCoretime<Runtime, crate::xcm_config::XcmRouter, GetLegacyLeaseImpl>,
+		coretime::migration::MigrateToV1<Runtime>,
 	);
 }
 
@@ -2213,6 +2226,12 @@ sp_api::impl_runtime_apis! {
 		}
 	}
 
+	impl coretime::CoretimeApi<Block, AccountId, Balance> for Runtime {
+		fn can_credit(who: AccountId, amount: Balance) -> Result<(), DispatchError> {
+			Coretime::can_credit(who, amount).map_err(Into::into)
+		}
+	}
+
 	impl pallet_beefy_mmr::BeefyMmrApi<Block, Hash> for RuntimeApi {
 		fn authority_set_proof() -> beefy_primitives::mmr::BeefyAuthoritySet<Hash> {
 			MmrLeaf::authority_set_proof()
@@ -45,7 +45,11 @@
 use frame_support::{traits::Get, weights::Weight};
 use core::marker::PhantomData;
 
-use runtime_parachains::configuration::{self, WeightInfo as ConfigWeightInfo};
+use pallet_xcm::WeightInfo as XcmWeightInfo;
+use runtime_parachains::{
+	configuration::{self, WeightInfo as ConfigWeightInfo},
+	coretime,
+};
 
 /// Weight functions for `runtime_common::coretime`.
 pub struct WeightInfo<T>(PhantomData<T>);
@@ -53,6 +57,20 @@ impl<T: frame_system::Config + configuration::Config> runtime_parachains::coreti
 	fn request_core_count() -> Weight {
 		<T as configuration::Config>::WeightInfo::set_config_with_u32()
 	}
+	fn request_revenue_info_at() -> Weight {
+		// TODO: run benchmarks. Placeholder accounting for the storage accessed, i.e.
+		// `Coretime::Paused` (r:1) and `OnDemandAssignmentProvider::Revenue` (r:1 w:1), and for
+		// sending the revenue to the broker chain.
+		T::DbWeight::get()
+			.reads_writes(2, 1)
+			.saturating_add(super::pallet_xcm::WeightInfo::<T>::send())
+	}
+	fn credit_account() -> Weight {
+		// TODO: run benchmarks. Placeholder accounting for the storage accessed, i.e.
+		// `Coretime::Paused` (r:1), `Coretime::CreditedInBlock` (r:1 w:1), `System::Account`
+		// (r:1 w:1) and `Balances::TotalIssuance` (r:1 w:1).
+		T::DbWeight::get().reads_writes(4, 3)
+	}
 	/// Storage: `CoreTimeAssignmentProvider::CoreDescriptors` (r:1 w:1)
 	/// Proof: `CoreTimeAssignmentProvider::CoreDescriptors` (`max_values`: None, `max_size`: None, mode: `Measured`)
 	/// Storage: `CoreTimeAssignmentProvider::CoreSchedules` (r:0 w:1)
@@ -69,5 +87,49 @@ impl<T: frame_system::Config + configuration::Config> runtime_parachains::coreti
 			.saturating_add(Weight::from_parts(15_028, 0).saturating_mul(s.into()))
 			.saturating_add(T::DbWeight::get().reads(1))
 			.saturating_add(T::DbWeight::get().writes(2))
+			// TODO: run benchmarks. The measured weight above only covers the assigner, the
+			// storage accessed by `Coretime` itself, i.e. `Coretime::Paused` (r:1),
+			// `Coretime::AssignmentsFrozen` (r:1), `Coretime::BrokerToRelayCore` (r:1),
+			// `Coretime::RelayToBrokerCore` (r:1) and `Coretime::LastAssignment` (w:1), is
+			// accounted for separately.
+			.saturating_add(T::DbWeight::get().reads_writes(4, 1))
+	}
+	fn force_clear_core() -> Weight {
+		// TODO: run benchmarks. Placeholder accounting for the storage accessed, i.e.
+		// `Coretime::BrokerToRelayCore` (r:1), `Coretime::RelayToBrokerCore` (r:1),
+		// `CoreTimeAssignmentProvider::CoreDescriptors` (r:1 w:1), `Coretime::LastAssignment` (w:1)
+		// and up to three queued `CoreTimeAssignmentProvider::CoreSchedules` (r:3 w:3).
+		T::DbWeight::get().reads_writes(6, 5)
+	}
+	fn set_paused() -> Weight {
+		// TODO: run benchmarks. Placeholder accounting for the storage accessed, i.e.
+		// `Coretime::Paused` (w:1).
+		T::DbWeight::get().writes(1)
+	}
+	fn migrate_para_cores(c: u32, ) -> Weight {
+		// TODO: run benchmarks. Placeholder accounting for the storage accessed, i.e.
+		// `Configuration::ActiveConfig` (r:1), `Coretime::Paused` (r:1) and
+		// `Coretime::AssignmentsFrozen` (r:1), then for each core `Coretime::BrokerToRelayCore`
+		// (r:1), `Coretime::RelayToBrokerCore` (r:1), `CoreTimeAssignmentProvider::CoreDescriptors`
+		// (r:1), `CoreTimeAssignmentProvider::CoreSchedules` (r:1) and `Coretime::LastAssignment`
+		// (r:1), and for reassigning each core with the maximum of 100 assignments.
+		let per_core = T::DbWeight::get()
+			.reads(5)
+			.saturating_add(<Self as coretime::WeightInfo>::assign_core(100));
+		T::DbWeight::get().reads(3).saturating_add(per_core.saturating_mul(c.into()))
+	}
+	fn set_assignments_frozen() -> Weight {
+		// TODO: run benchmarks. Placeholder accounting for the storage accessed, i.e.
+		// `Coretime::AssignmentsFrozen` (w:1).
+		T::DbWeight::get().writes(1)
+	}
+	fn remap_core() -> Weight {
+		// TODO: run benchmarks. Placeholder accounting for the storage accessed, i.e.
+		// `Coretime::BrokerToRelayCore` (r:1 w:2) and `Coretime::RelayToBrokerCore` (r:1 w:2).
+		T::DbWeight::get().reads_writes(2, 4)
+	}
+	fn notify_para() -> Weight {
+		// TODO: run benchmarks. Placeholder accounting for sending the notification to the para.
+		super::pallet_xcm::WeightInfo::<T>::send()
 	}
 }
@@ -130,6 +130,7 @@ pub fn new_full(config: Configuration, consensus: Consensus) -> Result<TaskManag
 			net_config,
 			block_announce_validator_builder: None,
 			warp_sync_params: None,
+			warp_sync_options: Default::default(),
 			block_relay: None,
 		})?;
 
@@ -423,6 +423,7 @@ pub fn new_full_base(
 			import_queue,
 			block_announce_validator_builder: None,
 			warp_sync_params: Some(WarpSyncParams::WithProvider(warp_sync)),
+			warp_sync_options: Default::default(),
 			block_relay: None,
 		})?;
 
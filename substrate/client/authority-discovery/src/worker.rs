@@ -490,6 +490,12 @@ where
 
 				debug!(target: LOG_TARGET, "Failed to put hash '{:?}' on Dht.", hash)
 			},
+			DhtEvent::StartedProviding(_) |
+			DhtEvent::StartProvidingFailed(_) |
+			DhtEvent::ProvidersFound(..) |
+			DhtEvent::ProvidersNotFound(_) => {
+				// Authority discovery doesn't use provider records.
+			},
 		}
 	}
 
@@ -20,10 +20,14 @@ use std::{
 	collections::HashSet,
 	sync::{Arc, Mutex},
 	task::Poll,
+	time::Instant,
 };
 
 use futures::{
-	channel::mpsc::{self, channel},
+	channel::{
+		mpsc::{self, channel},
+		oneshot,
+	},
 	executor::{block_on, LocalPool},
 	future::FutureExt,
 	sink::SinkExt,
@@ -38,7 +42,7 @@ use libp2p::{
 use prometheus_endpoint::prometheus::default_registry;
 
 use sc_client_api::HeaderBackend;
-use sc_network::Signature;
+use sc_network::{DhtError, Quorum, Signature};
 use sp_api::{ApiRef, ProvideRuntimeApi};
 use sp_keystore::{testing::MemoryKeystore, Keystore};
 use sp_runtime::traits::{Block as BlockT, NumberFor, Zero};
@@ -164,8 +168,15 @@ impl NetworkSigner for TestNetwork {
 	}
 }
 
+#[async_trait::async_trait]
 impl NetworkDHTProvider for TestNetwork {
-	fn put_value(&self, key: KademliaKey, value: Vec<u8>) {
+	fn put_value_to(
+		&self,
+		key: KademliaKey,
+		value: Vec<u8>,
+		_quorum: Quorum,
+		_expires: Option<Instant>,
+	) {
 		self.put_value_call.lock().unwrap().push((key.clone(), value.clone()));
 		self.event_sender
 			.clone()
@@ -179,6 +190,22 @@ impl NetworkDHTProvider for TestNetwork {
 			.unbounded_send(TestNetworkEvent::GetCalled(key.clone()))
 			.unwrap();
 	}
+	fn start_get_value(
+		&self,
+		key: &KademliaKey,
+	) -> oneshot::Receiver<std::result::Result<Vec<(KademliaKey, Vec<u8>)>, DhtError>> {
+		self.get_value(key);
+		oneshot::channel().1
+	}
+	async fn flush_dht_puts(&self, _timeout: Duration) -> std::result::Result<(), ()> {
+		Ok(())
+	}
+	fn start_providing(&self, _key: KademliaKey) {
+		unimplemented!();
+	}
+	fn get_providers(&self, _key: KademliaKey) {
+		unimplemented!();
+	}
 }
 
 impl NetworkStateInfo for TestNetwork {
@@ -193,6 +220,10 @@ impl NetworkStateInfo for TestNetwork {
 	fn listen_addresses(&self) -> Vec<Multiaddr> {
 		self.external_addresses.clone()
 	}
+
+	fn is_major_syncing(&self) -> bool {
+		false
+	}
 }
 
 struct TestSigner<'a> {
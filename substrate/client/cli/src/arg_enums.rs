@@ -251,3 +251,25 @@ impl Into<sc_network::config::SyncMode> for SyncMode {
 		}
 	}
 }
+
+/// IP address family to try first when dialing.
+#[derive(Debug, Clone, Copy, ValueEnum, PartialEq)]
+#[value(rename_all = "kebab-case")]
+pub enum IpPreference {
+	/// Dial IPv4 addresses first.
+	V4First,
+	/// Dial IPv6 addresses first.
+	V6First,
+	/// Keep the order in which addresses were discovered.
+	System,
+}
+
+impl Into<sc_network::config::IpPreference> for IpPreference {
+	fn into(self) -> sc_network::config::IpPreference {
+		match self {
+			IpPreference::V4First => sc_network::config::IpPreference::V4First,
+			IpPreference::V6First => sc_network::config::IpPreference::V6First,
+			IpPreference::System => sc_network::config::IpPreference::System,
+		}
+	}
+}
@@ -16,7 +16,10 @@
 // You should have received a copy of the GNU General Public License
 // along with this program. If not, see <https://www.gnu.org/licenses/>.
 
-use crate::{arg_enums::SyncMode, params::node_key_params::NodeKeyParams};
+use crate::{
+	arg_enums::{IpPreference, SyncMode},
+	params::node_key_params::NodeKeyParams,
+};
 use clap::Args;
 use sc_network::{
 	config::{
@@ -160,6 +163,16 @@ pub struct NetworkParams {
 	)]
 	pub sync: SyncMode,
 
+	/// IP address family to dial first for peers reachable over both IPv4 and IPv6.
+	#[arg(
+		long,
+		value_enum,
+		value_name = "IP_PREFERENCE",
+		default_value_t = IpPreference::System,
+		ignore_case = true
+	)]
+	pub ip_preference: IpPreference,
+
 	/// Maximum number of blocks per request.
 	///
 	/// Try reducing this number from the default value if you have a slow network connection
@@ -252,6 +265,7 @@ impl NetworkParams {
 				enable_mdns: !is_dev && !self.no_mdns,
 				allow_private_ip,
 			},
+			ip_preference: self.ip_preference.into(),
 			max_parallel_downloads: self.max_parallel_downloads,
 			max_blocks_per_request: self.max_blocks_per_request,
 			enable_dht_random_walk: !self.reserved_only,
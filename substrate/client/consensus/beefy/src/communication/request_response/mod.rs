@@ -63,6 +63,7 @@ pub(crate) fn on_demand_justifications_protocol_config<Hash: AsRef<[u8]>>(
 		// We are connected to all validators:
 		request_timeout: JUSTIF_REQUEST_TIMEOUT,
 		inbound_queue: Some(tx),
+		max_bandwidth_bps: None,
 	};
 	(rx, cfg)
 }
@@ -43,7 +43,7 @@ use sp_consensus_grandpa::AuthorityList;
 use sp_keyring::Ed25519Keyring;
 use sp_runtime::traits::NumberFor;
 use std::{
-	collections::HashSet,
+	collections::{HashMap, HashSet},
 	pin::Pin,
 	sync::Arc,
 	task::{Context, Poll},
@@ -83,6 +83,14 @@ impl NetworkPeers for TestNetwork {
 		unimplemented!()
 	}
 
+	fn pin_reputation(&self, _peer_id: PeerId, _value: i32) {
+		unimplemented!()
+	}
+
+	fn unpin_reputation(&self, _peer_id: PeerId) {
+		unimplemented!()
+	}
+
 	fn disconnect_peer(&self, _peer_id: PeerId, _protocol: ProtocolName) {}
 
 	fn accept_unreserved_peers(&self) {
@@ -109,6 +117,13 @@ impl NetworkPeers for TestNetwork {
 		unimplemented!();
 	}
 
+	fn set_reserved_peers_multi(
+		&self,
+		_sets: HashMap<ProtocolName, HashSet<Multiaddr>>,
+	) -> Result<(), String> {
+		unimplemented!();
+	}
+
 	fn add_peers_to_reserved_set(
 		&self,
 		_protocol: ProtocolName,
@@ -129,6 +144,22 @@ impl NetworkPeers for TestNetwork {
 		unimplemented!();
 	}
 
+	fn connected_peers(&self, _protocol: ProtocolName) -> Vec<PeerId> {
+		unimplemented!();
+	}
+
+	fn num_connected(&self, _protocol: ProtocolName) -> usize {
+		unimplemented!();
+	}
+
+	fn reserved_peers(&self, _protocol: ProtocolName) -> Result<HashSet<PeerId>, String> {
+		unimplemented!();
+	}
+
+	fn peer_protocols(&self, _peer_id: &PeerId) -> Vec<ProtocolName> {
+		unimplemented!();
+	}
+
 	fn peer_role(&self, _peer_id: PeerId, handshake: Vec<u8>) -> Option<ObservedRole> {
 		Roles::decode_all(&mut &handshake[..])
 			.ok()
@@ -279,6 +310,14 @@ impl NotificationService for TestNotificationService {
 	fn message_sink(&self, _peer: &PeerId) -> Option<Box<dyn MessageSink>> {
 		unimplemented!();
 	}
+
+	fn last_sent(&self, _peer: &PeerId) -> Option<std::time::Instant> {
+		unimplemented!();
+	}
+
+	fn connected_peers(&self) -> Vec<PeerId> {
+		unimplemented!();
+	}
 }
 
 pub(crate) struct Tester {
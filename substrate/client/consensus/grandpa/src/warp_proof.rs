@@ -29,6 +29,7 @@ use sp_consensus_grandpa::{AuthorityList, SetId, GRANDPA_ENGINE_ID};
 use sp_runtime::{
 	generic::BlockId,
 	traits::{Block as BlockT, Header as HeaderT, NumberFor, One},
+	Justifications,
 };
 
 use std::{collections::HashMap, sync::Arc};
@@ -311,6 +312,28 @@ where
 		}
 	}
 
+	fn verify_justifications(
+		&self,
+		header: &Block::Header,
+		justifications: &Justifications,
+		set_id: SetId,
+		authorities: AuthorityList,
+	) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+		let justification = justifications
+			.get(GRANDPA_ENGINE_ID)
+			.ok_or_else(|| "Missing GRANDPA justification".to_string())?;
+		let justification =
+			GrandpaJustification::<Block>::decode_all(&mut justification.as_slice())
+				.map_err(|e| format!("Justification decoding error: {:?}", e))?;
+
+		if justification.target() != (*header.number(), header.hash()) {
+			return Err("Mismatch between header and justification".into())
+		}
+
+		justification.verify(set_id, &authorities).map_err(Box::new)?;
+		Ok(())
+	}
+
 	fn current_authorities(&self) -> AuthorityList {
 		self.authority_set.inner().current_authorities.clone()
 	}
@@ -123,8 +123,11 @@ impl<B: BlockT> InformantDisplay<B> {
 						"⏩",
 						"Warping".into(),
 						format!(
-							", {}, {:.2} Mib",
+							", {}{}, {:.2} Mib",
 							warp.phase,
+							warp.milestone
+								.map(|milestone| format!(" (reached {milestone})"))
+								.unwrap_or_default(),
 							(warp.total_bytes as f32) / (1024f32 * 1024f32)
 						),
 					),
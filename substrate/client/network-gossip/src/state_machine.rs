@@ -629,6 +629,14 @@ mod tests {
 			unimplemented!()
 		}
 
+		fn pin_reputation(&self, _peer_id: PeerId, _value: i32) {
+			unimplemented!()
+		}
+
+		fn unpin_reputation(&self, _peer_id: PeerId) {
+			unimplemented!()
+		}
+
 		fn disconnect_peer(&self, _peer_id: PeerId, _protocol: ProtocolName) {
 			unimplemented!();
 		}
@@ -657,6 +665,13 @@ mod tests {
 			unimplemented!();
 		}
 
+		fn set_reserved_peers_multi(
+			&self,
+			_sets: HashMap<ProtocolName, HashSet<Multiaddr>>,
+		) -> Result<(), String> {
+			unimplemented!();
+		}
+
 		fn add_peers_to_reserved_set(
 			&self,
 			_protocol: ProtocolName,
@@ -677,6 +692,22 @@ mod tests {
 			unimplemented!();
 		}
 
+		fn connected_peers(&self, _protocol: ProtocolName) -> Vec<PeerId> {
+			unimplemented!();
+		}
+
+		fn num_connected(&self, _protocol: ProtocolName) -> usize {
+			unimplemented!();
+		}
+
+		fn reserved_peers(&self, _protocol: ProtocolName) -> Result<HashSet<PeerId>, String> {
+			unimplemented!();
+		}
+
+		fn peer_protocols(&self, _peer_id: &PeerId) -> Vec<ProtocolName> {
+			unimplemented!();
+		}
+
 		fn peer_role(&self, _peer_id: PeerId, _handshake: Vec<u8>) -> Option<ObservedRole> {
 			None
 		}
@@ -774,6 +805,14 @@ mod tests {
 		fn message_sink(&self, _peer: &PeerId) -> Option<Box<dyn MessageSink>> {
 			unimplemented!();
 		}
+
+		fn last_sent(&self, _peer: &PeerId) -> Option<std::time::Instant> {
+			unimplemented!();
+		}
+
+		fn connected_peers(&self) -> Vec<PeerId> {
+			unimplemented!();
+		}
 	}
 
 	#[test]
@@ -0,0 +1,79 @@
+// This file is part of Substrate.
+
+// Copyright (C) Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! Rate limiting of the outbound bytes of a protocol.
+//!
+//! Used by request-response protocols, see
+//! [`ProtocolConfig::max_bandwidth_bps`](crate::request_responses::ProtocolConfig::max_bandwidth_bps),
+//! and by notification protocols, see
+//! [`NonDefaultSetConfig::set_max_bandwidth_bps`](crate::config::NonDefaultSetConfig::set_max_bandwidth_bps).
+
+use std::time::{Duration, Instant};
+
+/// Limits the rate of outbound bytes of a protocol.
+#[derive(Debug)]
+pub(crate) struct TokenBucket {
+	/// Bytes added to the bucket per second, also its capacity.
+	rate: f64,
+	/// Bytes that can be sent right now. Negative after sending a message larger than the bucket.
+	tokens: f64,
+	/// When `tokens` was last updated.
+	last_refill: Instant,
+}
+
+impl TokenBucket {
+	/// Creates a full bucket refilled with `rate` bytes per second.
+	pub(crate) fn new(rate: u64, now: Instant) -> Self {
+		let rate = rate.max(1) as f64;
+		Self { rate, tokens: rate, last_refill: now }
+	}
+
+	/// Take `bytes` out of the bucket if it holds enough of them, or returns how long to wait
+	/// until it does.
+	///
+	/// Messages larger than the bucket can be sent once it is full.
+	pub(crate) fn try_consume(&mut self, now: Instant, bytes: usize) -> Option<Duration> {
+		let elapsed = now.saturating_duration_since(self.last_refill).as_secs_f64();
+		self.tokens = (self.tokens + elapsed * self.rate).min(self.rate);
+		self.last_refill = now;
+
+		let needed = (bytes as f64).min(self.rate);
+		if self.tokens >= needed {
+			self.tokens -= bytes as f64;
+			None
+		} else {
+			Some(Duration::from_secs_f64((needed - self.tokens) / self.rate))
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn token_bucket_lets_oversized_messages_through_when_full() {
+		let now = Instant::now();
+		let mut bucket = TokenBucket::new(1_000, now);
+
+		assert_eq!(bucket.try_consume(now, 3_000), None);
+		// The oversized message is paid off before anything else is sent.
+		assert!(bucket.try_consume(now + Duration::from_secs(2), 1).is_some());
+		assert_eq!(bucket.try_consume(now + Duration::from_secs(3), 1), None);
+	}
+}
@@ -31,13 +31,21 @@ use crate::{
 
 use futures::channel::oneshot;
 use libp2p::{
-	core::Multiaddr, identify::Info as IdentifyInfo, identity::PublicKey, kad::RecordKey,
-	swarm::NetworkBehaviour, PeerId,
+	core::Multiaddr,
+	identify::Info as IdentifyInfo,
+	identity::PublicKey,
+	kad::{Quorum, RecordKey},
+	swarm::NetworkBehaviour,
+	PeerId,
 };
 
 use parking_lot::Mutex;
 use sp_runtime::traits::Block as BlockT;
-use std::{collections::HashSet, sync::Arc, time::Duration};
+use std::{
+	collections::HashSet,
+	sync::Arc,
+	time::{Duration, Instant},
+};
 
 pub use crate::request_responses::{InboundFailure, OutboundFailure, ResponseFailure};
 
@@ -145,6 +153,16 @@ pub enum BehaviourOut {
 		notification: Vec<u8>,
 	},
 
+	/// Failed to negotiate a notification protocol with the given node.
+	ProtocolNegotiationFailed {
+		/// Node we tried to open the substream with.
+		remote: PeerId,
+		/// Main name of the protocol.
+		protocol: ProtocolName,
+		/// Why the negotiation failed.
+		reason: String,
+	},
+
 	/// We have obtained identity information from a peer, including the addresses it is listening
 	/// on.
 	PeerIdentify {
@@ -157,8 +175,8 @@ pub enum BehaviourOut {
 	/// We have learned about the existence of a node on the default set.
 	Discovered(PeerId),
 
-	/// Events generated by a DHT as a response to get_value or put_value requests as well as the
-	/// request duration.
+	/// Events generated by a DHT as a response to get_value, put_value, start_providing or
+	/// get_providers requests as well as the request duration.
 	Dht(DhtEvent, Duration),
 
 	/// Ignored event generated by lower layers.
@@ -233,11 +251,18 @@ impl<B: BlockT> Behaviour<B> {
 		target: &PeerId,
 		protocol: &str,
 		request: Vec<u8>,
+		timeout: Option<Duration>,
 		pending_response: oneshot::Sender<Result<Vec<u8>, RequestFailure>>,
 		connect: IfDisconnected,
 	) {
-		self.request_responses
-			.send_request(target, protocol, request, pending_response, connect)
+		self.request_responses.send_request(
+			target,
+			protocol,
+			request,
+			timeout,
+			pending_response,
+			connect,
+		)
 	}
 
 	/// Returns a shared reference to the user protocol.
@@ -269,8 +294,26 @@ impl<B: BlockT> Behaviour<B> {
 
 	/// Starts putting a record into DHT. Will later produce either a `ValuePut` or a
 	/// `ValuePutFailed` event.
-	pub fn put_value(&mut self, key: RecordKey, value: Vec<u8>) {
-		self.discovery.put_value(key, value);
+	pub fn put_value(
+		&mut self,
+		key: RecordKey,
+		value: Vec<u8>,
+		quorum: Quorum,
+		expires: Option<Instant>,
+	) {
+		self.discovery.put_value(key, value, quorum, expires);
+	}
+
+	/// Starts announcing the local node as a provider of the key. Will later produce either a
+	/// `StartedProviding` or a `StartProvidingFailed` event.
+	pub fn start_providing(&mut self, key: RecordKey) {
+		self.discovery.start_providing(key);
+	}
+
+	/// Start looking up the providers of the key. Will later produce `ProvidersFound` events or
+	/// a `ProvidersNotFound` event.
+	pub fn get_providers(&mut self, key: RecordKey) {
+		self.discovery.get_providers(key);
 	}
 }
 
@@ -301,6 +344,8 @@ impl From<CustomMessageOutcome> for BehaviourOut {
 				BehaviourOut::NotificationStreamClosed { remote, set_id },
 			CustomMessageOutcome::NotificationsReceived { remote, set_id, notification } =>
 				BehaviourOut::NotificationsReceived { remote, set_id, notification },
+			CustomMessageOutcome::ProtocolNegotiationFailed { remote, protocol, reason } =>
+				BehaviourOut::ProtocolNegotiationFailed { remote, protocol, reason },
 		}
 	}
 }
@@ -344,6 +389,14 @@ impl From<DiscoveryOut> for BehaviourOut {
 				BehaviourOut::Dht(DhtEvent::ValuePut(key), duration),
 			DiscoveryOut::ValuePutFailed(key, duration) =>
 				BehaviourOut::Dht(DhtEvent::ValuePutFailed(key), duration),
+			DiscoveryOut::StartedProviding(key, duration) =>
+				BehaviourOut::Dht(DhtEvent::StartedProviding(key), duration),
+			DiscoveryOut::StartProvidingFailed(key, duration) =>
+				BehaviourOut::Dht(DhtEvent::StartProvidingFailed(key), duration),
+			DiscoveryOut::ProvidersFound(key, providers, duration) =>
+				BehaviourOut::Dht(DhtEvent::ProvidersFound(key, providers), duration),
+			DiscoveryOut::ProvidersNotFound(key, duration) =>
+				BehaviourOut::Dht(DhtEvent::ProvidersNotFound(key), duration),
 			DiscoveryOut::RandomKademliaStarted => BehaviourOut::RandomKademliaStarted,
 		}
 	}
@@ -61,6 +61,7 @@ use std::{
 	path::{Path, PathBuf},
 	pin::Pin,
 	str::{self, FromStr},
+	sync::{atomic::AtomicBool, Arc},
 };
 
 /// Protocol name prefix, transmitted on the wire for legacy protocol names.
@@ -258,6 +259,18 @@ pub enum TransportConfig {
 	MemoryOnly,
 }
 
+/// Which IP address family to try first when dialing a peer known under both.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum IpPreference {
+	/// Dial IPv4 addresses before IPv6 ones.
+	V4First,
+	/// Dial IPv6 addresses before IPv4 ones.
+	V6First,
+	/// Keep the order in which the addresses were discovered.
+	#[default]
+	System,
+}
+
 /// The policy for connections to non-reserved peers.
 #[derive(Clone, Debug, PartialEq, Eq)]
 pub enum NonReservedPeerMode {
@@ -481,6 +494,9 @@ pub struct NonDefaultSetConfig {
 	/// Maximum allowed size of single notifications.
 	max_notification_size: u64,
 
+	/// Maximum rate, in bytes per second, at which notifications of this protocol are sent.
+	max_bandwidth_bps: Option<u64>,
+
 	/// Base configuration.
 	set_config: SetConfig,
 
@@ -510,6 +526,7 @@ impl NonDefaultSetConfig {
 			Self {
 				protocol_name,
 				max_notification_size,
+				max_bandwidth_bps: None,
 				fallback_names,
 				handshake,
 				set_config,
@@ -539,6 +556,11 @@ impl NonDefaultSetConfig {
 		self.max_notification_size
 	}
 
+	/// Get the maximum rate, in bytes per second, at which notifications are sent.
+	pub fn max_bandwidth_bps(&self) -> Option<u64> {
+		self.max_bandwidth_bps
+	}
+
 	/// Get reference to `SetConfig`.
 	pub fn set_config(&self) -> &SetConfig {
 		&self.set_config
@@ -567,6 +589,15 @@ impl NonDefaultSetConfig {
 	pub fn add_fallback_names(&mut self, fallback_names: Vec<ProtocolName>) {
 		self.fallback_names.extend(fallback_names);
 	}
+
+	/// Limit the rate, in bytes per second, at which notifications of this protocol are sent.
+	///
+	/// The limit is shared by all peers. Notifications exceeding it wait in the queue of their
+	/// peer, up to one second worth of bytes can be sent in a burst. `None` for no limit, which
+	/// is the default.
+	pub fn set_max_bandwidth_bps(&mut self, max_bandwidth_bps: Option<u64>) {
+		self.max_bandwidth_bps = max_bandwidth_bps;
+	}
 }
 
 /// Network service configuration.
@@ -605,6 +636,9 @@ pub struct NetworkConfiguration {
 	/// Configuration for the transport layer.
 	pub transport: TransportConfig,
 
+	/// Preferred IP address family when dialing peers with both IPv4 and IPv6 addresses.
+	pub ip_preference: IpPreference,
+
 	/// Maximum number of peers to ask the same blocks in parallel.
 	pub max_parallel_downloads: u32,
 
@@ -677,6 +711,7 @@ impl NetworkConfiguration {
 			client_version: client_version.into(),
 			node_name: node_name.into(),
 			transport: TransportConfig::Normal { enable_mdns: false, allow_private_ip: true },
+			ip_preference: IpPreference::System,
 			max_parallel_downloads: 5,
 			max_blocks_per_request: 64,
 			sync_mode: SyncMode::Full,
@@ -750,6 +785,11 @@ pub struct Params<Block: BlockT> {
 
 	/// Block announce protocol configuration
 	pub block_announce_config: NonDefaultSetConfig,
+
+	/// Flag set by the syncing engine while the node is major syncing.
+	///
+	/// Exposed through [`NetworkStateInfo::is_major_syncing`](crate::NetworkStateInfo).
+	pub is_major_syncing: Arc<AtomicBool>,
 }
 
 /// Full network configuration.
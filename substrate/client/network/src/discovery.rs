@@ -46,7 +46,10 @@
 //! active mechanism that asks nodes for the addresses they are listening on. Whenever we learn
 //! of a node's address, you must call `add_self_reported_address`.
 
-use crate::{config::ProtocolId, utils::LruHashSet};
+use crate::{
+	config::{IpPreference, ProtocolId},
+	utils::LruHashSet,
+};
 
 use array_bytes::bytes2hex;
 use futures::prelude::*;
@@ -57,8 +60,8 @@ use libp2p::{
 	kad::{
 		handler::KademliaHandler,
 		record::store::{MemoryStore, RecordStore},
-		GetClosestPeersError, GetRecordOk, Kademlia, KademliaBucketInserts, KademliaConfig,
-		KademliaEvent, QueryId, QueryResult, Quorum, Record, RecordKey,
+		GetClosestPeersError, GetProvidersOk, GetRecordOk, Kademlia, KademliaBucketInserts,
+		KademliaConfig, KademliaEvent, QueryId, QueryResult, Quorum, Record, RecordKey,
 	},
 	mdns::{self, tokio::Behaviour as TokioMdns},
 	multiaddr::Protocol,
@@ -79,7 +82,7 @@ use std::{
 	collections::{hash_map::Entry, HashMap, HashSet, VecDeque},
 	num::NonZeroUsize,
 	task::{Context, Poll},
-	time::Duration,
+	time::{Duration, Instant},
 };
 
 /// Maximum number of known external addresses that we will cache.
@@ -101,6 +104,7 @@ pub struct DiscoveryConfig {
 	dht_random_walk: bool,
 	allow_private_ip: bool,
 	allow_non_globals_in_dht: bool,
+	ip_preference: IpPreference,
 	discovery_only_if_under_num: u64,
 	enable_mdns: bool,
 	kademlia_disjoint_query_paths: bool,
@@ -117,6 +121,7 @@ impl DiscoveryConfig {
 			dht_random_walk: true,
 			allow_private_ip: true,
 			allow_non_globals_in_dht: false,
+			ip_preference: IpPreference::System,
 			discovery_only_if_under_num: std::u64::MAX,
 			enable_mdns: false,
 			kademlia_disjoint_query_paths: false,
@@ -160,6 +165,12 @@ impl DiscoveryConfig {
 		self
 	}
 
+	/// Which IP address family should be dialed first?
+	pub fn with_ip_preference(&mut self, value: IpPreference) -> &mut Self {
+		self.ip_preference = value;
+		self
+	}
+
 	/// Should MDNS discovery be supported?
 	pub fn with_mdns(&mut self, value: bool) -> &mut Self {
 		self.enable_mdns = value;
@@ -203,6 +214,7 @@ impl DiscoveryConfig {
 			dht_random_walk,
 			allow_private_ip,
 			allow_non_globals_in_dht,
+			ip_preference,
 			discovery_only_if_under_num,
 			enable_mdns,
 			kademlia_disjoint_query_paths,
@@ -260,11 +272,13 @@ impl DiscoveryConfig {
 				Toggle::from(None)
 			},
 			allow_non_globals_in_dht,
+			ip_preference,
 			known_external_addresses: LruHashSet::new(
 				NonZeroUsize::new(MAX_KNOWN_EXTERNAL_ADDRESSES)
 					.expect("value is a constant; constant is non-zero; qed."),
 			),
 			records_to_publish: Default::default(),
+			providers_to_find: Default::default(),
 		}
 	}
 }
@@ -300,6 +314,8 @@ pub struct DiscoveryBehaviour {
 	discovery_only_if_under_num: u64,
 	/// Should non-global addresses be added to the DHT?
 	allow_non_globals_in_dht: bool,
+	/// Which IP address family to put first in the addresses returned for a dial.
+	ip_preference: IpPreference,
 	/// A cache of discovered external addresses. Only used for logging purposes.
 	known_external_addresses: LruHashSet<Multiaddr>,
 	/// Records to publish per QueryId.
@@ -308,6 +324,10 @@ pub struct DiscoveryBehaviour {
 	/// did not return the record(in `FinishedWithNoAdditionalRecord`). We will then put the record
 	/// to these peers.
 	records_to_publish: HashMap<QueryId, Record>,
+	/// Keys of the provider lookups per QueryId, until a first provider is found.
+	///
+	/// Used to report that no provider has been found once a lookup finishes.
+	providers_to_find: HashMap<QueryId, RecordKey>,
 }
 
 impl DiscoveryBehaviour {
@@ -398,13 +418,58 @@ impl DiscoveryBehaviour {
 	/// `get_value`.
 	///
 	/// A corresponding `ValuePut` or `ValuePutFailed` event will later be generated.
-	pub fn put_value(&mut self, key: RecordKey, value: Vec<u8>) {
+	pub fn put_value(
+		&mut self,
+		key: RecordKey,
+		value: Vec<u8>,
+		quorum: Quorum,
+		expires: Option<Instant>,
+	) {
 		if let Some(k) = self.kademlia.as_mut() {
-			if let Err(e) = k.put_record(Record::new(key.clone(), value.clone()), Quorum::All) {
+			let mut record = Record::new(key.clone(), value);
+			record.expires = expires;
+			if let Err(e) = k.put_record(record, quorum) {
 				warn!(target: "sub-libp2p", "Libp2p => Failed to put record: {:?}", e);
 				self.pending_events
 					.push_back(DiscoveryOut::ValuePutFailed(key.clone(), Duration::from_secs(0)));
 			}
+		} else {
+			debug!(target: "sub-libp2p", "Libp2p => Kademlia disabled, failed to put record");
+			self.pending_events
+				.push_back(DiscoveryOut::ValuePutFailed(key, Duration::from_secs(0)));
+		}
+	}
+
+	/// Start announcing the local node as a provider of the key to the DHT. Other nodes can
+	/// later find it with `get_providers`.
+	///
+	/// A corresponding `StartedProviding` or `StartProvidingFailed` event will later be generated.
+	pub fn start_providing(&mut self, key: RecordKey) {
+		if let Some(k) = self.kademlia.as_mut() {
+			if let Err(e) = k.start_providing(key.clone()) {
+				warn!(target: "sub-libp2p", "Libp2p => Failed to start providing: {:?}", e);
+				self.pending_events
+					.push_back(DiscoveryOut::StartProvidingFailed(key, Duration::from_secs(0)));
+			}
+		} else {
+			debug!(target: "sub-libp2p", "Libp2p => Kademlia disabled, failed to start providing");
+			self.pending_events
+				.push_back(DiscoveryOut::StartProvidingFailed(key, Duration::from_secs(0)));
+		}
+	}
+
+	/// Start looking up the providers of the key in the DHT.
+	///
+	/// Corresponding `ProvidersFound` events, or a `ProvidersNotFound` event, will later be
+	/// generated.
+	pub fn get_providers(&mut self, key: RecordKey) {
+		if let Some(k) = self.kademlia.as_mut() {
+			let id = k.get_providers(key.clone());
+			self.providers_to_find.insert(id, key);
+		} else {
+			debug!(target: "sub-libp2p", "Libp2p => Kademlia disabled, failed to get providers");
+			self.pending_events
+				.push_back(DiscoveryOut::ProvidersNotFound(key, Duration::from_secs(0)));
 		}
 	}
 
@@ -491,6 +556,26 @@ pub enum DiscoveryOut {
 	/// Returning the corresponding key as well as the request duration.
 	ValuePutFailed(RecordKey, Duration),
 
+	/// The local node has been announced as a provider of the key.
+	///
+	/// Returning the corresponding key as well as the request duration.
+	StartedProviding(RecordKey, Duration),
+
+	/// Announcing the local node as a provider of the key failed.
+	///
+	/// Returning the corresponding key as well as the request duration.
+	StartProvidingFailed(RecordKey, Duration),
+
+	/// The DHT yielded providers of the key.
+	///
+	/// Returning the key and the providers found, as well as the request duration.
+	ProvidersFound(RecordKey, HashSet<PeerId>, Duration),
+
+	/// No provider of the key was found in the DHT.
+	///
+	/// Returning the corresponding key as well as the request duration.
+	ProvidersNotFound(RecordKey, Duration),
+
 	/// Started a random Kademlia query.
 	///
 	/// Only happens if [`DiscoveryConfig::with_dht_random_walk`] has been configured to `true`.
@@ -586,6 +671,8 @@ impl NetworkBehaviour for DiscoveryBehaviour {
 			list.extend(list_to_filter);
 		}
 
+		sort_by_ip_preference(&mut list, self.ip_preference);
+
 		trace!(target: "sub-libp2p", "Addresses of {:?}: {:?}", peer_id, list);
 
 		Ok(list)
@@ -867,6 +954,96 @@ impl NetworkBehaviour for DiscoveryBehaviour {
 						};
 						return Poll::Ready(ToSwarm::GenerateEvent(ev))
 					},
+					KademliaEvent::OutboundQueryProgressed {
+						result: QueryResult::StartProviding(res),
+						stats,
+						..
+					} => {
+						let ev = match res {
+							Ok(ok) => DiscoveryOut::StartedProviding(
+								ok.key,
+								stats.duration().unwrap_or_default(),
+							),
+							Err(e) => {
+								debug!(
+									target: "sub-libp2p",
+									"Libp2p => Failed to start providing: {:?}",
+									e,
+								);
+								DiscoveryOut::StartProvidingFailed(
+									e.into_key(),
+									stats.duration().unwrap_or_default(),
+								)
+							},
+						};
+						return Poll::Ready(ToSwarm::GenerateEvent(ev))
+					},
+					KademliaEvent::OutboundQueryProgressed {
+						result: QueryResult::GetProviders(res),
+						stats,
+						id,
+						..
+					} => {
+						let ev = match res {
+							Ok(GetProvidersOk::FoundProviders { key, providers }) => {
+								debug!(
+									target: "sub-libp2p",
+									"Libp2p => Found {} providers of {:?}",
+									providers.len(),
+									key,
+								);
+								self.providers_to_find.remove(&id);
+
+								DiscoveryOut::ProvidersFound(
+									key,
+									providers,
+									stats.duration().unwrap_or_default(),
+								)
+							},
+							Ok(GetProvidersOk::FinishedWithNoAdditionalRecord { .. }) => {
+								// Only report lookups that didn't find any provider.
+								let Some(key) = self.providers_to_find.remove(&id) else {
+									continue
+								};
+
+								DiscoveryOut::ProvidersNotFound(
+									key,
+									stats.duration().unwrap_or_default(),
+								)
+							},
+							Err(e) => {
+								debug!(
+									target: "sub-libp2p",
+									"Libp2p => Failed to get providers: {:?}",
+									e,
+								);
+								if self.providers_to_find.remove(&id).is_none() {
+									continue
+								}
+
+								DiscoveryOut::ProvidersNotFound(
+									e.into_key(),
+									stats.duration().unwrap_or_default(),
+								)
+							},
+						};
+						return Poll::Ready(ToSwarm::GenerateEvent(ev))
+					},
+					KademliaEvent::OutboundQueryProgressed {
+						result: QueryResult::RepublishProvider(res),
+						..
+					} => match res {
+						Ok(ok) => debug!(
+							target: "sub-libp2p",
+							"Libp2p => Provider record republished: {:?}",
+							ok.key,
+						),
+						Err(e) => debug!(
+							target: "sub-libp2p",
+							"Libp2p => Republishing of provider record {:?} failed with: {:?}",
+							e.key(), e,
+						),
+					},
 					KademliaEvent::OutboundQueryProgressed {
 						result: QueryResult::RepublishRecord(res),
 						..
@@ -949,25 +1126,49 @@ fn kademlia_protocol_name<Hash: AsRef<[u8]>>(genesis_hash: Hash, fork_id: Option
 	}
 }
 
+/// Reorder `addresses` so that those of the preferred IP family come first.
+///
+/// The sort is stable, so addresses of the same family keep their relative order. Addresses
+/// without an IP family (e.g. `/memory/...`) are placed between the two families.
+fn sort_by_ip_preference(addresses: &mut [Multiaddr], preference: IpPreference) {
+	let (preferred_v4, preferred_v6) = match preference {
+		IpPreference::V4First => (0, 2),
+		IpPreference::V6First => (2, 0),
+		IpPreference::System => return,
+	};
+
+	addresses.sort_by_key(|addr| match addr.iter().next() {
+		Some(Protocol::Ip4(_)) | Some(Protocol::Dns4(_)) => preferred_v4,
+		Some(Protocol::Ip6(_)) | Some(Protocol::Dns6(_)) => preferred_v6,
+		_ => 1,
+	});
+}
+
 #[cfg(test)]
 mod tests {
 	use super::{
 		kademlia_protocol_name, legacy_kademlia_protocol_name, DiscoveryConfig, DiscoveryOut,
+		Quorum, RecordKey, RecordStore,
 	};
-	use crate::config::ProtocolId;
+	use crate::config::{IpPreference, ProtocolId};
 	use futures::prelude::*;
 	use libp2p::{
 		core::{
 			transport::{MemoryTransport, Transport},
-			upgrade,
+			upgrade, Endpoint,
 		},
 		identity::Keypair,
 		noise,
-		swarm::{Executor, Swarm, SwarmBuilder, SwarmEvent},
+		swarm::{ConnectionId, Executor, NetworkBehaviour, Swarm, SwarmBuilder, SwarmEvent},
 		yamux, Multiaddr,
 	};
 	use sp_core::hash::H256;
-	use std::{collections::HashSet, pin::Pin, task::Poll};
+	use std::{
+		collections::HashSet,
+		pin::Pin,
+		task::Poll,
+		time::{Duration, Instant},
+	};
 
 	struct TokioExecutor(tokio::runtime::Runtime);
 	impl Executor for TokioExecutor {
@@ -1187,4 +1388,79 @@ mod tests {
 			);
 		}
 	}
+
+	#[test]
+	fn dial_addresses_follow_ip_preference() {
+		let remote_peer_id = Keypair::generate_ed25519().public().to_peer_id();
+		let v4: Multiaddr = "/ip4/1.2.3.4/tcp/30333".parse().unwrap();
+		let v6: Multiaddr = "/ip6/2001:db8::1/tcp/30333".parse().unwrap();
+
+		let addresses_for = |preference| {
+			let keypair = Keypair::generate_ed25519();
+			let mut config = DiscoveryConfig::new(keypair.public().to_peer_id());
+			config
+				.with_permanent_addresses([
+					(remote_peer_id, v4.clone()),
+					(remote_peer_id, v6.clone()),
+				])
+				.with_ip_preference(preference);
+			let mut discovery = config.finish();
+
+			discovery
+				.handle_pending_outbound_connection(
+					ConnectionId::new_unchecked(0),
+					Some(remote_peer_id),
+					&[],
+					Endpoint::Dialer,
+				)
+				.unwrap()
+		};
+
+		assert_eq!(addresses_for(IpPreference::V4First), vec![v4.clone(), v6.clone()]);
+		assert_eq!(addresses_for(IpPreference::V6First), vec![v6.clone(), v4.clone()]);
+		assert_eq!(addresses_for(IpPreference::System), vec![v4, v6]);
+	}
+
+	#[test]
+	fn put_value_keeps_record_expiration() {
+		let keypair = Keypair::generate_ed25519();
+		let mut config = DiscoveryConfig::new(keypair.public().to_peer_id());
+		config.with_kademlia(H256::random(), None, &ProtocolId::from("dot"));
+		let mut discovery = config.finish();
+
+		let expires = Instant::now() + Duration::from_secs(60);
+		discovery.put_value(RecordKey::new(b"key"), b"value".to_vec(), Quorum::One, Some(expires));
+
+		let records: Vec<_> = discovery
+			.kademlia
+			.as_mut()
+			.unwrap()
+			.store_mut()
+			.records()
+			.map(|record| record.into_owned())
+			.collect();
+		assert_eq!(records.len(), 1);
+		assert_eq!(records[0].value, b"value".to_vec());
+		assert_eq!(records[0].expires, Some(expires));
+	}
+
+	#[test]
+	fn providers_fail_without_kademlia() {
+		let keypair = Keypair::generate_ed25519();
+		let mut discovery = DiscoveryConfig::new(keypair.public().to_peer_id()).finish();
+		let key = RecordKey::new(b"key");
+
+		discovery.start_providing(key.clone());
+		discovery.get_providers(key.clone());
+
+		assert!(matches!(
+			discovery.pending_events.pop_front(),
+			Some(DiscoveryOut::StartProvidingFailed(k, _)) if k == key
+		));
+		assert!(matches!(
+			discovery.pending_events.pop_front(),
+			Some(DiscoveryOut::ProvidersNotFound(k, _)) if k == key
+		));
+		assert!(discovery.pending_events.is_empty());
+	}
 }
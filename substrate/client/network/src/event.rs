@@ -26,7 +26,10 @@ use libp2p::{kad::record::Key, PeerId};
 
 use sc_network_common::role::ObservedRole;
 
-/// Events generated by DHT as a response to get_value and put_value requests.
+use std::collections::HashSet;
+
+/// Events generated by DHT as a response to get_value, put_value, start_providing and
+/// get_providers requests.
 #[derive(Debug, Clone)]
 #[must_use]
 pub enum DhtEvent {
@@ -41,6 +44,29 @@ pub enum DhtEvent {
 
 	/// An error has occurred while putting a record into the DHT.
 	ValuePutFailed(Key),
+
+	/// The local node has been announced as a provider of the key.
+	StartedProviding(Key),
+
+	/// An error has occurred while announcing the local node as a provider of the key.
+	StartProvidingFailed(Key),
+
+	/// Providers of the key have been found.
+	///
+	/// Can be generated multiple times for the same lookup, as providers are discovered.
+	ProvidersFound(Key, HashSet<PeerId>),
+
+	/// No provider of the key has been found in the DHT.
+	ProvidersNotFound(Key),
+}
+
+/// Error returned for a DHT lookup started with
+/// [`NetworkDHTProvider::start_get_value`](crate::NetworkDHTProvider::start_get_value).
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum DhtError {
+	/// The requested record has not been found in the DHT.
+	#[error("The requested record has not been found in the DHT")]
+	ValueNotFound,
 }
 
 /// Type for events generated by networking layer.
@@ -89,4 +115,15 @@ pub enum Event {
 		/// Concerned protocol and associated message.
 		messages: Vec<(ProtocolName, Bytes)>,
 	},
+
+	/// Failed to negotiate a notification protocol with a peer, typically because the peer only
+	/// supports other versions of it.
+	ProtocolNegotiationFailed {
+		/// Node we tried to open the substream with.
+		peer: PeerId,
+		/// Main name of the protocol the local node tried to open.
+		protocol: ProtocolName,
+		/// Why the negotiation failed.
+		reason: String,
+	},
 }
@@ -242,6 +242,7 @@
 //!
 //! More precise usage details are still being worked on and will likely change in the future.
 
+mod bandwidth_limit;
 mod behaviour;
 mod protocol;
 
@@ -262,22 +263,22 @@ pub mod transport;
 pub mod types;
 pub mod utils;
 
-pub use event::{DhtEvent, Event};
+pub use event::{DhtError, DhtEvent, Event};
 #[doc(inline)]
 pub use libp2p::{multiaddr, Multiaddr, PeerId};
-pub use request_responses::{Config, IfDisconnected, RequestFailure};
+pub use request_responses::{Config, IfDisconnected, RequestError, RequestFailure};
 pub use sc_network_common::{
 	role::{ObservedRole, Roles},
 	types::ReputationChange,
 };
 pub use service::{
-	signature::Signature,
+	signature::{Signature, SignatureVerificationError},
 	traits::{
-		KademliaKey, MessageSink, NetworkBlock, NetworkDHTProvider, NetworkEventStream,
-		NetworkNotification, NetworkPeers, NetworkRequest, NetworkSigner, NetworkStateInfo,
-		NetworkStatus, NetworkStatusProvider, NetworkSyncForkRequest,
-		NotificationSender as NotificationSenderT, NotificationSenderError,
-		NotificationSenderReady, NotificationService,
+		ConnectivityReport, ConnectivityStatus, KademliaKey, MessageSink, NetworkBlock,
+		NetworkDHTProvider, NetworkEventStream, NetworkNotification, NetworkPeers, NetworkRequest,
+		NetworkSigner, NetworkStateInfo, NetworkStatus, NetworkStatusProvider,
+		NetworkSyncForkRequest, NotificationSender as NotificationSenderT, NotificationSenderError,
+		NotificationSenderReady, NotificationService, Quorum,
 	},
 	DecodingError, Keypair, NetworkService, NetworkWorker, NotificationSender, OutboundFailure,
 	PublicKey,
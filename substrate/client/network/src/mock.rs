@@ -50,6 +50,14 @@ impl PeerStoreProvider for MockPeerStore {
 		0
 	}
 
+	fn pin_reputation(&mut self, _peer_id: PeerId, _value: i32) {
+		unimplemented!()
+	}
+
+	fn unpin_reputation(&mut self, _peer_id: PeerId) {
+		unimplemented!()
+	}
+
 	fn peer_role(&self, _peer_id: &PeerId) -> Option<ObservedRole> {
 		None
 	}
@@ -72,6 +72,12 @@ pub trait PeerStoreProvider: Debug + Send {
 	/// Get peer reputation.
 	fn peer_reputation(&self, peer_id: &PeerId) -> i32;
 
+	/// Fix peer reputation at `value`, exempting it from decay and reputation changes.
+	fn pin_reputation(&mut self, peer_id: PeerId, value: i32);
+
+	/// Let a pinned peer reputation decay and change again, starting from its pinned value.
+	fn unpin_reputation(&mut self, peer_id: PeerId);
+
 	/// Get peer role, if available.
 	fn peer_role(&self, peer_id: &PeerId) -> Option<ObservedRole>;
 
@@ -110,6 +116,14 @@ impl PeerStoreProvider for PeerStoreHandle {
 		self.inner.lock().peer_reputation(peer_id)
 	}
 
+	fn pin_reputation(&mut self, peer_id: PeerId, value: i32) {
+		self.inner.lock().pin_reputation(peer_id, value)
+	}
+
+	fn unpin_reputation(&mut self, peer_id: PeerId) {
+		self.inner.lock().unpin_reputation(peer_id)
+	}
+
 	fn peer_role(&self, peer_id: &PeerId) -> Option<ObservedRole> {
 		self.inner.lock().peer_role(peer_id)
 	}
@@ -132,6 +146,14 @@ impl PeerStoreHandle {
 	pub fn add_known_peer(&mut self, peer_id: PeerId) {
 		self.inner.lock().add_known_peer(peer_id);
 	}
+
+	/// Adjust the reputation of several peers, locking the peer store only once.
+	pub fn report_peers(&mut self, reports: Vec<(PeerId, ReputationChange)>) {
+		let mut inner = self.inner.lock();
+		for (peer_id, change) in reports {
+			inner.report_peer(peer_id, change);
+		}
+	}
 }
 
 #[derive(Debug, Clone, Copy)]
@@ -144,11 +166,14 @@ struct PeerInfo {
 
 	/// Role of the peer, if known.
 	role: Option<ObservedRole>,
+
+	/// Whether the reputation is pinned, i.e., neither decays nor changes on reports.
+	pinned: bool,
 }
 
 impl Default for PeerInfo {
 	fn default() -> Self {
-		Self { reputation: 0, last_updated: Instant::now(), role: None }
+		Self { reputation: 0, last_updated: Instant::now(), role: None, pinned: false }
 	}
 }
 
@@ -179,11 +204,17 @@ impl PeerInfo {
 	}
 
 	fn add_reputation(&mut self, increment: i32) {
-		self.reputation = self.reputation.saturating_add(increment);
+		if !self.pinned {
+			self.reputation = self.reputation.saturating_add(increment);
+		}
 		self.bump_last_updated();
 	}
 
 	fn decay_reputation(&mut self, seconds_passed: u64) {
+		if self.pinned {
+			return
+		}
+
 		// Note that decaying the reputation value happens "on its own",
 		// so we don't do `bump_last_updated()`.
 		for _ in 0..seconds_passed {
@@ -279,6 +310,32 @@ impl PeerStoreInner {
 		self.peers.get(peer_id).map_or(0, |info| info.reputation)
 	}
 
+	fn pin_reputation(&mut self, peer_id: PeerId, value: i32) {
+		let peer_info = self.peers.entry(peer_id).or_default();
+		peer_info.reputation = value;
+		peer_info.pinned = true;
+		peer_info.bump_last_updated();
+
+		if peer_info.is_banned() {
+			self.protocols.iter().for_each(|handle| handle.disconnect_peer(peer_id));
+		}
+
+		trace!(target: LOG_TARGET, "Pinned {peer_id} reputation to {value}.");
+	}
+
+	fn unpin_reputation(&mut self, peer_id: PeerId) {
+		if let Some(peer_info) = self.peers.get_mut(&peer_id) {
+			peer_info.pinned = false;
+			peer_info.bump_last_updated();
+
+			trace!(
+				target: LOG_TARGET,
+				"Unpinned {peer_id} reputation at {}.",
+				peer_info.reputation,
+			);
+		}
+	}
+
 	fn peer_role(&self, peer_id: &PeerId) -> Option<ObservedRole> {
 		self.peers.get(peer_id).map_or(None, |info| info.role)
 	}
@@ -308,10 +365,11 @@ impl PeerStoreInner {
 			.iter_mut()
 			.for_each(|(_, info)| info.decay_reputation(seconds_passed));
 
-		// Retain only entries with non-zero reputation values or not expired ones.
+		// Retain only pinned entries, entries with non-zero reputation values or not expired ones.
 		let now = Instant::now();
-		self.peers
-			.retain(|_, info| info.reputation != 0 || info.last_updated + FORGET_AFTER > now);
+		self.peers.retain(|_, info| {
+			info.pinned || info.reputation != 0 || info.last_updated + FORGET_AFTER > now
+		});
 	}
 
 	fn add_known_peer(&mut self, peer_id: PeerId) {
@@ -380,7 +438,9 @@ impl PeerStore {
 
 #[cfg(test)]
 mod tests {
-	use super::PeerInfo;
+	use super::{PeerInfo, PeerStore, PeerStoreProvider};
+	use libp2p::PeerId;
+	use sc_network_common::types::ReputationChange;
 
 	#[test]
 	fn decaying_zero_reputation_yields_zero() {
@@ -447,4 +507,39 @@ mod tests {
 		peer_info.decay_reputation(SECONDS / 2);
 		assert_eq!(peer_info.reputation, 0);
 	}
+
+	#[test]
+	fn report_peers_applies_every_report() {
+		let (peer1, peer2) = (PeerId::random(), PeerId::random());
+		let mut handle = PeerStore::new(Vec::new()).handle();
+
+		handle.report_peers(vec![
+			(peer1, ReputationChange::new(-100, "test")),
+			(peer2, ReputationChange::new(50, "test")),
+			(peer1, ReputationChange::new(-20, "test")),
+		]);
+		assert_eq!(handle.peer_reputation(&peer1), -120);
+		assert_eq!(handle.peer_reputation(&peer2), 50);
+	}
+
+	#[test]
+	fn pinned_reputation_is_exempt_from_reports_and_decay() {
+		const PINNED_REPUTATION: i32 = 1_000;
+
+		let peer_id = PeerId::random();
+		let mut handle = PeerStore::new(Vec::new()).handle();
+
+		handle.pin_reputation(peer_id, PINNED_REPUTATION);
+		handle.report_peer(peer_id, ReputationChange::new(-500, "test"));
+		handle.report_disconnect(peer_id);
+		handle.inner.lock().progress_time(100);
+		assert_eq!(handle.peer_reputation(&peer_id), PINNED_REPUTATION);
+
+		handle.unpin_reputation(peer_id);
+		handle.report_peer(peer_id, ReputationChange::new(-500, "test"));
+		assert_eq!(handle.peer_reputation(&peer_id), PINNED_REPUTATION - 500);
+
+		handle.inner.lock().progress_time(1);
+		assert!(handle.peer_reputation(&peer_id) < PINNED_REPUTATION - 500);
+	}
 }
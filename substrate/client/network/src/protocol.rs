@@ -101,6 +101,7 @@ impl<B: BlockT> Protocol<B> {
 					fallback_names: block_announces_protocol.fallback_names().cloned().collect(),
 					handshake: block_announces_protocol.handshake().as_ref().unwrap().to_vec(),
 					max_notification_size: block_announces_protocol.max_notification_size(),
+					max_bandwidth_bps: block_announces_protocol.max_bandwidth_bps(),
 				};
 
 				let (handle, command_stream) =
@@ -114,6 +115,7 @@ impl<B: BlockT> Protocol<B> {
 					fallback_names: s.fallback_names().cloned().collect(),
 					handshake: s.handshake().as_ref().map_or(roles.encode(), |h| (*h).to_vec()),
 					max_notification_size: s.max_notification_size(),
+					max_bandwidth_bps: s.max_bandwidth_bps(),
 				};
 
 				let (handle, command_stream) = s.take_protocol_handle().split();
@@ -227,6 +229,15 @@ pub enum CustomMessageOutcome {
 		/// Received notification.
 		notification: Vec<u8>,
 	},
+	/// Failed to negotiate a notification protocol with a remote.
+	ProtocolNegotiationFailed {
+		// Peer ID.
+		remote: PeerId,
+		/// Main name of the protocol.
+		protocol: ProtocolName,
+		/// Why the negotiation failed.
+		reason: String,
+	},
 }
 
 impl<B: BlockT> NetworkBehaviour for Protocol<B> {
@@ -381,6 +392,12 @@ impl<B: BlockT> NetworkBehaviour for Protocol<B> {
 					)
 				}
 			},
+			NotificationsOut::ProtocolNegotiationFailed { peer_id, protocol, reason, .. } =>
+				Some(CustomMessageOutcome::ProtocolNegotiationFailed {
+					remote: peer_id,
+					protocol,
+					reason,
+				}),
 		};
 
 		match outcome {
@@ -17,6 +17,7 @@
 // along with this program. If not, see <https://www.gnu.org/licenses/>.
 
 use crate::{
+	bandwidth_limit::TokenBucket,
 	protocol::notifications::{
 		handler::{self, NotificationsSink, NotifsHandler, NotifsHandlerIn, NotifsHandlerOut},
 		service::{metrics, NotificationCommand, ProtocolHandle, ValidationCallResult},
@@ -39,7 +40,7 @@ use libp2p::{
 	PeerId,
 };
 use log::{debug, error, trace, warn};
-use parking_lot::RwLock;
+use parking_lot::{Mutex, RwLock};
 use rand::distributions::{Distribution as _, Uniform};
 use sc_utils::mpsc::TracingUnboundedReceiver;
 use smallvec::SmallVec;
@@ -181,6 +182,8 @@ pub struct ProtocolConfig {
 	pub handshake: Vec<u8>,
 	/// Maximum allowed size for a notification.
 	pub max_notification_size: u64,
+	/// Maximum rate, in bytes per second, at which notifications are sent to all peers.
+	pub max_bandwidth_bps: Option<u64>,
 }
 
 /// Identifier for a delay firing.
@@ -397,6 +400,19 @@ pub enum NotificationsOut {
 		/// Message that has been received.
 		message: BytesMut,
 	},
+
+	/// Failed to negotiate a custom protocol with the remote, e.g. because it only supports
+	/// other versions of the protocol.
+	ProtocolNegotiationFailed {
+		/// Id of the peer we tried to open the substream with.
+		peer_id: PeerId,
+		/// Peerset set ID the substream would have been tied to.
+		set_id: SetId,
+		/// Main name of the protocol.
+		protocol: ProtocolName,
+		/// Why the negotiation failed.
+		reason: String,
+	},
 }
 
 impl Notifications {
@@ -421,6 +437,9 @@ impl Notifications {
 						fallback_names: cfg.fallback_names,
 						handshake: Arc::new(RwLock::new(cfg.handshake)),
 						max_notification_size: cfg.max_notification_size,
+						bandwidth_limit: cfg.max_bandwidth_bps.map(|rate| {
+							Arc::new(Mutex::new(TokenBucket::new(rate, Instant::now())))
+						}),
 					},
 					(protocol_handle, command_stream),
 				)
@@ -1994,6 +2013,21 @@ impl NetworkBehaviour for Notifications {
 				}
 			},
 
+			NotifsHandlerOut::ProtocolNegotiationFailed { protocol_index, reason } => {
+				let set_id = SetId::from(protocol_index);
+				trace!(target: "sub-libp2p",
+					"Handler({:?}, {:?}) => ProtocolNegotiationFailed({:?}, {})",
+					peer_id, connection_id, set_id, reason);
+
+				let event = NotificationsOut::ProtocolNegotiationFailed {
+					peer_id,
+					set_id,
+					protocol: self.notif_protocols[protocol_index].name.clone(),
+					reason,
+				};
+				self.events.push_back(ToSwarm::GenerateEvent(event));
+			},
+
 			NotifsHandlerOut::CloseResult { protocol_index } => {
 				let set_id = SetId::from(protocol_index);
 
@@ -2457,6 +2491,7 @@ mod tests {
 						fallback_names: Vec::new(),
 						handshake: vec![1, 2, 3, 4],
 						max_notification_size: u64::MAX,
+						max_bandwidth_bps: None,
 					},
 					notif_handle,
 					command_stream,
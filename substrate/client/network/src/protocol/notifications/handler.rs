@@ -58,6 +58,7 @@
 //! [`NotifsHandlerIn::Open`] has gotten an answer.
 
 use crate::{
+	bandwidth_limit::TokenBucket,
 	protocol::notifications::{
 		service::metrics,
 		upgrade::{
@@ -74,11 +75,12 @@ use futures::{
 	lock::{Mutex as FuturesMutex, MutexGuard as FuturesMutexGuard},
 	prelude::*,
 };
+use futures_timer::Delay;
 use libp2p::{
-	core::ConnectedPoint,
+	core::{upgrade::UpgradeError, ConnectedPoint},
 	swarm::{
-		handler::ConnectionEvent, ConnectionHandler, ConnectionHandlerEvent, KeepAlive,
-		NegotiatedSubstream, SubstreamProtocol,
+		handler::ConnectionEvent, ConnectionHandler, ConnectionHandlerEvent,
+		ConnectionHandlerUpgrErr, KeepAlive, NegotiatedSubstream, SubstreamProtocol,
 	},
 	PeerId,
 };
@@ -152,7 +154,12 @@ impl NotifsHandler {
 						config.max_notification_size,
 					);
 
-					Protocol { config, in_upgrade, state: State::Closed { pending_opening: false } }
+					Protocol {
+						config,
+						in_upgrade,
+						state: State::Closed { pending_opening: false },
+						throttle_timer: None,
+					}
 				})
 				.collect(),
 			peer_id,
@@ -175,6 +182,8 @@ pub struct ProtocolConfig {
 	pub handshake: Arc<RwLock<Vec<u8>>>,
 	/// Maximum allowed size for a notification.
 	pub max_notification_size: u64,
+	/// Limits the rate of notifications sent to all peers, shared by all connections.
+	pub bandwidth_limit: Option<Arc<Mutex<TokenBucket>>>,
 }
 
 /// Fields specific for each individual protocol.
@@ -187,6 +196,9 @@ struct Protocol {
 
 	/// Current state of the substreams for this protocol.
 	state: State,
+
+	/// Fires once the bandwidth limit of the protocol allows sending the next notification.
+	throttle_timer: Option<Delay>,
 }
 
 /// See the module-level documentation to learn about the meaning of these variants.
@@ -301,6 +313,17 @@ pub enum NotifsHandlerOut {
 		protocol_index: usize,
 	},
 
+	/// The remote doesn't support the protocol, or negotiating it failed otherwise.
+	///
+	/// Always immediately followed by a [`NotifsHandlerOut::OpenResultErr`] for the same
+	/// protocol.
+	ProtocolNegotiationFailed {
+		/// Index of the protocol in the list of protocols passed at initialization.
+		protocol_index: usize,
+		/// Why the negotiation failed.
+		reason: String,
+	},
+
 	/// Acknowledges a [`NotifsHandlerIn::Close`].
 	CloseResult {
 		/// Index of the protocol in the list of protocols passed at initialization.
@@ -410,32 +433,45 @@ impl NotificationsSink {
 		&self.inner.peer_id
 	}
 
+	/// Returns `true` if the background task dedicated to the peer is still accepting
+	/// notifications.
+	///
+	/// Returns `false` once the substream has been closed or if the synchronous channel has
+	/// overflowed, in which case the connection will be closed shortly after.
+	pub fn is_connected(&self) -> bool {
+		self.inner.sync_channel.lock().as_ref().is_some_and(|tx| !tx.is_closed())
+	}
+
 	/// Sends a notification to the peer.
 	///
 	/// If too many messages are already buffered, the notification is silently discarded and the
 	/// connection to the peer will be closed shortly after.
 	///
+	/// Returns `true` if the notification was queued for sending.
+	///
 	/// The protocol name is expected to be checked ahead of calling this method. It is a logic
 	/// error to send a notification using an unknown protocol.
 	///
 	/// This method will be removed in a future version.
-	pub fn send_sync_notification(&self, message: impl Into<Vec<u8>>) {
+	pub fn send_sync_notification(&self, message: impl Into<Vec<u8>>) -> bool {
 		let mut lock = self.inner.sync_channel.lock();
 
-		if let Some(tx) = lock.as_mut() {
-			let message = message.into();
-			let result = tx.try_send(NotificationsSinkMessage::Notification { message });
+		let Some(tx) = lock.as_mut() else { return false };
+		let message = message.into();
+		let result = tx.try_send(NotificationsSinkMessage::Notification { message });
 
-			if result.is_err() {
-				// Cloning the `mpsc::Sender` guarantees the allocation of an extra spot in the
-				// buffer, and therefore `try_send` will succeed.
-				let _result2 = tx.clone().try_send(NotificationsSinkMessage::ForceClose);
-				debug_assert!(_result2.map(|()| true).unwrap_or_else(|err| err.is_disconnected()));
+		if result.is_err() {
+			// Cloning the `mpsc::Sender` guarantees the allocation of an extra spot in the
+			// buffer, and therefore `try_send` will succeed.
+			let _result2 = tx.clone().try_send(NotificationsSinkMessage::ForceClose);
+			debug_assert!(_result2.map(|()| true).unwrap_or_else(|err| err.is_disconnected()));
 
-				// Destroy the sender in order to not send more `ForceClose` messages.
-				*lock = None;
-			}
+			// Destroy the sender in order to not send more `ForceClose` messages.
+			*lock = None;
+			return false
 		}
+
+		true
 	}
 
 	/// Wait until the remote is ready to accept a notification.
@@ -616,6 +652,16 @@ impl ConnectionHandler for NotifsHandler {
 					self.protocols[dial_upgrade_error.info].state =
 						State::Closed { pending_opening: false };
 
+					if let ConnectionHandlerUpgrErr::Upgrade(UpgradeError::Select(error)) =
+						dial_upgrade_error.error
+					{
+						self.events_queue.push_back(ConnectionHandlerEvent::Custom(
+							NotifsHandlerOut::ProtocolNegotiationFailed {
+								protocol_index: dial_upgrade_error.info,
+								reason: error.to_string(),
+							},
+						));
+					}
 					self.events_queue.push_back(ConnectionHandlerEvent::Custom(
 						NotifsHandlerOut::OpenResultErr { protocol_index: dial_upgrade_error.info },
 					));
@@ -749,22 +795,32 @@ impl ConnectionHandler for NotifsHandler {
 		// For each open substream, try send messages from `notifications_sink_rx` to the
 		// substream.
 		for protocol_index in 0..self.protocols.len() {
+			let protocol = &mut self.protocols[protocol_index];
 			if let State::Open {
 				notifications_sink_rx, out_substream: Some(out_substream), ..
-			} = &mut self.protocols[protocol_index].state
+			} = &mut protocol.state
 			{
+				// Wait for the bandwidth limit of the protocol before sending anything else.
+				if let Some(timer) = protocol.throttle_timer.as_mut() {
+					if timer.poll_unpin(cx).is_pending() {
+						continue
+					}
+					protocol.throttle_timer = None;
+				}
+
 				loop {
 					// Only proceed with `out_substream.poll_ready_unpin` if there is an element
 					// available in `notifications_sink_rx`. This avoids waking up the task when
 					// a substream is ready to send if there isn't actually something to send.
-					match Pin::new(&mut *notifications_sink_rx).as_mut().poll_peek(cx) {
+					let size = match Pin::new(&mut *notifications_sink_rx).as_mut().poll_peek(cx) {
 						Poll::Ready(Some(&NotificationsSinkMessage::ForceClose)) =>
 							return Poll::Ready(ConnectionHandlerEvent::Close(
 								NotifsHandlerError::SyncNotificationsClogged,
 							)),
-						Poll::Ready(Some(&NotificationsSinkMessage::Notification { .. })) => {},
+						Poll::Ready(Some(NotificationsSinkMessage::Notification { message })) =>
+							message.len(),
 						Poll::Ready(None) | Poll::Pending => break,
-					}
+					};
 
 					// Before we extract the element from `notifications_sink_rx`, check that the
 					// substream is ready to accept a message.
@@ -773,6 +829,20 @@ impl ConnectionHandler for NotifsHandler {
 						Poll::Pending => break,
 					}
 
+					// The notification stays in `notifications_sink_rx` until the bandwidth limit
+					// allows sending it.
+					if let Some(bandwidth_limit) = &protocol.config.bandwidth_limit {
+						if let Some(wait) = bandwidth_limit.lock().try_consume(Instant::now(), size)
+						{
+							let mut timer = Delay::new(wait);
+							if timer.poll_unpin(cx).is_pending() {
+								protocol.throttle_timer = Some(timer);
+								break
+							}
+							continue
+						}
+					}
+
 					// Now that the substream is ready for a message, grab what to send.
 					let message = match notifications_sink_rx.poll_next_unpin(cx) {
 						Poll::Ready(Some(NotificationsSinkMessage::Notification { message })) =>
@@ -878,7 +948,7 @@ pub mod tests {
 	};
 	use asynchronous_codec::Framed;
 	use libp2p::{
-		core::muxing::SubstreamBox,
+		core::{muxing::SubstreamBox, upgrade::NegotiationError},
 		swarm::{handler, ConnectionHandlerUpgrErr},
 		Multiaddr,
 	};
@@ -1075,9 +1145,11 @@ pub mod tests {
 				fallback_names: vec![],
 				handshake: Arc::new(RwLock::new(b"hello, world".to_vec())),
 				max_notification_size: u64::MAX,
+				bandwidth_limit: None,
 			},
 			in_upgrade: NotificationsIn::new("/foo", Vec::new(), u64::MAX),
 			state: State::Closed { pending_opening: false },
+			throttle_timer: None,
 		};
 
 		NotifsHandler {
@@ -1621,6 +1693,64 @@ pub mod tests {
 		.await;
 	}
 
+	#[tokio::test]
+	async fn notifications_wait_for_bandwidth_limit() {
+		let mut handler = notifs_handler();
+		handler.protocols[0].config.bandwidth_limit =
+			Some(Arc::new(Mutex::new(TokenBucket::new(10_000, Instant::now()))));
+		let (io, _io2) = MockSubstream::negotiated().await;
+		let codec = UviBytes::default();
+
+		let (async_tx, async_rx) = futures::channel::mpsc::channel(ASYNC_NOTIFICATIONS_BUFFER_SIZE);
+		let (sync_tx, sync_rx) = futures::channel::mpsc::channel(SYNC_NOTIFICATIONS_BUFFER_SIZE);
+		let notifications_sink = NotificationsSink {
+			inner: Arc::new(NotificationsSinkInner {
+				peer_id: PeerId::random(),
+				async_channel: FuturesMutex::new(async_tx),
+				sync_channel: Mutex::new(Some(sync_tx)),
+			}),
+			metrics: None,
+		};
+
+		handler.protocols[0].state = State::Open {
+			notifications_sink_rx: stream::select(async_rx.fuse(), sync_rx.fuse()).peekable(),
+			out_substream: Some(NotificationsOutSubstream::new(Framed::new(io, codec))),
+			in_substream: None,
+		};
+
+		notifications_sink.send_sync_notification(vec![0; 6_000]);
+		notifications_sink.send_sync_notification(vec![1; 6_000]);
+
+		// The first notification fits into the limit, the second one has to wait.
+		futures::future::poll_fn(|cx| {
+			assert!(handler.poll(cx).is_pending());
+			assert!(handler.protocols[0].throttle_timer.is_some());
+			let State::Open { notifications_sink_rx, .. } = &mut handler.protocols[0].state else {
+				panic!("protocol is open")
+			};
+			assert!(std::matches!(
+				Pin::new(notifications_sink_rx).poll_peek(cx),
+				Poll::Ready(Some(NotificationsSinkMessage::Notification { message }))
+					if message[0] == 1
+			));
+			Poll::Ready(())
+		})
+		.await;
+
+		tokio::time::sleep(Duration::from_millis(300)).await;
+
+		futures::future::poll_fn(|cx| {
+			assert!(handler.poll(cx).is_pending());
+			assert!(handler.protocols[0].throttle_timer.is_none());
+			let State::Open { notifications_sink_rx, .. } = &mut handler.protocols[0].state else {
+				panic!("protocol is open")
+			};
+			assert!(Pin::new(notifications_sink_rx).poll_peek(cx).is_pending());
+			Poll::Ready(())
+		})
+		.await;
+	}
+
 	#[tokio::test]
 	async fn close_desired_by_remote() {
 		let mut handler = notifs_handler();
@@ -1661,4 +1791,59 @@ pub mod tests {
 		})
 		.await;
 	}
+
+	#[test]
+	fn dial_negotiation_failure_is_reported() {
+		let mut handler = notifs_handler();
+
+		handler.on_behaviour_event(NotifsHandlerIn::Open { protocol_index: 0 });
+		assert!(std::matches!(
+			handler.events_queue.pop_front(),
+			Some(ConnectionHandlerEvent::OutboundSubstreamRequest { .. })
+		));
+
+		handler.on_connection_event(handler::ConnectionEvent::DialUpgradeError(
+			handler::DialUpgradeError {
+				info: 0,
+				error: ConnectionHandlerUpgrErr::Upgrade(UpgradeError::Select(
+					NegotiationError::Failed,
+				)),
+			},
+		));
+
+		assert!(std::matches!(
+			handler.events_queue.pop_front(),
+			Some(ConnectionHandlerEvent::Custom(NotifsHandlerOut::ProtocolNegotiationFailed {
+				protocol_index: 0,
+				..
+			}))
+		));
+		assert!(std::matches!(
+			handler.events_queue.pop_front(),
+			Some(ConnectionHandlerEvent::Custom(NotifsHandlerOut::OpenResultErr {
+				protocol_index: 0
+			}))
+		));
+		assert!(std::matches!(
+			handler.protocols[0].state,
+			State::Closed { pending_opening: false }
+		));
+	}
+
+	#[test]
+	fn dial_timeout_is_not_reported_as_negotiation_failure() {
+		let mut handler = notifs_handler();
+
+		handler.on_behaviour_event(NotifsHandlerIn::Open { protocol_index: 0 });
+		handler.events_queue.clear();
+		handler.on_connection_event(handler::ConnectionEvent::DialUpgradeError(
+			handler::DialUpgradeError { info: 0, error: ConnectionHandlerUpgrErr::Timeout },
+		));
+
+		assert!(std::matches!(
+			handler.events_queue.pop_front(),
+			Some(ConnectionHandlerEvent::Custom(NotifsHandlerOut::OpenResultErr { .. }))
+		));
+		assert!(handler.events_queue.is_empty());
+	}
 }
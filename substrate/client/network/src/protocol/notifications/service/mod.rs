@@ -38,7 +38,12 @@ use tokio_stream::wrappers::ReceiverStream;
 
 use sc_utils::mpsc::{tracing_unbounded, TracingUnboundedReceiver, TracingUnboundedSender};
 
-use std::{collections::HashMap, fmt::Debug, sync::Arc};
+use std::{
+	collections::{HashMap, HashSet},
+	fmt::Debug,
+	sync::Arc,
+	time::Instant,
+};
 
 pub(crate) mod metrics;
 
@@ -91,6 +96,11 @@ impl MessageSink for NotificationSink {
 			res
 		})
 	}
+
+	/// Check whether the substream to the peer associated with this [`MessageSink`] is still open.
+	fn is_connected(&self) -> bool {
+		self.lock().0.is_connected()
+	}
 }
 
 /// Inner notification event to deal with `NotificationsSinks` without exposing that
@@ -184,6 +194,9 @@ struct PeerContext {
 
 	/// Distributable notification sink.
 	shared_sink: NotificationSink,
+
+	/// When was the last notification successfully enqueued for the peer.
+	last_sent: Arc<Mutex<Option<Instant>>>,
 }
 
 /// Handle that is passed on to the notifications protocol.
@@ -238,7 +251,9 @@ impl NotificationService for NotificationHandle {
 				notification.len(),
 			);
 
-			let _ = info.sink.send_sync_notification(notification);
+			if info.sink.send_sync_notification(notification) {
+				*info.last_sent.lock() = Some(Instant::now());
+			}
 		}
 	}
 
@@ -249,7 +264,8 @@ impl NotificationService for NotificationHandle {
 		notification: Vec<u8>,
 	) -> Result<(), error::Error> {
 		let notification_len = notification.len();
-		let sink = &self.peers.get(&peer).ok_or_else(|| error::Error::PeerDoesntExist(*peer))?.sink;
+		let context = self.peers.get(&peer).ok_or_else(|| error::Error::PeerDoesntExist(*peer))?;
+		let sink = &context.sink;
 
 		sink.reserve_notification()
 			.await
@@ -262,6 +278,7 @@ impl NotificationService for NotificationHandle {
 					&self.protocol,
 					notification_len,
 				);
+				*context.last_sent.lock() = Some(Instant::now());
 				res
 			})
 	}
@@ -304,6 +321,7 @@ impl NotificationService for NotificationHandle {
 						PeerContext {
 							sink: sink.clone(),
 							shared_sink: Arc::new(Mutex::new((sink, self.protocol.clone()))),
+							last_sent: Default::default(),
 						},
 					);
 					return Some(NotificationEvent::NotificationStreamOpened {
@@ -362,6 +380,16 @@ impl NotificationService for NotificationHandle {
 			None => None,
 		}
 	}
+
+	/// Get the time when the last notification was successfully enqueued for `peer`.
+	fn last_sent(&self, peer: &PeerId) -> Option<Instant> {
+		self.peers.get(peer).and_then(|context| *context.last_sent.lock())
+	}
+
+	/// Get the peers with an open substream.
+	fn connected_peers(&self) -> Vec<PeerId> {
+		self.peers.keys().copied().collect()
+	}
 }
 
 /// Channel pair which allows `Notifications` to interact with a protocol.
@@ -409,8 +437,8 @@ pub(crate) struct ProtocolHandle {
 	/// Subscribers of the notification protocol.
 	subscribers: Subscribers,
 
-	/// Number of connected peers.
-	num_peers: usize,
+	/// Peers with an open substream, shared between all clones of the handle.
+	peers: Arc<Mutex<HashSet<PeerId>>>,
 
 	/// Delegate validation to `Peerset`.
 	delegate_to_peerset: bool,
@@ -427,7 +455,13 @@ pub(crate) enum ValidationCallResult {
 impl ProtocolHandle {
 	/// Create new [`ProtocolHandle`].
 	fn new(protocol: ProtocolName, subscribers: Subscribers) -> Self {
-		Self { protocol, subscribers, num_peers: 0usize, metrics: None, delegate_to_peerset: false }
+		Self {
+			protocol,
+			subscribers,
+			peers: Default::default(),
+			metrics: None,
+			delegate_to_peerset: false,
+		}
 	}
 
 	/// Set metrics.
@@ -526,6 +560,8 @@ impl ProtocolHandle {
 	) -> Result<(), ()> {
 		metrics::register_substream_opened(&self.metrics, &self.protocol);
 
+		self.peers.lock().insert(peer);
+
 		let mut subscribers = self.subscribers.lock();
 		log::trace!(target: LOG_TARGET, "{}: substream opened for {peer:?}", self.protocol);
 
@@ -540,7 +576,6 @@ impl ProtocolHandle {
 				})
 				.is_ok()
 		});
-		self.num_peers += 1;
 
 		Ok(())
 	}
@@ -549,6 +584,8 @@ impl ProtocolHandle {
 	pub fn report_substream_closed(&mut self, peer: PeerId) -> Result<(), ()> {
 		metrics::register_substream_closed(&self.metrics, &self.protocol);
 
+		self.peers.lock().remove(&peer);
+
 		let mut subscribers = self.subscribers.lock();
 		log::trace!(target: LOG_TARGET, "{}: substream closed for {peer:?}", self.protocol);
 
@@ -557,7 +594,6 @@ impl ProtocolHandle {
 				.unbounded_send(InnerNotificationEvent::NotificationStreamClosed { peer })
 				.is_ok()
 		});
-		self.num_peers -= 1;
 
 		Ok(())
 	}
@@ -613,7 +649,14 @@ impl ProtocolHandle {
 
 	/// Get the number of connected peers.
 	pub fn num_peers(&self) -> usize {
-		self.num_peers
+		self.peers.lock().len()
+	}
+
+	/// Get the set of peers with an open substream.
+	///
+	/// The set is kept up to date as substreams are opened and closed.
+	pub fn open_peers(&self) -> Arc<Mutex<HashSet<PeerId>>> {
+		self.peers.clone()
 	}
 }
 
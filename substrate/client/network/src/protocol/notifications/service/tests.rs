@@ -179,6 +179,134 @@ async fn send_async_notification() {
 	);
 }
 
+#[tokio::test]
+async fn last_sent_is_updated_on_send() {
+	let (proto, mut notif) = notification_service("/proto/1".into());
+	let (sink, mut async_rx, mut sync_rx) = NotificationsSink::new(PeerId::random());
+	let (mut handle, _stream) = proto.split();
+	let peer_id = PeerId::random();
+
+	// nothing known about a peer that never connected
+	assert_eq!(notif.last_sent(&peer_id), None);
+
+	// report that a substream has been opened
+	handle
+		.report_substream_opened(peer_id, Direction::Inbound, vec![1, 3, 3, 7], None, sink)
+		.unwrap();
+
+	if let Some(NotificationEvent::NotificationStreamOpened { peer, .. }) = notif.next_event().await
+	{
+		assert_eq!(peer_id, peer);
+	} else {
+		panic!("invalid event received");
+	}
+
+	// connected but nothing sent yet
+	assert_eq!(notif.last_sent(&peer_id), None);
+
+	let before = Instant::now();
+	notif.send_sync_notification(&peer_id, vec![1, 3, 3, 8]);
+	assert_eq!(
+		sync_rx.next().await,
+		Some(NotificationsSinkMessage::Notification { message: vec![1, 3, 3, 8] })
+	);
+	let sync_sent = notif.last_sent(&peer_id).unwrap();
+	assert!(sync_sent >= before);
+	assert!(sync_sent.elapsed() < std::time::Duration::from_secs(5));
+
+	notif.send_async_notification(&peer_id, vec![1, 3, 3, 9]).await.unwrap();
+	assert_eq!(
+		async_rx.next().await,
+		Some(NotificationsSinkMessage::Notification { message: vec![1, 3, 3, 9] })
+	);
+	let async_sent = notif.last_sent(&peer_id).unwrap();
+	assert!(async_sent >= sync_sent);
+
+	// a notification the sink drops doesn't count as sent
+	drop(sync_rx);
+	notif.send_sync_notification(&peer_id, vec![1, 3, 4, 0]);
+	assert_eq!(notif.last_sent(&peer_id), Some(async_sent));
+}
+
+#[tokio::test]
+async fn connected_peers_tracks_open_substreams() {
+	let (proto, mut notif) = notification_service("/proto/1".into());
+	let (mut handle, _stream) = proto.split();
+	let peers = [PeerId::random(), PeerId::random()];
+
+	assert!(notif.connected_peers().is_empty());
+
+	for peer_id in peers {
+		let (sink, _, _) = NotificationsSink::new(peer_id);
+		handle
+			.report_substream_opened(peer_id, Direction::Inbound, vec![1, 3, 3, 7], None, sink)
+			.unwrap();
+
+		if let Some(NotificationEvent::NotificationStreamOpened { peer, .. }) =
+			notif.next_event().await
+		{
+			assert_eq!(peer_id, peer);
+		} else {
+			panic!("invalid event received");
+		}
+	}
+
+	let mut connected = notif.connected_peers();
+	connected.sort();
+	let mut expected = peers.to_vec();
+	expected.sort();
+	assert_eq!(connected, expected);
+
+	// a cloned handle starts with the same peers
+	let mut cloned = notif.clone().unwrap();
+	let mut connected = cloned.connected_peers();
+	connected.sort();
+	assert_eq!(connected, expected);
+
+	handle.report_substream_closed(peers[0]).unwrap();
+
+	for notif in [&mut notif, &mut cloned] {
+		if let Some(NotificationEvent::NotificationStreamClosed { peer }) = notif.next_event().await
+		{
+			assert_eq!(peers[0], peer);
+		} else {
+			panic!("invalid event received");
+		}
+		assert_eq!(notif.connected_peers(), vec![peers[1]]);
+	}
+}
+
+#[tokio::test]
+async fn broadcast_notification_is_sent_to_every_connected_peer() {
+	let (proto, mut notif) = notification_service("/proto/1".into());
+	let (mut handle, _stream) = proto.split();
+	let mut sync_rxs = Vec::new();
+
+	for peer_id in [PeerId::random(), PeerId::random()] {
+		let (sink, _, sync_rx) = NotificationsSink::new(peer_id);
+		sync_rxs.push(sync_rx);
+		handle
+			.report_substream_opened(peer_id, Direction::Inbound, vec![1, 3, 3, 7], None, sink)
+			.unwrap();
+
+		if let Some(NotificationEvent::NotificationStreamOpened { peer, .. }) =
+			notif.next_event().await
+		{
+			assert_eq!(peer_id, peer);
+		} else {
+			panic!("invalid event received");
+		}
+	}
+
+	notif.broadcast_notification(vec![1, 3, 3, 8]);
+	for sync_rx in &mut sync_rxs {
+		assert_eq!(
+			sync_rx.next().await,
+			Some(NotificationsSinkMessage::Notification { message: vec![1, 3, 3, 8] })
+		);
+	}
+}
+
 #[tokio::test]
 async fn send_sync_notification_to_non_existent_peer() {
 	let (proto, notif) = notification_service("/proto/1".into());
@@ -690,6 +818,35 @@ async fn sending_notifications_using_notifications_sink_works() {
 	);
 }
 
+#[tokio::test]
+async fn message_sink_reports_closed_substream() {
+	let (proto, mut notif) = notification_service("/proto/1".into());
+	let (sink, async_rx, sync_rx) = NotificationsSink::new(PeerId::random());
+	let (mut handle, _stream) = proto.split();
+	let peer_id = PeerId::random();
+
+	handle
+		.report_substream_opened(peer_id, Direction::Inbound, vec![1, 3, 3, 7], None, sink)
+		.unwrap();
+
+	if let Some(NotificationEvent::NotificationStreamOpened { peer, .. }) =
+		notif.next_event().await
+	{
+		assert_eq!(peer_id, peer);
+	} else {
+		panic!("invalid event received");
+	}
+
+	let sink = notif.message_sink(&peer_id).unwrap();
+	assert!(sink.is_connected());
+
+	// dropping the receivers is what happens when the substream is closed
+	drop(async_rx);
+	drop(sync_rx);
+	assert!(!sink.is_connected());
+	assert!(sink.send_async_notification(vec![1, 3, 3, 7]).await.is_err());
+}
+
 #[test]
 fn try_to_get_notifications_sink_for_non_existent_peer() {
 	let (_proto, notif) = notification_service("/proto/1".into());
@@ -44,10 +44,12 @@
 use futures::{channel::oneshot, future::Either, FutureExt, StreamExt};
 use libp2p::PeerId;
 use log::{debug, error, trace, warn};
+use parking_lot::Mutex;
 use sc_utils::mpsc::{tracing_unbounded, TracingUnboundedReceiver, TracingUnboundedSender};
 use sp_arithmetic::traits::SaturatedConversion;
 use std::{
 	collections::{HashMap, HashSet},
+	sync::Arc,
 	time::{Duration, Instant},
 };
 use wasm_timer::Delay;
@@ -174,6 +176,9 @@ pub struct ProtocolHandle {
 	actions_tx: TracingUnboundedSender<Action>,
 	/// Connection events from `Notifications`. We prioritize them over actions.
 	events_tx: TracingUnboundedSender<Event>,
+	/// Reserved peers as requested through this handle, see
+	/// [`ProtocolHandle::current_reserved_peers`].
+	reserved_peers: Arc<Mutex<HashSet<PeerId>>>,
 }
 
 impl ProtocolHandle {
@@ -185,6 +190,10 @@ impl ProtocolHandle {
 	/// > **Note**: Keep in mind that the networking has to know an address for this node,
 	/// > otherwise it will not be able to connect to it.
 	pub fn add_reserved_peer(&self, peer_id: PeerId) {
+		// Keep the lock while sending, so the actions reach `ProtocolController` in the same
+		// order the copy was updated.
+		let mut reserved_peers = self.reserved_peers.lock();
+		reserved_peers.insert(peer_id);
 		let _ = self.actions_tx.unbounded_send(Action::AddReservedPeer(peer_id));
 	}
 
@@ -192,11 +201,15 @@ impl ProtocolHandle {
 	///
 	/// Has no effect if the node was not a reserved peer.
 	pub fn remove_reserved_peer(&self, peer_id: PeerId) {
+		let mut reserved_peers = self.reserved_peers.lock();
+		reserved_peers.remove(&peer_id);
 		let _ = self.actions_tx.unbounded_send(Action::RemoveReservedPeer(peer_id));
 	}
 
 	/// Set reserved peers to the new set.
 	pub fn set_reserved_peers(&self, peer_ids: HashSet<PeerId>) {
+		let mut reserved_peers = self.reserved_peers.lock();
+		*reserved_peers = peer_ids.clone();
 		let _ = self.actions_tx.unbounded_send(Action::SetReservedPeers(peer_ids));
 	}
 
@@ -217,6 +230,14 @@ impl ProtocolHandle {
 		let _ = self.actions_tx.unbounded_send(Action::GetReservedPeers(pending_response));
 	}
 
+	/// Get the reserved peers without a round trip to [`ProtocolController`].
+	///
+	/// Reflects every update made through a [`ProtocolHandle`] right away, even if
+	/// [`ProtocolController`] has not processed it yet.
+	pub fn current_reserved_peers(&self) -> HashSet<PeerId> {
+		self.reserved_peers.lock().clone()
+	}
+
 	/// Notify about incoming connection. [`ProtocolController`] will either accept or reject it.
 	pub fn incoming_connection(&self, peer_id: PeerId, incoming_index: IncomingIndex) {
 		let _ = self
@@ -302,7 +323,11 @@ impl ProtocolController {
 	) -> (ProtocolHandle, ProtocolController) {
 		let (actions_tx, actions_rx) = tracing_unbounded("mpsc_api_protocol", 10_000);
 		let (events_tx, events_rx) = tracing_unbounded("mpsc_notifications_protocol", 10_000);
-		let handle = ProtocolHandle { actions_tx, events_tx };
+		let handle = ProtocolHandle {
+			actions_tx,
+			events_tx,
+			reserved_peers: Arc::new(Mutex::new(config.reserved_nodes.clone())),
+		};
 		peer_store.register_protocol(handle.clone());
 		let reserved_nodes =
 			config.reserved_nodes.iter().map(|p| (*p, PeerState::NotConnected)).collect();
@@ -862,6 +887,8 @@ mod tests {
 			fn set_peer_role(&mut self, peer_id: &PeerId, role: ObservedRole);
 			fn report_peer(&mut self, peer_id: PeerId, change: ReputationChange);
 			fn peer_reputation(&self, peer_id: &PeerId) -> i32;
+			fn pin_reputation(&mut self, peer_id: PeerId, value: i32);
+			fn unpin_reputation(&mut self, peer_id: PeerId);
 			fn peer_role(&self, peer_id: &PeerId) -> Option<ObservedRole>;
 			fn outgoing_candidates<'a>(&self, count: usize, ignored: HashSet<&'a PeerId>) -> Vec<PeerId>;
 		}
@@ -29,18 +29,21 @@
 //! followed with the response.
 //!
 //! - Requests have a certain time limit before they time out. This time includes the time it
-//! takes to send/receive the request and response.
+//! takes to send/receive the request and response. Individual requests can be given a shorter
+//! time limit than the one of their protocol.
 //!
 //! - If provided, a ["requests processing"](ProtocolConfig::inbound_queue) channel
 //! is used to handle incoming requests.
 
 use crate::{
+	bandwidth_limit::TokenBucket,
 	peer_store::{PeerStoreProvider, BANNED_THRESHOLD},
 	types::ProtocolName,
 	ReputationChange,
 };
 
 use futures::{channel::oneshot, prelude::*};
+use futures_timer::Delay;
 use libp2p::{
 	core::{Endpoint, Multiaddr},
 	request_response::{self, Behaviour, Codec, Message, ProtocolSupport, ResponseChannel},
@@ -54,7 +57,7 @@ use libp2p::{
 };
 
 use std::{
-	collections::{hash_map::Entry, HashMap},
+	collections::{hash_map::Entry, HashMap, HashSet, VecDeque},
 	io, iter,
 	pin::Pin,
 	task::{Context, Poll},
@@ -73,12 +76,28 @@ pub enum RequestFailure {
 	UnknownProtocol,
 	#[error("Remote has closed the substream before answering, thereby signaling that it considers the request as valid, but refused to answer it.")]
 	Refused,
+	/// The receiver of the response was dropped before the response arrived.
+	///
+	/// The response is discarded, but still counted in the
+	/// `substrate_sub_libp2p_requests_out_failure_total` metric with the `obsolete` reason.
 	#[error("The remote replied, but the local node is no longer interested in the response.")]
 	Obsolete,
 	#[error("Problem on the network: {0}")]
 	Network(OutboundFailure),
 }
 
+/// Error in a request whose response is decoded, see
+/// [`NetworkRequest::request_typed`](crate::NetworkRequest::request_typed).
+#[derive(Debug, thiserror::Error)]
+pub enum RequestError {
+	/// The request itself failed.
+	#[error(transparent)]
+	NetworkError(#[from] RequestFailure),
+	/// The remote replied with a response that does not decode.
+	#[error("Response could not be decoded: {0}")]
+	InvalidResponse(#[source] codec::Error),
+}
+
 /// Configuration for a single request-response protocol.
 #[derive(Debug, Clone)]
 pub struct ProtocolConfig {
@@ -126,6 +145,13 @@ pub struct ProtocolConfig {
 	/// advertise support for this protocol, but any incoming request will lead to an error being
 	/// sent back.
 	pub inbound_queue: Option<async_channel::Sender<IncomingRequest>>,
+
+	/// Maximum rate, in bytes per second, at which requests and responses of this protocol are
+	/// sent.
+	///
+	/// Requests and responses exceeding the rate are delayed, up to one second worth of bytes can
+	/// be sent in a burst. `None` for no limit.
+	pub max_bandwidth_bps: Option<u64>,
 }
 
 /// A single request received by a peer on a request-response protocol.
@@ -234,6 +260,33 @@ pub enum Event {
 	},
 }
 
+/// A request or response waiting for the bandwidth limit of its protocol.
+enum ThrottledMessage {
+	Request {
+		target: PeerId,
+		request: Vec<u8>,
+		timeout: Option<Duration>,
+		pending_response: oneshot::Sender<Result<Vec<u8>, RequestFailure>>,
+		queued_at: Instant,
+	},
+	Response {
+		peer: PeerId,
+		request_id: RequestId,
+		inner_channel: ResponseChannel<Result<Vec<u8>, ()>>,
+		payload: Vec<u8>,
+		sent_feedback: Option<oneshot::Sender<()>>,
+	},
+}
+
+impl ThrottledMessage {
+	fn len(&self) -> usize {
+		match self {
+			Self::Request { request, .. } => request.len(),
+			Self::Response { payload, .. } => payload.len(),
+		}
+	}
+}
+
 /// Combination of a protocol name and a request id.
 ///
 /// Uniquely identifies an inbound or outbound request among all handled protocols. Note however
@@ -267,6 +320,14 @@ pub struct RequestResponsesBehaviour {
 	pending_requests:
 		HashMap<ProtocolRequestId, (Instant, oneshot::Sender<Result<Vec<u8>, RequestFailure>>)>,
 
+	/// Fire once the timeout of a pending request that was sent with its own timeout elapses.
+	request_deadlines:
+		stream::FuturesUnordered<Pin<Box<dyn Future<Output = (PeerId, ProtocolRequestId)> + Send>>>,
+
+	/// Requests that hit their own timeout, but are still pending in their request-response
+	/// [`Behaviour`]. Their eventual response or failure is discarded.
+	timed_out_requests: HashSet<ProtocolRequestId>,
+
 	/// Whenever an incoming request arrives, a `Future` is added to this list and will yield the
 	/// start time and the response to send back to the remote.
 	pending_responses: stream::FuturesUnordered<
@@ -282,6 +343,15 @@ pub struct RequestResponsesBehaviour {
 
 	/// Primarily used to get a reputation of a node.
 	peer_store: Box<dyn PeerStoreProvider>,
+
+	/// Token buckets of the protocols with a [`ProtocolConfig::max_bandwidth_bps`].
+	bandwidth_limits: HashMap<ProtocolName, TokenBucket>,
+
+	/// Requests and responses waiting for the bandwidth limit of their protocol, oldest first.
+	throttled: HashMap<ProtocolName, VecDeque<ThrottledMessage>>,
+
+	/// Fires once the oldest throttled message of some protocol can be sent.
+	throttle_timer: Option<Delay>,
 }
 
 /// Generated by the response builder and waiting to be processed.
@@ -301,6 +371,7 @@ impl RequestResponsesBehaviour {
 		peer_store: Box<dyn PeerStoreProvider>,
 	) -> Result<Self, RegisterError> {
 		let mut protocols = HashMap::new();
+		let mut bandwidth_limits = HashMap::new();
 		for protocol in list {
 			let mut cfg = Config::default();
 			cfg.set_connection_keep_alive(Duration::from_secs(10));
@@ -323,6 +394,11 @@ impl RequestResponsesBehaviour {
 				cfg,
 			);
 
+			if let Some(rate) = protocol.max_bandwidth_bps {
+				bandwidth_limits
+					.insert(protocol.name.clone(), TokenBucket::new(rate, Instant::now()));
+			}
+
 			match protocols.entry(protocol.name) {
 				Entry::Vacant(e) => e.insert((rq_rp, protocol.inbound_queue)),
 				Entry::Occupied(e) => return Err(RegisterError::DuplicateProtocol(e.key().clone())),
@@ -332,10 +408,15 @@ impl RequestResponsesBehaviour {
 		Ok(Self {
 			protocols,
 			pending_requests: Default::default(),
+			request_deadlines: Default::default(),
+			timed_out_requests: Default::default(),
 			pending_responses: Default::default(),
 			pending_responses_arrival_time: Default::default(),
 			send_feedback: Default::default(),
 			peer_store,
+			bandwidth_limits,
+			throttled: Default::default(),
+			throttle_timer: None,
 		})
 	}
 
@@ -344,12 +425,17 @@ impl RequestResponsesBehaviour {
 	/// If there is no established connection to the target peer, the behavior is determined by the
 	/// choice of `connect`.
 	///
+	/// If `timeout` is `Some`, the request fails with [`OutboundFailure::Timeout`] once it elapses,
+	/// even if the [`ProtocolConfig::request_timeout`] of the protocol is longer. A longer
+	/// `timeout` has no effect, as the protocol times out the request first.
+	///
 	/// An error is returned if the protocol doesn't match one that has been registered.
 	pub fn send_request(
 		&mut self,
 		target: &PeerId,
 		protocol_name: &str,
 		request: Vec<u8>,
+		timeout: Option<Duration>,
 		pending_response: oneshot::Sender<Result<Vec<u8>, RequestFailure>>,
 		connect: IfDisconnected,
 	) {
@@ -357,12 +443,27 @@ impl RequestResponsesBehaviour {
 
 		if let Some((protocol, _)) = self.protocols.get_mut(protocol_name) {
 			if protocol.is_connected(target) || connect.should_connect() {
-				let request_id = protocol.send_request(target, request);
-				let prev_req_id = self.pending_requests.insert(
-					(protocol_name.to_string().into(), request_id).into(),
-					(Instant::now(), pending_response),
-				);
-				debug_assert!(prev_req_id.is_none(), "Expect request id to be unique.");
+				let protocol_name = ProtocolName::from(protocol_name.to_string());
+				if self.is_throttled(&protocol_name, request.len()) {
+					self.throttled.entry(protocol_name).or_default().push_back(
+						ThrottledMessage::Request {
+							target: *target,
+							request,
+							timeout,
+							pending_response,
+							queued_at: Instant::now(),
+						},
+					);
+				} else {
+					self.send_request_now(
+						protocol_name,
+						target,
+						request,
+						timeout,
+						pending_response,
+						Instant::now(),
+					);
+				}
 			} else if pending_response.send(Err(RequestFailure::NotConnected)).is_err() {
 				log::debug!(
 					target: "sub-libp2p",
@@ -380,6 +481,137 @@ impl RequestResponsesBehaviour {
 			);
 		}
 	}
+
+	/// Whether a message of `bytes` on `protocol` has to wait for its bandwidth limit.
+	///
+	/// If not, the bytes are accounted for, so the message must be sent right away.
+	fn is_throttled(&mut self, protocol: &ProtocolName, bytes: usize) -> bool {
+		let Some(bucket) = self.bandwidth_limits.get_mut(protocol) else { return false };
+
+		// Keep the order of messages that are already waiting.
+		self.throttled.get(protocol).map_or(false, |queue| !queue.is_empty()) ||
+			bucket.try_consume(Instant::now(), bytes).is_some()
+	}
+
+	/// Pass a request down to the request-response [`Behaviour`] of `protocol_name`.
+	///
+	/// `timeout` counts from `started`, so the time spent waiting for the bandwidth limit is
+	/// included.
+	fn send_request_now(
+		&mut self,
+		protocol_name: ProtocolName,
+		target: &PeerId,
+		request: Vec<u8>,
+		timeout: Option<Duration>,
+		pending_response: oneshot::Sender<Result<Vec<u8>, RequestFailure>>,
+		started: Instant,
+	) {
+		if let Some((protocol, _)) = self.protocols.get_mut(&protocol_name) {
+			let request_id = protocol.send_request(target, request);
+			let id = ProtocolRequestId::from((protocol_name, request_id));
+			if let Some(timeout) = timeout {
+				let target = *target;
+				let deadline = Delay::new(timeout.saturating_sub(started.elapsed()));
+				let id = id.clone();
+				self.request_deadlines.push(Box::pin(deadline.map(move |()| (target, id))));
+			}
+			let prev_req_id = self.pending_requests.insert(id, (started, pending_response));
+			debug_assert!(prev_req_id.is_none(), "Expect request id to be unique.");
+		}
+	}
+
+	/// Pass a response down to the request-response [`Behaviour`] of `protocol_name`.
+	fn send_response_now(
+		&mut self,
+		protocol_name: ProtocolName,
+		peer: PeerId,
+		request_id: RequestId,
+		inner_channel: ResponseChannel<Result<Vec<u8>, ()>>,
+		payload: Vec<u8>,
+		sent_feedback: Option<oneshot::Sender<()>>,
+	) {
+		if let Some((protocol, _)) = self.protocols.get_mut(&protocol_name) {
+			log::trace!(target: "sub-libp2p", "send response to {peer} ({protocol_name:?}), {} bytes", payload.len());
+
+			if protocol.send_response(inner_channel, Ok(payload)).is_err() {
+				// Note: Failure is handled further below when receiving
+				// `InboundFailure` event from request-response [`Behaviour`].
+				log::debug!(
+					target: "sub-libp2p",
+					"Failed to send response for {:?} on protocol {:?} due to a \
+					 timeout or due to the connection to the peer being closed. \
+					 Dropping response",
+					request_id, protocol_name,
+				);
+			} else if let Some(sent_feedback) = sent_feedback {
+				self.send_feedback.insert((protocol_name, request_id).into(), sent_feedback);
+			}
+		}
+	}
+
+	/// Send the throttled requests and responses that fit into the bandwidth limit of their
+	/// protocol again, and arm [`Self::throttle_timer`] for the remaining ones.
+	fn send_throttled(&mut self, cx: &mut Context) {
+		loop {
+			let now = Instant::now();
+			let mut ready = Vec::new();
+			let mut next_wake: Option<Duration> = None;
+			for (protocol, queue) in self.throttled.iter_mut() {
+				let Some(bucket) = self.bandwidth_limits.get_mut(protocol) else { continue };
+				while let Some(message) = queue.front() {
+					if let Some(wait) = bucket.try_consume(now, message.len()) {
+						next_wake = Some(next_wake.map_or(wait, |next| next.min(wait)));
+						break
+					}
+					ready.push((protocol.clone(), queue.pop_front().expect("checked above; qed")));
+				}
+			}
+			self.throttled.retain(|_, queue| !queue.is_empty());
+
+			for (protocol, message) in ready {
+				match message {
+					ThrottledMessage::Request {
+						target,
+						request,
+						timeout,
+						pending_response,
+						queued_at,
+					} => self.send_request_now(
+						protocol,
+						&target,
+						request,
+						timeout,
+						pending_response,
+						queued_at,
+					),
+					ThrottledMessage::Response {
+						peer,
+						request_id,
+						inner_channel,
+						payload,
+						sent_feedback,
+					} => self.send_response_now(
+						protocol,
+						peer,
+						request_id,
+						inner_channel,
+						payload,
+						sent_feedback,
+					),
+				}
+			}
+
+			let Some(wait) = next_wake else {
+				self.throttle_timer = None;
+				return
+			};
+			let mut timer = Delay::new(wait);
+			if timer.poll_unpin(cx).is_pending() {
+				self.throttle_timer = Some(timer);
+				return
+			}
+		}
+	}
 }
 
 impl NetworkBehaviour for RequestResponsesBehaviour {
@@ -552,6 +784,43 @@ impl NetworkBehaviour for RequestResponsesBehaviour {
 		params: &mut impl PollParameters,
 	) -> Poll<ToSwarm<Self::OutEvent, THandlerInEvent<Self>>> {
 		'poll_all: loop {
+			// Throttled messages are retried on every call, the timer only makes sure that there
+			// is one once the bandwidth limit allows sending them.
+			if let Some(timer) = self.throttle_timer.as_mut() {
+				if timer.poll_unpin(cx).is_ready() {
+					self.throttle_timer = None;
+				}
+			}
+			self.send_throttled(cx);
+
+			// Fail the requests whose own timeout elapsed before the one of their protocol.
+			while let Poll::Ready(Some((peer, id))) = self.request_deadlines.poll_next_unpin(cx) {
+				let Some((started, pending_response)) = self.pending_requests.remove(&id) else {
+					continue
+				};
+				if pending_response
+					.send(Err(RequestFailure::Network(OutboundFailure::Timeout)))
+					.is_err()
+				{
+					log::debug!(
+						target: "sub-libp2p",
+						"Request with id {:?} timed out. At the same time local \
+						 node is no longer interested in the result.",
+						id.request_id,
+					);
+				}
+
+				let out = Event::RequestFinished {
+					peer,
+					protocol: id.protocol.clone(),
+					duration: started.elapsed(),
+					result: Err(RequestFailure::Network(OutboundFailure::Timeout)),
+				};
+				self.timed_out_requests.insert(id);
+
+				return Poll::Ready(ToSwarm::GenerateEvent(out))
+			}
+
 			// Poll to see if any response is ready to be sent back.
 			while let Poll::Ready(Some(outcome)) = self.pending_responses.poll_next_unpin(cx) {
 				let RequestProcessingOutcome {
@@ -568,23 +837,25 @@ impl NetworkBehaviour for RequestResponsesBehaviour {
 				};
 
 				if let Ok(payload) = result {
-					if let Some((protocol, _)) = self.protocols.get_mut(&*protocol_name) {
-						log::trace!(target: "sub-libp2p", "send response to {peer} ({protocol_name:?}), {} bytes", payload.len());
-
-						if protocol.send_response(inner_channel, Ok(payload)).is_err() {
-							// Note: Failure is handled further below when receiving
-							// `InboundFailure` event from request-response [`Behaviour`].
-							log::debug!(
-								target: "sub-libp2p",
-								"Failed to send response for {:?} on protocol {:?} due to a \
-								 timeout or due to the connection to the peer being closed. \
-								 Dropping response",
-								request_id, protocol_name,
-							);
-						} else if let Some(sent_feedback) = sent_feedback {
-							self.send_feedback
-								.insert((protocol_name, request_id).into(), sent_feedback);
-						}
+					if self.is_throttled(&protocol_name, payload.len()) {
+						self.throttled.entry(protocol_name).or_default().push_back(
+							ThrottledMessage::Response {
+								peer,
+								request_id,
+								inner_channel,
+								payload,
+								sent_feedback,
+							},
+						);
+					} else {
+						self.send_response_now(
+							protocol_name,
+							peer,
+							request_id,
+							inner_channel,
+							payload,
+							sent_feedback,
+						);
 					}
 				}
 
@@ -710,6 +981,11 @@ impl NetworkBehaviour for RequestResponsesBehaviour {
 										.map_err(|_| RequestFailure::Obsolete);
 									(started, delivered)
 								},
+								// Already reported when its own timeout elapsed.
+								None if self
+									.timed_out_requests
+									.remove(&(protocol.clone(), request_id).into()) =>
+									continue,
 								None => {
 									log::warn!(
 										target: "sub-libp2p",
@@ -756,6 +1032,10 @@ impl NetworkBehaviour for RequestResponsesBehaviour {
 									}
 									started
 								},
+								None if self
+									.timed_out_requests
+									.remove(&(protocol.clone(), request_id).into()) =>
+									continue,
 								None => {
 									log::warn!(
 										target: "sub-libp2p",
@@ -825,6 +1105,9 @@ impl NetworkBehaviour for RequestResponsesBehaviour {
 				}
 			}
 
+			// Arm the timer for messages throttled while polling.
+			self.send_throttled(cx);
+
 			break Poll::Pending
 		}
 	}
@@ -1059,6 +1342,7 @@ mod tests {
 					max_response_size: 1024 * 1024,
 					request_timeout: Duration::from_secs(30),
 					inbound_queue: Some(tx),
+					max_bandwidth_bps: None,
 				};
 
 				build_swarm(iter::once(protocol_config))
@@ -1104,6 +1388,7 @@ mod tests {
 							&peer_id,
 							protocol_name,
 							b"this is a request".to_vec(),
+							None,
 							sender,
 							IfDisconnected::ImmediateError,
 						);
@@ -1122,6 +1407,192 @@ mod tests {
 		});
 	}
 
+	#[test]
+	fn response_to_dropped_receiver_is_reported_obsolete() {
+		let protocol_name = "/test/req-resp/1";
+		let mut pool = LocalPool::new();
+
+		// Build swarms whose behaviour is [`RequestResponsesBehaviour`].
+		let mut swarms = (0..2)
+			.map(|_| {
+				let (tx, mut rx) = async_channel::bounded::<IncomingRequest>(64);
+
+				pool.spawner()
+					.spawn_obj(
+						async move {
+							while let Some(rq) = rx.next().await {
+								let _ = rq.pending_response.send(super::OutgoingResponse {
+									result: Ok(b"this is a response".to_vec()),
+									reputation_changes: Vec::new(),
+									sent_feedback: None,
+								});
+							}
+						}
+						.boxed()
+						.into(),
+					)
+					.unwrap();
+
+				let protocol_config = ProtocolConfig {
+					name: From::from(protocol_name),
+					fallback_names: Vec::new(),
+					max_request_size: 1024,
+					max_response_size: 1024 * 1024,
+					request_timeout: Duration::from_secs(30),
+					inbound_queue: Some(tx),
+					max_bandwidth_bps: None,
+				};
+
+				build_swarm(iter::once(protocol_config))
+			})
+			.collect::<Vec<_>>();
+
+		// Ask `swarm[0]` to dial `swarm[1]`. There isn't any discovery mechanism in place in
+		// this test, so they wouldn't connect to each other.
+		{
+			let dial_addr = swarms[1].1.clone();
+			Swarm::dial(&mut swarms[0].0, dial_addr).unwrap();
+		}
+
+		let (mut swarm, _) = swarms.remove(0);
+		// Running `swarm[0]` in the background.
+		pool.spawner()
+			.spawn_obj({
+				async move {
+					loop {
+						let _ = swarm.select_next_some().await;
+					}
+				}
+				.boxed()
+				.into()
+			})
+			.unwrap();
+
+		// Remove and run the remaining swarm.
+		let (mut swarm, _) = swarms.remove(0);
+		pool.run_until(async move {
+			loop {
+				match swarm.select_next_some().await {
+					SwarmEvent::ConnectionEstablished { peer_id, .. } => {
+						let (sender, receiver) = oneshot::channel();
+						swarm.behaviour_mut().send_request(
+							&peer_id,
+							protocol_name,
+							b"this is a request".to_vec(),
+							None,
+							sender,
+							IfDisconnected::ImmediateError,
+						);
+						// Not interested in the response anymore.
+						drop(receiver);
+					},
+					SwarmEvent::Behaviour(Event::RequestFinished { result, .. }) => {
+						assert!(matches!(result, Err(RequestFailure::Obsolete)));
+						break
+					},
+					_ => {},
+				}
+			}
+		});
+	}
+
+	#[test]
+	fn request_with_own_timeout_times_out_before_protocol() {
+		let protocol_name = "/test/req-resp/1";
+		let mut pool = LocalPool::new();
+
+		// Build swarms whose behaviour is [`RequestResponsesBehaviour`].
+		let mut swarms = (0..2)
+			.map(|_| {
+				let (tx, mut rx) = async_channel::bounded::<IncomingRequest>(64);
+
+				pool.spawner()
+					.spawn_obj(
+						async move {
+							// Hold on to the requests without ever answering them.
+							let mut unanswered = Vec::new();
+							while let Some(rq) = rx.next().await {
+								unanswered.push(rq.pending_response);
+							}
+						}
+						.boxed()
+						.into(),
+					)
+					.unwrap();
+
+				let protocol_config = ProtocolConfig {
+					name: From::from(protocol_name),
+					fallback_names: Vec::new(),
+					max_request_size: 1024,
+					max_response_size: 1024 * 1024,
+					request_timeout: Duration::from_secs(30),
+					inbound_queue: Some(tx),
+					max_bandwidth_bps: None,
+				};
+
+				build_swarm(iter::once(protocol_config))
+			})
+			.collect::<Vec<_>>();
+
+		// Ask `swarm[0]` to dial `swarm[1]`. There isn't any discovery mechanism in place in
+		// this test, so they wouldn't connect to each other.
+		{
+			let dial_addr = swarms[1].1.clone();
+			Swarm::dial(&mut swarms[0].0, dial_addr).unwrap();
+		}
+
+		let (mut swarm, _) = swarms.remove(0);
+		// Running `swarm[0]` in the background.
+		pool.spawner()
+			.spawn_obj({
+				async move {
+					loop {
+						let _ = swarm.select_next_some().await;
+					}
+				}
+				.boxed()
+				.into()
+			})
+			.unwrap();
+
+		// Remove and run the remaining swarm.
+		let (mut swarm, _) = swarms.remove(0);
+		pool.run_until(async move {
+			let mut response_receiver = None;
+
+			loop {
+				match swarm.select_next_some().await {
+					SwarmEvent::ConnectionEstablished { peer_id, .. } => {
+						let (sender, receiver) = oneshot::channel();
+						swarm.behaviour_mut().send_request(
+							&peer_id,
+							protocol_name,
+							b"this is a request".to_vec(),
+							Some(Duration::from_millis(100)),
+							sender,
+							IfDisconnected::ImmediateError,
+						);
+						response_receiver = Some(receiver);
+					},
+					SwarmEvent::Behaviour(Event::RequestFinished { result, duration, .. }) => {
+						assert!(matches!(
+							result,
+							Err(RequestFailure::Network(OutboundFailure::Timeout))
+						));
+						assert!(duration < Duration::from_secs(30));
+						break
+					},
+					_ => {},
+				}
+			}
+
+			assert!(matches!(
+				response_receiver.unwrap().await.unwrap(),
+				Err(RequestFailure::Network(OutboundFailure::Timeout))
+			));
+		});
+	}
+
 	#[test]
 	fn max_response_size_exceeded() {
 		let protocol_name = "/test/req-resp/1";
@@ -1156,6 +1627,7 @@ mod tests {
 					max_response_size: 8, // <-- important for the test
 					request_timeout: Duration::from_secs(30),
 					inbound_queue: Some(tx),
+					max_bandwidth_bps: None,
 				};
 
 				build_swarm(iter::once(protocol_config))
@@ -1203,6 +1675,7 @@ mod tests {
 							&peer_id,
 							protocol_name,
 							b"this is a request".to_vec(),
+							None,
 							sender,
 							IfDisconnected::ImmediateError,
 						);
@@ -1249,6 +1722,7 @@ mod tests {
 					max_response_size: 1024 * 1024,
 					request_timeout: Duration::from_secs(30),
 					inbound_queue: None,
+					max_bandwidth_bps: None,
 				},
 				ProtocolConfig {
 					name: From::from(protocol_name_2),
@@ -1257,6 +1731,7 @@ mod tests {
 					max_response_size: 1024 * 1024,
 					request_timeout: Duration::from_secs(30),
 					inbound_queue: None,
+					max_bandwidth_bps: None,
 				},
 			];
 
@@ -1275,6 +1750,7 @@ mod tests {
 					max_response_size: 1024 * 1024,
 					request_timeout: Duration::from_secs(30),
 					inbound_queue: Some(tx_1),
+					max_bandwidth_bps: None,
 				},
 				ProtocolConfig {
 					name: From::from(protocol_name_2),
@@ -1283,6 +1759,7 @@ mod tests {
 					max_response_size: 1024 * 1024,
 					request_timeout: Duration::from_secs(30),
 					inbound_queue: Some(tx_2),
+					max_bandwidth_bps: None,
 				},
 			];
 
@@ -1361,6 +1838,7 @@ mod tests {
 							&peer_id,
 							protocol_name_1,
 							b"this is a request".to_vec(),
+							None,
 							sender_1,
 							IfDisconnected::ImmediateError,
 						);
@@ -1368,6 +1846,7 @@ mod tests {
 							&peer_id,
 							protocol_name_2,
 							b"this is a request".to_vec(),
+							None,
 							sender_2,
 							IfDisconnected::ImmediateError,
 						);
@@ -1389,4 +1868,111 @@ mod tests {
 			assert_eq!(response_receiver_2.await.unwrap().unwrap(), b"this is a response");
 		});
 	}
+
+	#[test]
+	fn bandwidth_limit_only_throttles_its_protocol() {
+		let capped = "/test/capped/1";
+		let uncapped = "/test/uncapped/1";
+		let mut pool = LocalPool::new();
+
+		// Build swarms whose behaviour is [`RequestResponsesBehaviour`].
+		let mut swarms = (0..2)
+			.map(|_| {
+				let protocols =
+					[(capped, Some(10_000)), (uncapped, None)].map(|(name, max_bandwidth_bps)| {
+						let (tx, mut rx) = async_channel::bounded::<IncomingRequest>(64);
+
+						pool.spawner()
+							.spawn_obj(
+								async move {
+									while let Some(rq) = rx.next().await {
+										let _ = rq.pending_response.send(super::OutgoingResponse {
+											result: Ok(b"ok".to_vec()),
+											reputation_changes: Vec::new(),
+											sent_feedback: None,
+										});
+									}
+								}
+								.boxed()
+								.into(),
+							)
+							.unwrap();
+
+						ProtocolConfig {
+							name: From::from(name),
+							fallback_names: Vec::new(),
+							max_request_size: 1024 * 1024,
+							max_response_size: 1024 * 1024,
+							request_timeout: Duration::from_secs(30),
+							inbound_queue: Some(tx),
+							max_bandwidth_bps,
+						}
+					});
+
+				build_swarm(protocols.into_iter())
+			})
+			.collect::<Vec<_>>();
+
+		// Ask `swarm[0]` to dial `swarm[1]`. There isn't any discovery mechanism in place in
+		// this test, so they wouldn't connect to each other.
+		{
+			let dial_addr = swarms[1].1.clone();
+			Swarm::dial(&mut swarms[0].0, dial_addr).unwrap();
+		}
+
+		let (mut swarm, _) = swarms.remove(0);
+		// Running `swarm[0]` in the background.
+		pool.spawner()
+			.spawn_obj({
+				async move {
+					loop {
+						let _ = swarm.select_next_some().await;
+					}
+				}
+				.boxed()
+				.into()
+			})
+			.unwrap();
+
+		// Remove and run the remaining swarm.
+		let (mut swarm, _) = swarms.remove(0);
+		let finished = pool.run_until(async move {
+			let started = Instant::now();
+			let mut receivers = Vec::new();
+			let mut finished = HashMap::<ProtocolName, Vec<Duration>>::new();
+			loop {
+				match swarm.select_next_some().await {
+					SwarmEvent::ConnectionEstablished { peer_id, .. } =>
+						for protocol in [capped, uncapped] {
+							for _ in 0..5 {
+								let (sender, receiver) = oneshot::channel();
+								swarm.behaviour_mut().send_request(
+									&peer_id,
+									protocol,
+									vec![0; 5_000],
+									None,
+									sender,
+									IfDisconnected::ImmediateError,
+								);
+								receivers.push(receiver);
+							}
+						},
+					SwarmEvent::Behaviour(Event::RequestFinished { protocol, result, .. }) => {
+						result.unwrap();
+						finished.entry(protocol).or_default().push(started.elapsed());
+						if finished.values().map(Vec::len).sum::<usize>() == 10 {
+							break finished
+						}
+					},
+					_ => {},
+				}
+			}
+		});
+
+		let last =
+			|protocol: &'static str| *finished[&ProtocolName::from(protocol)].iter().max().unwrap();
+		// Two requests fit into the burst, the remaining three are sent half a second apart.
+		assert!(last(capped) >= Duration::from_secs(1));
+		assert!(last(uncapped) < Duration::from_secs(1));
+	}
 }
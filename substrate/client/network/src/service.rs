@@ -32,7 +32,7 @@ use crate::{
 	config::{parse_addr, FullNetworkConfiguration, MultiaddrWithPeerId, Params, TransportConfig},
 	discovery::DiscoveryConfig,
 	error::Error,
-	event::{DhtEvent, Event},
+	event::{DhtError, DhtEvent, Event},
 	network_state::{
 		NetworkState, NotConnectedPeer as NetworkStateNotConnectedPeer, Peer as NetworkStatePeer,
 	},
@@ -43,10 +43,10 @@ use crate::{
 	service::{
 		signature::{Signature, SigningError},
 		traits::{
-			NetworkDHTProvider, NetworkEventStream, NetworkNotification, NetworkPeers,
-			NetworkRequest, NetworkSigner, NetworkStateInfo, NetworkStatus, NetworkStatusProvider,
-			NotificationSender as NotificationSenderT, NotificationSenderError,
-			NotificationSenderReady as NotificationSenderReadyT,
+			ConnectivityReport, NetworkDHTProvider, NetworkEventStream, NetworkNotification,
+			NetworkPeers, NetworkRequest, NetworkSigner, NetworkStateInfo, NetworkStatus,
+			NetworkStatusProvider, NotificationSender as NotificationSenderT,
+			NotificationSenderError, NotificationSenderReady as NotificationSenderReadyT,
 		},
 	},
 	transport,
@@ -62,7 +62,7 @@ use libp2p::{
 	connection_limits::Exceeded,
 	core::{upgrade, ConnectedPoint, Endpoint},
 	identify::Info as IdentifyInfo,
-	kad::record::Key as KademliaKey,
+	kad::{record::Key as KademliaKey, Quorum},
 	multiaddr,
 	ping::Failure as PingFailure,
 	swarm::{
@@ -84,16 +84,17 @@ use sp_runtime::traits::Block as BlockT;
 
 use std::{
 	cmp,
-	collections::{HashMap, HashSet},
+	collections::{hash_map::Entry, HashMap, HashSet, VecDeque},
 	fs, iter,
 	marker::PhantomData,
 	num::NonZeroUsize,
 	pin::Pin,
 	str,
 	sync::{
-		atomic::{AtomicUsize, Ordering},
+		atomic::{AtomicBool, AtomicUsize, Ordering},
 		Arc,
 	},
+	time::{Duration, Instant},
 };
 
 pub use behaviour::{InboundFailure, OutboundFailure, ResponseFailure};
@@ -110,6 +111,8 @@ pub mod traits;
 pub struct NetworkService<B: BlockT + 'static, H: ExHashT> {
 	/// Number of peers we're connected to.
 	num_connected: Arc<AtomicUsize>,
+	/// Are we actively catching up with the chain?
+	is_major_syncing: Arc<AtomicBool>,
 	/// The local external addresses.
 	external_addresses: Arc<Mutex<HashSet<Multiaddr>>>,
 	/// Listen addresses. Do **NOT** include a trailing `/p2p/` with our `PeerId`.
@@ -132,6 +135,9 @@ pub struct NetworkService<B: BlockT + 'static, H: ExHashT> {
 	protocol_handles: Vec<protocol_controller::ProtocolHandle>,
 	/// Shortcut to sync protocol handle (`protocol_handles[0]`).
 	sync_protocol_handle: protocol_controller::ProtocolHandle,
+	/// Peers with an open substream, per notification protocol, indexed by `SetId`. Kept up to
+	/// date by the notification protocol handles owned by the worker.
+	open_peers: Vec<Arc<Mutex<HashSet<PeerId>>>>,
 	/// Marker to pin the `H` generic. Serves no purpose except to not break backwards
 	/// compatibility.
 	_marker: PhantomData<H>,
@@ -386,6 +392,7 @@ where
 			protocol_handles.clone(),
 			from_protocol_controllers,
 		)?;
+		let open_peers = notif_protocol_handles.iter().map(|handle| handle.open_peers()).collect();
 
 		// Build the swarm.
 		let (mut swarm, bandwidth): (Swarm<Behaviour<B>>, _) = {
@@ -403,6 +410,7 @@ where
 				);
 				config.with_dht_random_walk(network_config.enable_dht_random_walk);
 				config.allow_non_globals_in_dht(network_config.allow_non_globals_in_dht);
+				config.with_ip_preference(network_config.ip_preference);
 				config.use_kademlia_disjoint_query_paths(
 					network_config.kademlia_disjoint_query_paths,
 				);
@@ -512,12 +520,14 @@ where
 			external_addresses,
 			listen_addresses: listen_addresses.clone(),
 			num_connected: num_connected.clone(),
+			is_major_syncing: params.is_major_syncing,
 			local_peer_id,
 			local_identity,
 			to_worker,
 			notification_protocol_ids,
 			protocol_handles,
 			sync_protocol_handle,
+			open_peers,
 			peer_store_handle: params.peer_store.clone(),
 			_marker: PhantomData,
 			_block: Default::default(),
@@ -535,6 +545,10 @@ where
 			reported_invalid_boot_nodes: Default::default(),
 			peer_store_handle: params.peer_store,
 			notif_protocol_handles,
+			pending_get_values: HashMap::new(),
+			pending_put_values: HashMap::new(),
+			next_put_value_id: 0,
+			pending_dht_flushes: Vec::new(),
 			_marker: Default::default(),
 			_block: Default::default(),
 		})
@@ -542,8 +556,13 @@ where
 
 	/// High-level network status information.
 	pub fn status(&self) -> NetworkStatus {
+		let (num_connected_inbound, num_connected_outbound) =
+			self.num_connected_peers_by_direction();
+
 		NetworkStatus {
 			num_connected_peers: self.num_connected_peers(),
+			num_connected_inbound,
+			num_connected_outbound,
 			total_bytes_inbound: self.total_bytes_inbound(),
 			total_bytes_outbound: self.total_bytes_outbound(),
 		}
@@ -564,6 +583,24 @@ where
 		self.network_service.behaviour().user_protocol().num_sync_peers()
 	}
 
+	/// Returns the number of peers we're connected to, split into peers which dialed us and peers
+	/// we dialed.
+	pub fn num_connected_peers_by_direction(&self) -> (usize, usize) {
+		let behaviour = self.network_service.behaviour();
+		// A peer is listed once for every notification protocol open with it.
+		let open_peers: HashSet<_> = behaviour.user_protocol().open_peers().collect();
+		open_peers.into_iter().fold((0, 0), |(inbound, outbound), peer_id| {
+			match behaviour
+				.node(peer_id)
+				.and_then(|node| node.endpoint().map(|endpoint| endpoint.is_dialer()))
+			{
+				Some(true) => (inbound, outbound + 1),
+				Some(false) => (inbound + 1, outbound),
+				None => (inbound, outbound),
+			}
+		})
+	}
+
 	/// Adds an address for a node.
 	pub fn add_known_address(&mut self, peer_id: PeerId, addr: Multiaddr) {
 		self.network_service.behaviour_mut().add_known_address(peer_id, addr);
@@ -731,10 +768,42 @@ impl<B: BlockT + 'static, H: ExHashT> NetworkService<B, H> {
 		}
 	}
 
-	/// Get the list of reserved peers.
+	/// Assemble a [`ConnectivityReport`] from the established connections, the reserved peers of
+	/// all peer sets, the DHT routing table and the discovered external addresses.
+	///
+	/// Returns an error if the `NetworkWorker` is no longer running.
+	pub async fn connectivity_report(&self) -> Result<ConnectivityReport, ()> {
+		let (tx, rx) = oneshot::channel();
+
+		let _ = self
+			.to_worker
+			.unbounded_send(ServiceToWorkerMsg::Connectivity { pending_response: tx });
+
+		// The channel can only be closed if the network worker no longer exists.
+		let (connected_peers, num_dht_peers) = rx.await.map_err(|_| ())?;
+
+		let mut reserved_peers = HashSet::new();
+		for handle in &self.protocol_handles {
+			let (tx, rx) = oneshot::channel();
+			handle.reserved_peers(tx);
+
+			// The channel can only be closed if `ProtocolController` no longer exists.
+			reserved_peers.extend(rx.await.map_err(|_| ())?);
+		}
+
+		Ok(ConnectivityReport {
+			num_connected_peers: connected_peers.len(),
+			num_reserved_peers: reserved_peers.len(),
+			num_connected_reserved_peers: reserved_peers.intersection(&connected_peers).count(),
+			num_dht_peers,
+			external_addresses: self.external_addresses(),
+		})
+	}
+
+	/// Get the list of reserved peers of the sync peer set.
 	///
 	/// Returns an error if the `NetworkWorker` is no longer running.
-	pub async fn reserved_peers(&self) -> Result<Vec<PeerId>, ()> {
+	pub async fn sync_reserved_peers(&self) -> Result<Vec<PeerId>, ()> {
 		let (tx, rx) = oneshot::channel();
 
 		self.sync_protocol_handle.reserved_peers(tx);
@@ -791,6 +860,11 @@ where
 	fn local_peer_id(&self) -> PeerId {
 		self.local_peer_id
 	}
+
+	/// Returns whether the node is major syncing.
+	fn is_major_syncing(&self) -> bool {
+		self.is_major_syncing.load(Ordering::Relaxed)
+	}
 }
 
 impl<B, H> NetworkSigner for NetworkService<B, H>
@@ -803,6 +877,7 @@ where
 	}
 }
 
+#[async_trait::async_trait]
 impl<B, H> NetworkDHTProvider for NetworkService<B, H>
 where
 	B: BlockT + 'static,
@@ -816,12 +891,69 @@ where
 		let _ = self.to_worker.unbounded_send(ServiceToWorkerMsg::GetValue(key.clone()));
 	}
 
+	/// Start getting a value from the DHT.
+	///
+	/// The result is sent on the returned channel, in addition to the `ValueFound` or
+	/// `ValueNotFound` event passed as an item on the [`NetworkWorker`] stream.
+	fn start_get_value(
+		&self,
+		key: &KademliaKey,
+	) -> oneshot::Receiver<Result<Vec<(KademliaKey, Vec<u8>)>, DhtError>> {
+		let (tx, rx) = oneshot::channel();
+		let _ = self.to_worker.unbounded_send(ServiceToWorkerMsg::StartGetValue {
+			key: key.clone(),
+			pending_response: tx,
+		});
+		rx
+	}
+
 	/// Start putting a value in the DHT.
 	///
 	/// This will generate either a `ValuePut` or a `ValuePutFailed` event and pass it as an
 	/// item on the [`NetworkWorker`] stream.
-	fn put_value(&self, key: KademliaKey, value: Vec<u8>) {
-		let _ = self.to_worker.unbounded_send(ServiceToWorkerMsg::PutValue(key, value));
+	fn put_value_to(
+		&self,
+		key: KademliaKey,
+		value: Vec<u8>,
+		quorum: Quorum,
+		expires: Option<Instant>,
+	) {
+		let _ = self.to_worker.unbounded_send(ServiceToWorkerMsg::PutValue {
+			key,
+			value,
+			quorum,
+			expires,
+		});
+	}
+
+	async fn flush_dht_puts(&self, timeout: Duration) -> Result<(), ()> {
+		let (tx, rx) = oneshot::channel();
+
+		let _ = self
+			.to_worker
+			.unbounded_send(ServiceToWorkerMsg::FlushDhtPuts { pending_response: tx });
+
+		match future::select(rx, futures_timer::Delay::new(timeout)).await {
+			future::Either::Left((Ok(()), _)) => Ok(()),
+			// Either timed out, or the network worker no longer exists.
+			_ => Err(()),
+		}
+	}
+
+	/// Start announcing the local node as a provider of the key.
+	///
+	/// This will generate either a `StartedProviding` or a `StartProvidingFailed` event and pass
+	/// it as an item on the [`NetworkWorker`] stream.
+	fn start_providing(&self, key: KademliaKey) {
+		let _ = self.to_worker.unbounded_send(ServiceToWorkerMsg::StartProviding(key));
+	}
+
+	/// Start looking up the providers of the key.
+	///
+	/// This will generate `ProvidersFound` events or a `ProvidersNotFound` event and pass them as
+	/// items on the [`NetworkWorker`] stream.
+	fn get_providers(&self, key: KademliaKey) {
+		let _ = self.to_worker.unbounded_send(ServiceToWorkerMsg::GetProviders(key));
 	}
 }
 
@@ -869,10 +1001,22 @@ where
 		self.peer_store_handle.clone().report_peer(peer_id, cost_benefit);
 	}
 
+	fn report_peers(&self, reports: Vec<(PeerId, ReputationChange)>) {
+		self.peer_store_handle.clone().report_peers(reports);
+	}
+
 	fn peer_reputation(&self, peer_id: &PeerId) -> i32 {
 		self.peer_store_handle.peer_reputation(peer_id)
 	}
 
+	fn pin_reputation(&self, peer_id: PeerId, value: i32) {
+		self.peer_store_handle.clone().pin_reputation(peer_id, value);
+	}
+
+	fn unpin_reputation(&self, peer_id: PeerId) {
+		self.peer_store_handle.clone().unpin_reputation(peer_id);
+	}
+
 	fn disconnect_peer(&self, peer_id: PeerId, protocol: ProtocolName) {
 		let _ = self
 			.to_worker
@@ -937,6 +1081,48 @@ where
 		Ok(())
 	}
 
+	fn set_reserved_peers_multi(
+		&self,
+		sets: HashMap<ProtocolName, HashSet<Multiaddr>>,
+	) -> Result<(), String> {
+		// Validate all sets before applying any, so an error doesn't leave them half-updated.
+		let sets = sets
+			.into_iter()
+			.map(|(protocol, peers)| {
+				let Some(set_id) = self.notification_protocol_ids.get(&protocol) else {
+					return Err(format!(
+						"Cannot set reserved peers for unknown protocol: {}",
+						protocol
+					))
+				};
+				let peers_addrs = self.split_multiaddr_and_peer_id(peers)?;
+				// Make sure the local peer ID is never added to the PSM.
+				if peers_addrs.iter().any(|(peer_id, _)| *peer_id == self.local_peer_id) {
+					return Err("Local peer ID cannot be added as a reserved peer.".to_string())
+				}
+				Ok((*set_id, peers_addrs))
+			})
+			.collect::<Result<Vec<_>, String>>()?;
+
+		for (set_id, peers_addrs) in sets {
+			let mut peers: HashSet<PeerId> = HashSet::with_capacity(peers_addrs.len());
+
+			for (peer_id, addr) in peers_addrs.into_iter() {
+				peers.insert(peer_id);
+
+				if !addr.is_empty() {
+					let _ = self
+						.to_worker
+						.unbounded_send(ServiceToWorkerMsg::AddKnownAddress(peer_id, addr));
+				}
+			}
+
+			self.protocol_handles[usize::from(set_id)].set_reserved_peers(peers);
+		}
+
+		Ok(())
+	}
+
 	fn add_peers_to_reserved_set(
 		&self,
 		protocol: ProtocolName,
@@ -992,6 +1178,38 @@ where
 		self.num_connected.load(Ordering::Relaxed)
 	}
 
+	fn connected_peers(&self, protocol: ProtocolName) -> Vec<PeerId> {
+		self.notification_protocol_ids
+			.get(&protocol)
+			.map(|set_id| self.open_peers[usize::from(*set_id)].lock().iter().copied().collect())
+			.unwrap_or_default()
+	}
+
+	fn num_connected(&self, protocol: ProtocolName) -> usize {
+		self.notification_protocol_ids
+			.get(&protocol)
+			.map_or(0, |set_id| self.open_peers[usize::from(*set_id)].lock().len())
+	}
+
+	fn reserved_peers(&self, protocol: ProtocolName) -> Result<HashSet<PeerId>, String> {
+		let Some(set_id) = self.notification_protocol_ids.get(&protocol) else {
+			return Err(format!("Cannot get reserved peers for unknown protocol: {}", protocol))
+		};
+
+		Ok(self.protocol_handles[usize::from(*set_id)].current_reserved_peers())
+	}
+
+	fn peer_protocols(&self, peer_id: &PeerId) -> Vec<ProtocolName> {
+		let mut protocols = self
+			.notification_protocol_ids
+			.iter()
+			.filter(|(_, set_id)| self.open_peers[usize::from(**set_id)].lock().contains(peer_id))
+			.map(|(protocol, set_id)| (*set_id, protocol.clone()))
+			.collect::<Vec<_>>();
+		protocols.sort_unstable_by_key(|(set_id, _)| *set_id);
+		protocols.into_iter().map(|(_, protocol)| protocol).collect()
+	}
+
 	fn peer_role(&self, peer_id: PeerId, handshake: Vec<u8>) -> Option<ObservedRole> {
 		match Roles::decode_all(&mut &handshake[..]) {
 			Ok(role) => Some(role.into()),
@@ -1049,10 +1267,32 @@ where
 		protocol: ProtocolName,
 		request: Vec<u8>,
 		connect: IfDisconnected,
+	) -> Result<Vec<u8>, RequestFailure> {
+		self.request_with_timeout(target, protocol, request, None, connect).await
+	}
+
+	fn start_request(
+		&self,
+		target: PeerId,
+		protocol: ProtocolName,
+		request: Vec<u8>,
+		tx: oneshot::Sender<Result<Vec<u8>, RequestFailure>>,
+		connect: IfDisconnected,
+	) {
+		self.start_request_with_timeout(target, protocol, request, None, tx, connect)
+	}
+
+	async fn request_with_timeout(
+		&self,
+		target: PeerId,
+		protocol: ProtocolName,
+		request: Vec<u8>,
+		timeout: Option<Duration>,
+		connect: IfDisconnected,
 	) -> Result<Vec<u8>, RequestFailure> {
 		let (tx, rx) = oneshot::channel();
 
-		self.start_request(target, protocol, request, tx, connect);
+		self.start_request_with_timeout(target, protocol, request, timeout, tx, connect);
 
 		match rx.await {
 			Ok(v) => v,
@@ -1063,11 +1303,12 @@ where
 		}
 	}
 
-	fn start_request(
+	fn start_request_with_timeout(
 		&self,
 		target: PeerId,
 		protocol: ProtocolName,
 		request: Vec<u8>,
+		timeout: Option<Duration>,
 		tx: oneshot::Sender<Result<Vec<u8>, RequestFailure>>,
 		connect: IfDisconnected,
 	) {
@@ -1075,6 +1316,7 @@ where
 			target,
 			protocol: protocol.into(),
 			request,
+			timeout,
 			pending_response: tx,
 			connect,
 		});
@@ -1153,13 +1395,28 @@ impl<'a> NotificationSenderReadyT for NotificationSenderReady<'a> {
 /// Each entry corresponds to a method of `NetworkService`.
 enum ServiceToWorkerMsg {
 	GetValue(KademliaKey),
-	PutValue(KademliaKey, Vec<u8>),
+	StartGetValue {
+		key: KademliaKey,
+		pending_response: oneshot::Sender<Result<Vec<(KademliaKey, Vec<u8>)>, DhtError>>,
+	},
+	PutValue {
+		key: KademliaKey,
+		value: Vec<u8>,
+		quorum: Quorum,
+		expires: Option<Instant>,
+	},
+	FlushDhtPuts {
+		pending_response: oneshot::Sender<()>,
+	},
+	StartProviding(KademliaKey),
+	GetProviders(KademliaKey),
 	AddKnownAddress(PeerId, Multiaddr),
 	EventStream(out_events::Sender),
 	Request {
 		target: PeerId,
 		protocol: ProtocolName,
 		request: Vec<u8>,
+		timeout: Option<Duration>,
 		pending_response: oneshot::Sender<Result<Vec<u8>, RequestFailure>>,
 		connect: IfDisconnected,
 	},
@@ -1169,6 +1426,10 @@ enum ServiceToWorkerMsg {
 	NetworkState {
 		pending_response: oneshot::Sender<Result<NetworkState, RequestFailure>>,
 	},
+	Connectivity {
+		/// The peers we have an established connection to, and the size of the DHT routing table.
+		pending_response: oneshot::Sender<(HashSet<PeerId>, usize)>,
+	},
 	DisconnectPeer(PeerId, ProtocolName),
 }
 
@@ -1203,6 +1464,17 @@ where
 	peer_store_handle: PeerStoreHandle,
 	/// Notification protocol handles.
 	notif_protocol_handles: Vec<protocol::ProtocolHandle>,
+	/// Senders waiting for the result of a DHT lookup, see
+	/// [`NetworkDHTProvider::start_get_value`].
+	pending_get_values:
+		HashMap<KademliaKey, Vec<oneshot::Sender<Result<Vec<(KademliaKey, Vec<u8>)>, DhtError>>>>,
+	/// Ids of the DHT puts in progress, per key, oldest first.
+	pending_put_values: HashMap<KademliaKey, VecDeque<u64>>,
+	/// Id given to the next DHT put.
+	next_put_value_id: u64,
+	/// Senders waiting for the DHT puts that were in progress when they called
+	/// [`NetworkDHTProvider::flush_dht_puts`] to complete, with the ids of the remaining puts.
+	pending_dht_flushes: Vec<(HashSet<u64>, oneshot::Sender<()>)>,
 	/// Marker to pin the `H` generic. Serves no purpose except to not break backwards
 	/// compatibility.
 	_marker: PhantomData<H>,
@@ -1273,13 +1545,88 @@ where
 		true
 	}
 
+	/// Send the result of a DHT lookup to the callers of `start_get_value` waiting for it.
+	fn answer_pending_get_values(&mut self, event: &DhtEvent) {
+		match event {
+			DhtEvent::ValueFound(values) => {
+				let keys: HashSet<_> = values.iter().map(|(key, _)| key.clone()).collect();
+				for key in keys {
+					for pending_response in self.pending_get_values.remove(&key).unwrap_or_default()
+					{
+						let _ = pending_response.send(Ok(values.clone()));
+					}
+				}
+			},
+			DhtEvent::ValueNotFound(key) =>
+				for pending_response in self.pending_get_values.remove(key).unwrap_or_default() {
+					let _ = pending_response.send(Err(DhtError::ValueNotFound));
+				},
+			DhtEvent::ValuePut(_) |
+			DhtEvent::ValuePutFailed(_) |
+			DhtEvent::StartedProviding(_) |
+			DhtEvent::StartProvidingFailed(_) |
+			DhtEvent::ProvidersFound(..) |
+			DhtEvent::ProvidersNotFound(_) => {},
+		}
+	}
+
+	/// Account for a completed DHT put and notify the callers of `flush_dht_puts` once all the
+	/// puts they wait for have completed.
+	///
+	/// Puts of the same key are assumed to complete in the order they were started.
+	fn answer_pending_dht_flushes(&mut self, event: &DhtEvent) {
+		let (DhtEvent::ValuePut(key) | DhtEvent::ValuePutFailed(key)) = event else { return };
+
+		let Entry::Occupied(mut entry) = self.pending_put_values.entry(key.clone()) else { return };
+		let completed = entry.get_mut().pop_front();
+		if entry.get().is_empty() {
+			entry.remove();
+		}
+		let Some(completed) = completed else { return };
+
+		for (mut remaining, pending_response) in std::mem::take(&mut self.pending_dht_flushes) {
+			remaining.remove(&completed);
+			if remaining.is_empty() {
+				let _ = pending_response.send(());
+			} else if !pending_response.is_canceled() {
+				self.pending_dht_flushes.push((remaining, pending_response));
+			}
+		}
+	}
+
 	/// Process the next message coming from the `NetworkService`.
 	fn handle_worker_message(&mut self, msg: ServiceToWorkerMsg) {
 		match msg {
 			ServiceToWorkerMsg::GetValue(key) =>
 				self.network_service.behaviour_mut().get_value(key),
-			ServiceToWorkerMsg::PutValue(key, value) =>
-				self.network_service.behaviour_mut().put_value(key, value),
+			ServiceToWorkerMsg::StartGetValue { key, pending_response } => {
+				self.pending_get_values.entry(key.clone()).or_default().push(pending_response);
+				self.network_service.behaviour_mut().get_value(key);
+			},
+			ServiceToWorkerMsg::PutValue { key, value, quorum, expires } => {
+				let id = self.next_put_value_id;
+				self.next_put_value_id += 1;
+				self.pending_put_values.entry(key.clone()).or_default().push_back(id);
+				self.network_service.behaviour_mut().put_value(key, value, quorum, expires);
+			},
+			ServiceToWorkerMsg::FlushDhtPuts { pending_response } => {
+				// Forget the flushes whose callers timed out.
+				self.pending_dht_flushes
+					.retain(|(_, pending_response)| !pending_response.is_canceled());
+
+				// Only wait for the puts started before the flush.
+				let remaining: HashSet<u64> =
+					self.pending_put_values.values().flatten().copied().collect();
+				if remaining.is_empty() {
+					let _ = pending_response.send(());
+				} else {
+					self.pending_dht_flushes.push((remaining, pending_response));
+				}
+			},
+			ServiceToWorkerMsg::StartProviding(key) =>
+				self.network_service.behaviour_mut().start_providing(key),
+			ServiceToWorkerMsg::GetProviders(key) =>
+				self.network_service.behaviour_mut().get_providers(key),
 			ServiceToWorkerMsg::AddKnownAddress(peer_id, addr) =>
 				self.network_service.behaviour_mut().add_known_address(peer_id, addr),
 			ServiceToWorkerMsg::EventStream(sender) => self.event_streams.push(sender),
@@ -1287,6 +1634,7 @@ where
 				target,
 				protocol,
 				request,
+				timeout,
 				pending_response,
 				connect,
 			} => {
@@ -1294,6 +1642,7 @@ where
 					&target,
 					&protocol,
 					request,
+					timeout,
 					pending_response,
 					connect,
 				);
@@ -1304,6 +1653,15 @@ where
 			ServiceToWorkerMsg::NetworkState { pending_response } => {
 				let _ = pending_response.send(Ok(self.network_state()));
 			},
+			ServiceToWorkerMsg::Connectivity { pending_response } => {
+				let connected_peers = self.network_service.connected_peers().copied().collect();
+				let num_dht_peers = self
+					.network_service
+					.behaviour_mut()
+					.num_entries_per_kbucket()
+					.map_or(0, |buckets| buckets.into_iter().map(|(_, entries)| entries).sum());
+				let _ = pending_response.send((connected_peers, num_dht_peers));
+			},
 			ServiceToWorkerMsg::DisconnectPeer(who, protocol_name) => self
 				.network_service
 				.behaviour_mut()
@@ -1478,6 +1836,21 @@ where
 				let _ = self.notif_protocol_handles[usize::from(set_id)]
 					.report_notification_received(remote, notification);
 			},
+			SwarmEvent::Behaviour(BehaviourOut::ProtocolNegotiationFailed {
+				remote,
+				protocol,
+				reason,
+			}) => {
+				debug!(
+					target: "sub-libp2p",
+					"Failed to negotiate {protocol} with {remote}: {reason}",
+				);
+				self.event_streams.send(Event::ProtocolNegotiationFailed {
+					peer: remote,
+					protocol,
+					reason,
+				});
+			},
 			SwarmEvent::Behaviour(BehaviourOut::Dht(event, duration)) => {
 				if let Some(metrics) = self.metrics.as_ref() {
 					let query_type = match event {
@@ -1485,6 +1858,10 @@ where
 						DhtEvent::ValueNotFound(_) => "value-not-found",
 						DhtEvent::ValuePut(_) => "value-put",
 						DhtEvent::ValuePutFailed(_) => "value-put-failed",
+						DhtEvent::StartedProviding(_) => "started-providing",
+						DhtEvent::StartProvidingFailed(_) => "start-providing-failed",
+						DhtEvent::ProvidersFound(..) => "providers-found",
+						DhtEvent::ProvidersNotFound(_) => "providers-not-found",
 					};
 					metrics
 						.kademlia_query_duration
@@ -1492,6 +1869,8 @@ where
 						.observe(duration.as_secs_f64());
 				}
 
+				self.answer_pending_get_values(&event);
+				self.answer_pending_dht_flushes(&event);
 				self.event_streams.send(Event::Dht(event));
 			},
 			SwarmEvent::Behaviour(BehaviourOut::None) => {
@@ -313,6 +313,11 @@ impl Metrics {
 						.with_label_values(&[protocol, "sent", name])
 						.inc_by(u64::try_from(message.len()).unwrap_or(u64::MAX));
 				},
+			Event::ProtocolNegotiationFailed { protocol, .. } => {
+				format_label("notif-negotiation-failed-", protocol, |protocol_label| {
+					self.events_total.with_label_values(&[protocol_label, "sent", name]).inc();
+				});
+			},
 		}
 	}
 
@@ -342,6 +347,11 @@ impl Metrics {
 						.with_label_values(&[protocol, "received", name])
 						.inc_by(u64::try_from(message.len()).unwrap_or(u64::MAX));
 				},
+			Event::ProtocolNegotiationFailed { protocol, .. } => {
+				format_label("notif-negotiation-failed-", protocol, |protocol_label| {
+					self.events_total.with_label_values(&[protocol_label, "received", name]).inc();
+				});
+			},
 		}
 	}
 }
@@ -27,6 +27,24 @@ use libp2p::{
 
 pub use libp2p::identity::SigningError;
 
+/// Length of an ed25519 signature, in bytes.
+const ED25519_SIGNATURE_LENGTH: usize = 64;
+
+/// Error returned when a signature cannot be checked at all, as opposed to a well-formed signature
+/// that simply doesn't match the message.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, thiserror::Error)]
+pub enum SignatureVerificationError {
+	/// The public key is not a valid protobuf encoded public key.
+	#[error("Invalid public key")]
+	InvalidPublicKey,
+	/// The signature bytes are not a well-formed signature for the type of the public key.
+	#[error("Invalid signature")]
+	InvalidSignature,
+	/// The public key does not belong to the expected peer.
+	#[error("Public key does not match the peer ID")]
+	InvalidPeerId,
+}
+
 /// A result of signing a message with a network identity. Since `PeerId` is potentially a hash of a
 /// `PublicKey`, you need to reveal the `PublicKey` next to the signature, so the verifier can check
 /// if the signature was made by the entity that controls a given `PeerId`.
@@ -54,4 +72,77 @@ impl Signature {
 		*peer_id == self.public_key.to_peer_id() &&
 			self.public_key.verify(message.as_ref(), &self.bytes)
 	}
+
+	/// Verify a signature given as a protobuf encoded public key and the raw signature bytes.
+	///
+	/// Returns whether `signature` was made for `message`, or an error if the public key or the
+	/// signature cannot be parsed, or if the public key does not belong to `peer_id`.
+	pub fn verify_encoded(
+		message: impl AsRef<[u8]>,
+		peer_id: &PeerId,
+		public_key: &[u8],
+		signature: &[u8],
+	) -> Result<bool, SignatureVerificationError> {
+		let public_key = PublicKey::try_decode_protobuf(public_key)
+			.map_err(|_| SignatureVerificationError::InvalidPublicKey)?;
+
+		if *peer_id != public_key.to_peer_id() {
+			return Err(SignatureVerificationError::InvalidPeerId)
+		}
+
+		if public_key.clone().try_into_ed25519().is_ok() &&
+			signature.len() != ED25519_SIGNATURE_LENGTH
+		{
+			return Err(SignatureVerificationError::InvalidSignature)
+		}
+
+		Ok(public_key.verify(message.as_ref(), signature))
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn verify_encoded_works() {
+		let keypair = Keypair::generate_ed25519();
+		let peer_id = keypair.public().to_peer_id();
+		let public_key = keypair.public().encode_protobuf();
+		let signature = Signature::sign_message(b"message", &keypair).unwrap();
+
+		assert_eq!(
+			Signature::verify_encoded(b"message", &peer_id, &public_key, &signature.bytes),
+			Ok(true),
+		);
+		assert_eq!(
+			Signature::verify_encoded(b"other message", &peer_id, &public_key, &signature.bytes),
+			Ok(false),
+		);
+	}
+
+	#[test]
+	fn verify_encoded_reports_unparseable_input() {
+		let keypair = Keypair::generate_ed25519();
+		let peer_id = keypair.public().to_peer_id();
+		let public_key = keypair.public().encode_protobuf();
+		let signature = Signature::sign_message(b"message", &keypair).unwrap();
+
+		for bad_public_key in [&[0xff; 8][..], &[], &public_key[..public_key.len() - 1]] {
+			assert_eq!(
+				Signature::verify_encoded(b"message", &peer_id, bad_public_key, &signature.bytes),
+				Err(SignatureVerificationError::InvalidPublicKey),
+			);
+		}
+		for bad_signature in [&[][..], &signature.bytes[1..], &[0u8; 65]] {
+			assert_eq!(
+				Signature::verify_encoded(b"message", &peer_id, &public_key, bad_signature),
+				Err(SignatureVerificationError::InvalidSignature),
+			);
+		}
+		assert_eq!(
+			Signature::verify_encoded(b"message", &PeerId::random(), &public_key, &signature.bytes),
+			Err(SignatureVerificationError::InvalidPeerId),
+		);
+	}
 }
@@ -23,26 +23,75 @@
 use crate::{
 	config::MultiaddrWithPeerId,
 	error,
-	event::Event,
-	request_responses::{IfDisconnected, RequestFailure},
-	service::signature::Signature,
+	event::{DhtError, Event},
+	request_responses::{IfDisconnected, OutboundFailure, RequestError, RequestFailure},
+	service::signature::{Signature, SignatureVerificationError},
 	types::ProtocolName,
 	ReputationChange,
 };
 
-use futures::{channel::oneshot, Stream};
+use codec::{Decode, DecodeAll, Encode};
+use futures::{
+	channel::oneshot,
+	future::{self, Either},
+	Stream,
+};
+use futures_timer::Delay;
 use libp2p::{Multiaddr, PeerId};
 
 use sc_network_common::role::ObservedRole;
 
-use std::{collections::HashSet, fmt::Debug, future::Future, pin::Pin, sync::Arc};
+use std::{
+	collections::{HashMap, HashSet},
+	fmt::Debug,
+	future::Future,
+	pin::Pin,
+	sync::Arc,
+	time::{Duration, Instant},
+};
 
-pub use libp2p::{identity::SigningError, kad::record::Key as KademliaKey};
+pub use libp2p::{
+	identity::SigningError,
+	kad::{record::Key as KademliaKey, Quorum},
+};
 
 /// Signer with network identity
 pub trait NetworkSigner {
 	/// Signs the message with the `KeyPair` that defines the local [`PeerId`].
 	fn sign_with_local_identity(&self, msg: impl AsRef<[u8]>) -> Result<Signature, SigningError>;
+
+	/// Verify that `signature` was made for `message` by `peer_id`.
+	///
+	/// `public_key` is the protobuf encoding of the public key of `peer_id`, as sent along with a
+	/// [`Signature`]. Returns `Ok(false)` if both the key and the signature are well-formed but the
+	/// signature doesn't match, and an error if they cannot be checked against `peer_id`.
+	///
+	/// See [`Signature::verify_encoded`].
+	fn verify(
+		&self,
+		peer_id: PeerId,
+		public_key: &[u8],
+		signature: &[u8],
+		message: &[u8],
+	) -> Result<bool, SignatureVerificationError> {
+		Signature::verify_encoded(message, &peer_id, public_key, signature)
+	}
+
+	/// Like [`NetworkSigner::verify`], but rejects public keys that are not ed25519 keys with
+	/// [`SignatureVerificationError::InvalidPublicKey`].
+	fn verify_ed25519(
+		&self,
+		peer_id: PeerId,
+		public_key: &[u8],
+		signature: &[u8],
+		message: &[u8],
+	) -> Result<bool, SignatureVerificationError> {
+		libp2p::identity::PublicKey::try_decode_protobuf(public_key)
+			.ok()
+			.and_then(|public_key| public_key.try_into_ed25519().ok())
+			.ok_or(SignatureVerificationError::InvalidPublicKey)?;
+		self.verify(peer_id, public_key, signature, message)
+	}
 }
 
 impl<T> NetworkSigner for Arc<T>
@@ -53,15 +102,99 @@ where
 	fn sign_with_local_identity(&self, msg: impl AsRef<[u8]>) -> Result<Signature, SigningError> {
 		T::sign_with_local_identity(self, msg)
 	}
+
+	fn verify(
+		&self,
+		peer_id: PeerId,
+		public_key: &[u8],
+		signature: &[u8],
+		message: &[u8],
+	) -> Result<bool, SignatureVerificationError> {
+		T::verify(self, peer_id, public_key, signature, message)
+	}
+
+	fn verify_ed25519(
+		&self,
+		peer_id: PeerId,
+		public_key: &[u8],
+		signature: &[u8],
+		message: &[u8],
+	) -> Result<bool, SignatureVerificationError> {
+		T::verify_ed25519(self, peer_id, public_key, signature, message)
+	}
 }
 
 /// Provides access to the networking DHT.
+#[async_trait::async_trait]
 pub trait NetworkDHTProvider {
 	/// Start getting a value from the DHT.
 	fn get_value(&self, key: &KademliaKey);
 
+	/// Start getting a value from the DHT, returning a channel on which the result is delivered.
+	///
+	/// Saves subscribing to the event stream for one-shot lookups. The result is still reported on
+	/// the event stream as a `ValueFound` or `ValueNotFound` event.
+	///
+	/// Keep in mind that the receiver might get a `Canceled` error if the network shuts down
+	/// before the lookup completes.
+	fn start_get_value(
+		&self,
+		key: &KademliaKey,
+	) -> oneshot::Receiver<Result<Vec<(KademliaKey, Vec<u8>)>, DhtError>>;
+
 	/// Start putting a value in the DHT.
-	fn put_value(&self, key: KademliaKey, value: Vec<u8>);
+	///
+	/// The record is stored with [`Quorum::All`], i.e. only reported as put once all of the closest
+	/// peers found have stored it, and with the default expiration of the DHT records.
+	fn put_value(&self, key: KademliaKey, value: Vec<u8>) {
+		self.put_value_to(key, value, Quorum::All, None)
+	}
+
+	/// Start putting a value in the DHT, with the given quorum and expiration.
+	///
+	/// `expires` overrides the default expiration of the record on the peers storing it.
+	fn put_value_to(
+		&self,
+		key: KademliaKey,
+		value: Vec<u8>,
+		quorum: Quorum,
+		expires: Option<Instant>,
+	);
+
+	/// Wait for all DHT puts started so far to complete, for at most `timeout`.
+	///
+	/// Puts started after this call are not waited for.
+	///
+	/// A put completes once it reached its quorum or failed, see `put_value_to`. Meant to be called
+	/// before shutting down, so that records like the authority discovery one do not disappear
+	/// from the DHT until they are put again. This is best-effort: puts may still be lost if the
+	/// network does not confirm them in time.
+	///
+	/// Returns an error on timeout, or if the network is no longer running. The default
+	/// implementation doesn't keep track of the puts and returns an error right away.
+	fn flush_dht_puts<'life0, 'async_trait>(
+		&'life0 self,
+		_timeout: Duration,
+	) -> Pin<Box<dyn Future<Output = Result<(), ()>> + Send + 'async_trait>>
+	where
+		'life0: 'async_trait,
+		Self: 'async_trait,
+	{
+		Box::pin(future::ready(Err(())))
+	}
+
+	/// Start announcing the local node as a provider of the key in the DHT.
+	///
+	/// Like for `get_value`, the result is delivered on the event stream, as a `StartedProviding`
+	/// or `StartProvidingFailed` event.
+	fn start_providing(&self, key: KademliaKey);
+
+	/// Start looking up the providers of the key in the DHT.
+	///
+	/// Like for `get_value`, the result is delivered on the event stream, as one or more
+	/// `ProvidersFound` events as providers are discovered, or a `ProvidersNotFound` event if none
+	/// is found.
+	fn get_providers(&self, key: KademliaKey);
 }
 
 impl<T> NetworkDHTProvider for Arc<T>
@@ -73,9 +206,45 @@ where
 		T::get_value(self, key)
 	}
 
+	fn start_get_value(
+		&self,
+		key: &KademliaKey,
+	) -> oneshot::Receiver<Result<Vec<(KademliaKey, Vec<u8>)>, DhtError>> {
+		T::start_get_value(self, key)
+	}
+
 	fn put_value(&self, key: KademliaKey, value: Vec<u8>) {
 		T::put_value(self, key, value)
 	}
+
+	fn put_value_to(
+		&self,
+		key: KademliaKey,
+		value: Vec<u8>,
+		quorum: Quorum,
+		expires: Option<Instant>,
+	) {
+		T::put_value_to(self, key, value, quorum, expires)
+	}
+
+	fn flush_dht_puts<'life0, 'async_trait>(
+		&'life0 self,
+		timeout: Duration,
+	) -> Pin<Box<dyn Future<Output = Result<(), ()>> + Send + 'async_trait>>
+	where
+		'life0: 'async_trait,
+		Self: 'async_trait,
+	{
+		T::flush_dht_puts(self, timeout)
+	}
+
+	fn start_providing(&self, key: KademliaKey) {
+		T::start_providing(self, key)
+	}
+
+	fn get_providers(&self, key: KademliaKey) {
+		T::get_providers(self, key)
+	}
 }
 
 /// Provides an ability to set a fork sync request for a particular block.
@@ -104,12 +273,67 @@ where
 pub struct NetworkStatus {
 	/// Total number of connected peers.
 	pub num_connected_peers: usize,
+	/// Number of connected peers which dialed us.
+	pub num_connected_inbound: usize,
+	/// Number of connected peers we dialed.
+	pub num_connected_outbound: usize,
 	/// The total number of bytes received.
 	pub total_bytes_inbound: u64,
 	/// The total number of bytes sent.
 	pub total_bytes_outbound: u64,
 }
 
+/// Overall outcome of a [`ConnectivityReport`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ConnectivityStatus {
+	/// The node is connected to peers and reachable through the DHT.
+	Pass,
+	/// The node is connected to the network, but some of the signals are degraded.
+	Warn,
+	/// The node is neither connected to any peer, nor knows any peer through the DHT.
+	Fail,
+}
+
+/// Diagnostic combining the signals answering whether the node can reach the network.
+#[derive(Clone, Debug)]
+pub struct ConnectivityReport {
+	/// Number of peers we have an established connection to.
+	pub num_connected_peers: usize,
+	/// Number of reserved peers, over all peer sets.
+	pub num_reserved_peers: usize,
+	/// Number of reserved peers we have an established connection to.
+	pub num_connected_reserved_peers: usize,
+	/// Number of peers in the DHT routing table.
+	pub num_dht_peers: usize,
+	/// The external addresses discovered so far.
+	pub external_addresses: Vec<Multiaddr>,
+}
+
+impl ConnectivityReport {
+	/// Summarize the report.
+	///
+	/// Fails if the node is isolated. Warns if there are no connected peers or no DHT peers,
+	/// if none of the reserved peers is connected, or if no external address was discovered.
+	pub fn summary(&self) -> ConnectivityStatus {
+		if self.num_connected_peers == 0 && self.num_dht_peers == 0 {
+			return ConnectivityStatus::Fail
+		}
+
+		let reserved_unreachable =
+			self.num_reserved_peers > 0 && self.num_connected_reserved_peers == 0;
+
+		if self.num_connected_peers == 0 ||
+			self.num_dht_peers == 0 ||
+			reserved_unreachable ||
+			self.external_addresses.is_empty()
+		{
+			ConnectivityStatus::Warn
+		} else {
+			ConnectivityStatus::Pass
+		}
+	}
+}
+
 /// Provides high-level status information about network.
 #[async_trait::async_trait]
 pub trait NetworkStatusProvider {
@@ -157,9 +381,26 @@ pub trait NetworkPeers {
 	/// given scalar.
 	fn report_peer(&self, peer_id: PeerId, cost_benefit: ReputationChange);
 
+	/// Report several peers at once, in order, as with [`NetworkPeers::report_peer`].
+	fn report_peers(&self, reports: Vec<(PeerId, ReputationChange)>) {
+		for (peer_id, cost_benefit) in reports {
+			self.report_peer(peer_id, cost_benefit);
+		}
+	}
+
 	/// Get peer reputation.
 	fn peer_reputation(&self, peer_id: &PeerId) -> i32;
 
+	/// Fix the reputation of a given peer at `value`.
+	///
+	/// A pinned reputation does not decay over time and is not affected by
+	/// [`NetworkPeers::report_peer`], until it is released with
+	/// [`NetworkPeers::unpin_reputation`].
+	fn pin_reputation(&self, peer_id: PeerId, value: i32);
+
+	/// Release a reputation pinned with [`NetworkPeers::pin_reputation`].
+	fn unpin_reputation(&self, peer_id: PeerId);
+
 	/// Disconnect from a node as soon as possible.
 	///
 	/// This triggers the same effects as if the connection had closed itself spontaneously.
@@ -204,6 +445,16 @@ pub trait NetworkPeers {
 		peers: HashSet<Multiaddr>,
 	) -> Result<(), String>;
 
+	/// Set the reserved sets of several protocols at once, see
+	/// [`NetworkPeers::set_reserved_peers`].
+	///
+	/// All protocols and addresses are validated before any set is updated, so on `Err` none of
+	/// the sets were changed.
+	fn set_reserved_peers_multi(
+		&self,
+		sets: HashMap<ProtocolName, HashSet<Multiaddr>>,
+	) -> Result<(), String>;
+
 	/// Add peers to a peer set.
 	///
 	/// Each `Multiaddr` must end with a `/p2p/` component containing the `PeerId`. It can also
@@ -230,6 +481,37 @@ pub trait NetworkPeers {
 	/// Returns the number of peers in the sync peer set we're connected to.
 	fn sync_num_connected(&self) -> usize;
 
+	/// Returns the peers with an open substream on the notification `protocol`.
+	///
+	/// Only fully open substreams, whose handshake has been accepted, are counted. Substreams which
+	/// are still being negotiated or validated are not. Returns an empty list if `protocol` is not
+	/// a registered notification protocol.
+	fn connected_peers(&self, protocol: ProtocolName) -> Vec<PeerId>;
+
+	/// Returns the number of peers with an open substream on the notification `protocol`.
+	///
+	/// This is the length of [`NetworkPeers::connected_peers`] without collecting the peers.
+	/// Returns `0` if `protocol` does not refer to a known notification protocol.
+	fn num_connected(&self, protocol: ProtocolName) -> usize {
+		self.connected_peers(protocol).len()
+	}
+
+	/// Returns the reserved peers of `protocol`.
+	///
+	/// These are the peers configured with [`NetworkPeers::set_reserved_peers`] and
+	/// [`NetworkPeers::add_peers_to_reserved_set`], whether or not we are connected to them.
+	///
+	/// Returns `Err` if `protocol` does not refer to a known protocol.
+	fn reserved_peers(&self, protocol: ProtocolName) -> Result<HashSet<PeerId>, String>;
+
+	/// Returns the notification protocols with an open substream to `peer_id`, e.g. to find out
+	/// which protocols a connected peer never opened.
+	///
+	/// Like [`NetworkPeers::connected_peers`], only fully open substreams are counted.
+	/// Request-response protocols are negotiated anew for every request and are not included.
+	/// Returns an empty list for unknown peers.
+	fn peer_protocols(&self, peer_id: &PeerId) -> Vec<ProtocolName>;
+
 	/// Attempt to get peer role.
 	///
 	/// Right now the peer role is decoded from the received handshake for all protocols
@@ -261,10 +543,22 @@ where
 		T::report_peer(self, peer_id, cost_benefit)
 	}
 
+	fn report_peers(&self, reports: Vec<(PeerId, ReputationChange)>) {
+		T::report_peers(self, reports)
+	}
+
 	fn peer_reputation(&self, peer_id: &PeerId) -> i32 {
 		T::peer_reputation(self, peer_id)
 	}
 
+	fn pin_reputation(&self, peer_id: PeerId, value: i32) {
+		T::pin_reputation(self, peer_id, value)
+	}
+
+	fn unpin_reputation(&self, peer_id: PeerId) {
+		T::unpin_reputation(self, peer_id)
+	}
+
 	fn disconnect_peer(&self, peer_id: PeerId, protocol: ProtocolName) {
 		T::disconnect_peer(self, peer_id, protocol)
 	}
@@ -293,6 +587,13 @@ where
 		T::set_reserved_peers(self, protocol, peers)
 	}
 
+	fn set_reserved_peers_multi(
+		&self,
+		sets: HashMap<ProtocolName, HashSet<Multiaddr>>,
+	) -> Result<(), String> {
+		T::set_reserved_peers_multi(self, sets)
+	}
+
 	fn add_peers_to_reserved_set(
 		&self,
 		protocol: ProtocolName,
@@ -313,6 +614,22 @@ where
 		T::sync_num_connected(self)
 	}
 
+	fn connected_peers(&self, protocol: ProtocolName) -> Vec<PeerId> {
+		T::connected_peers(self, protocol)
+	}
+
+	fn num_connected(&self, protocol: ProtocolName) -> usize {
+		T::num_connected(self, protocol)
+	}
+
+	fn reserved_peers(&self, protocol: ProtocolName) -> Result<HashSet<PeerId>, String> {
+		T::reserved_peers(self, protocol)
+	}
+
+	fn peer_protocols(&self, peer_id: &PeerId) -> Vec<ProtocolName> {
+		T::peer_protocols(self, peer_id)
+	}
+
 	fn peer_role(&self, peer_id: PeerId, handshake: Vec<u8>) -> Option<ObservedRole> {
 		T::peer_role(self, peer_id, handshake)
 	}
@@ -352,6 +669,13 @@ pub trait NetworkStateInfo {
 
 	/// Returns the local Peer ID.
 	fn local_peer_id(&self) -> PeerId;
+
+	/// Returns `true` while the node is catching up with the chain (e.g. during full or warp sync).
+	///
+	/// This reads a flag cached by the syncing engine, which refreshes it after each event it
+	/// processes and at least once per engine tick (roughly every second). The value can thus lag
+	/// behind the actual sync state for a short while, but calling this is cheap.
+	fn is_major_syncing(&self) -> bool;
 }
 
 impl<T> NetworkStateInfo for Arc<T>
@@ -370,6 +694,10 @@ where
 	fn local_peer_id(&self) -> PeerId {
 		T::local_peer_id(self)
 	}
+
+	fn is_major_syncing(&self) -> bool {
+		T::is_major_syncing(self)
+	}
 }
 
 /// Reserved slot in the notifications buffer, ready to accept data.
@@ -572,6 +900,89 @@ pub trait NetworkRequest {
 		tx: oneshot::Sender<Result<Vec<u8>, RequestFailure>>,
 		connect: IfDisconnected,
 	);
+
+	/// Variation of `request` whose request fails with
+	/// `RequestFailure::Network(OutboundFailure::Timeout)` after `timeout` instead of the
+	/// `request_timeout` of the protocol, e.g. for latency-sensitive queries.
+	///
+	/// `timeout` can only shorten the `request_timeout` the protocol was registered with. `None`
+	/// behaves like `request`.
+	///
+	/// The default implementation drops the future returned by `request` once `timeout` elapses.
+	fn request_with_timeout<'life0, 'async_trait>(
+		&'life0 self,
+		target: PeerId,
+		protocol: ProtocolName,
+		request: Vec<u8>,
+		timeout: Option<Duration>,
+		connect: IfDisconnected,
+	) -> Pin<Box<dyn Future<Output = Result<Vec<u8>, RequestFailure>> + Send + 'async_trait>>
+	where
+		'life0: 'async_trait,
+		Self: 'async_trait,
+	{
+		let request = self.request(target, protocol, request, connect);
+		let Some(timeout) = timeout else { return request };
+
+		Box::pin(async move {
+			match future::select(request, Delay::new(timeout)).await {
+				Either::Left((response, _)) => response,
+				Either::Right(_) => Err(RequestFailure::Network(OutboundFailure::Timeout)),
+			}
+		})
+	}
+
+	/// Variation of `start_request` with the `timeout` of `request_with_timeout`.
+	fn start_request_with_timeout(
+		&self,
+		target: PeerId,
+		protocol: ProtocolName,
+		request: Vec<u8>,
+		timeout: Option<Duration>,
+		tx: oneshot::Sender<Result<Vec<u8>, RequestFailure>>,
+		connect: IfDisconnected,
+	);
+
+	/// Variation of `start_request` which sends the same request to each of the `targets`.
+	///
+	/// Returns one receiver per target, in the order of `targets`. Each of them resolves on its
+	/// own, as soon as the response of its target arrives.
+	fn start_requests(
+		&self,
+		targets: Vec<PeerId>,
+		protocol: ProtocolName,
+		request: Vec<u8>,
+		connect: IfDisconnected,
+	) -> Vec<oneshot::Receiver<Result<Vec<u8>, RequestFailure>>> {
+		targets
+			.into_iter()
+			.map(|target| {
+				let (tx, rx) = oneshot::channel();
+				self.start_request(target, protocol.clone(), request.clone(), tx, connect);
+				rx
+			})
+			.collect()
+	}
+
+	/// Variation of `request` which SCALE encodes `request` and decodes the response as `Resp`.
+	///
+	/// A response which does not decode, or has bytes left over, fails with
+	/// [`RequestError::InvalidResponse`].
+	async fn request_typed<Req, Resp>(
+		&self,
+		target: PeerId,
+		protocol: ProtocolName,
+		request: Req,
+		connect: IfDisconnected,
+	) -> Result<Resp, RequestError>
+	where
+		Self: Sized,
+		Req: Encode + Send,
+		Resp: Decode + Send,
+	{
+		let response = self.request(target, protocol, request.encode(), connect).await?;
+		Resp::decode_all(&mut &response[..]).map_err(RequestError::InvalidResponse)
+	}
 }
 
 // Manual implementation to avoid extra boxing here
@@ -604,6 +1015,43 @@ where
 	) {
 		T::start_request(self, target, protocol, request, tx, connect)
 	}
+
+	fn request_with_timeout<'life0, 'async_trait>(
+		&'life0 self,
+		target: PeerId,
+		protocol: ProtocolName,
+		request: Vec<u8>,
+		timeout: Option<Duration>,
+		connect: IfDisconnected,
+	) -> Pin<Box<dyn Future<Output = Result<Vec<u8>, RequestFailure>> + Send + 'async_trait>>
+	where
+		'life0: 'async_trait,
+		Self: 'async_trait,
+	{
+		T::request_with_timeout(self, target, protocol, request, timeout, connect)
+	}
+
+	fn start_request_with_timeout(
+		&self,
+		target: PeerId,
+		protocol: ProtocolName,
+		request: Vec<u8>,
+		timeout: Option<Duration>,
+		tx: oneshot::Sender<Result<Vec<u8>, RequestFailure>>,
+		connect: IfDisconnected,
+	) {
+		T::start_request_with_timeout(self, target, protocol, request, timeout, tx, connect)
+	}
+
+	fn start_requests(
+		&self,
+		targets: Vec<PeerId>,
+		protocol: ProtocolName,
+		request: Vec<u8>,
+		connect: IfDisconnected,
+	) -> Vec<oneshot::Receiver<Result<Vec<u8>, RequestFailure>>> {
+		T::start_requests(self, targets, protocol, request, connect)
+	}
 }
 
 /// Provides ability to announce blocks to the network.
@@ -772,6 +1220,24 @@ pub trait NotificationService: Debug + Send {
 		notification: Vec<u8>,
 	) -> Result<(), error::Error>;
 
+	/// Send synchronous `notification` to every peer with an open substream.
+	///
+	/// Behaves like calling [`NotificationService::send_sync_notification()`] for each peer
+	/// returned by [`NotificationService::connected_peers()`]: the notification is dropped for
+	/// peers whose buffer is full.
+	///
+	/// The per-peer buffers own their messages, so every peer but the last gets its own copy of
+	/// `notification`.
+	fn broadcast_notification(&self, notification: Vec<u8>) {
+		let peers = self.connected_peers();
+		if let Some((last, rest)) = peers.split_last() {
+			for peer in rest {
+				self.send_sync_notification(peer, notification.clone());
+			}
+			self.send_sync_notification(last, notification);
+		}
+	}
+
 	/// Set handshake for the notification protocol replacing the old handshake.
 	async fn set_handshake(&mut self, handshake: Vec<u8>) -> Result<(), ()>;
 
@@ -794,6 +1260,25 @@ pub trait NotificationService: Debug + Send {
 
 	/// Get message sink of the peer.
 	fn message_sink(&self, peer: &PeerId) -> Option<Box<dyn MessageSink>>;
+
+	/// Get the time when the last notification was successfully enqueued for `peer` using
+	/// [`NotificationService::send_sync_notification()`] or
+	/// [`NotificationService::send_async_notification()`].
+	///
+	/// Returns `None` if the peer is not connected or nothing has been sent to it yet. Protocols
+	/// running their own keep-alive logic can use this to detect idle peers.
+	///
+	/// The default implementation doesn't keep track of sent notifications and always returns
+	/// `None`.
+	fn last_sent(&self, _peer: &PeerId) -> Option<Instant> {
+		None
+	}
+
+	/// Get the peers that currently have an open substream for this protocol.
+	///
+	/// A handle obtained with [`NotificationService::clone()`] starts with the same peers, so it
+	/// can build its own view of the peers without replaying past events.
+	fn connected_peers(&self) -> Vec<PeerId>;
 }
 
 /// Message sink for peers.
@@ -816,4 +1301,15 @@ pub trait MessageSink: Send + Sync {
 	///
 	/// Returns an error if the peer does not exist.
 	async fn send_async_notification(&self, notification: Vec<u8>) -> Result<(), error::Error>;
+
+	/// Check whether the substream to the peer associated with this [`MessageSink`] is still open.
+	///
+	/// The substream may be closed at any moment, so `true` doesn't guarantee that a subsequent
+	/// send succeeds. `false` isn't final either: if the substream is replaced, e.g. after the
+	/// peer reconnects, the sink sends over the new substream and reports `true` again.
+	///
+	/// The default implementation doesn't know about the substream and always returns `true`.
+	fn is_connected(&self) -> bool {
+		true
+	}
 }
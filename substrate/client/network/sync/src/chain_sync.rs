@@ -39,7 +39,7 @@ use crate::{
 	},
 	warp::{
 		self, EncodedProof, WarpProofImportResult, WarpProofRequest, WarpSync, WarpSyncConfig,
-		WarpSyncPhase, WarpSyncProgress,
+		WarpSyncOptions, WarpSyncPhase, WarpSyncProgress,
 	},
 };
 
@@ -49,8 +49,12 @@ use log::{debug, error, info, trace, warn};
 
 use sc_client_api::{BlockBackend, ProofProvider};
 use sc_consensus::{BlockImportError, BlockImportStatus, IncomingBlock};
-use sc_network_common::sync::message::{
-	BlockAnnounce, BlockAttributes, BlockData, BlockRequest, BlockResponse, Direction, FromBlock,
+use sc_network_common::{
+	sync::message::{
+		BlockAnnounce, BlockAttributes, BlockData, BlockRequest, BlockResponse, Direction,
+		FromBlock,
+	},
+	types::ReputationChange,
 };
 use sp_arithmetic::traits::Saturating;
 use sp_blockchain::{Error as ClientError, HeaderBackend, HeaderMetadata};
@@ -64,9 +68,11 @@ use sp_runtime::{
 };
 
 use std::{
+	cmp::Reverse,
 	collections::{HashMap, HashSet},
 	ops::Range,
 	sync::Arc,
+	time::Instant,
 };
 
 #[cfg(test)]
@@ -95,9 +101,6 @@ const STATE_SYNC_FINALITY_THRESHOLD: u32 = 8;
 /// so far behind.
 const MAJOR_SYNC_BLOCKS: u8 = 5;
 
-/// Number of peers that need to be connected before warp sync is started.
-const MIN_PEERS_TO_START_WARP_SYNC: usize = 3;
-
 mod rep {
 	use sc_network::ReputationChange as Rep;
 	/// Reputation change when a peer sent us a message that led to a
@@ -131,6 +134,19 @@ mod rep {
 
 	/// Peer response data does not have requested bits.
 	pub const BAD_RESPONSE: Rep = Rep::new(-(1 << 12), "Incomplete response");
+
+	/// Peer did not answer a warp proof request in time.
+	pub const WARP_PROOF_TIMEOUT: Rep = Rep::new(-(1 << 10), "Warp proof request timeout");
+
+	/// Peer sent a warp sync target block with a missing or different header.
+	pub const WRONG_TARGET_HEADER: Rep = Rep::new(-(1 << 29), "Wrong warp target header");
+
+	/// Peer sent a warp sync target block without its body.
+	pub const MISSING_BODY: Rep = Rep::new(-(1 << 29), "Missing warp target body");
+
+	/// Peer sent a warp proof that failed verification, was not expected or led to an
+	/// implausibly old target block.
+	pub const BAD_WARP_PROOF: Rep = Rep::new(-(1 << 29), "Bad warp proof");
 }
 
 enum AllowedRequests {
@@ -195,6 +211,8 @@ pub enum ChainSyncAction<B: BlockT> {
 	SendStateRequest { peer_id: PeerId, request: OpaqueStateRequest },
 	/// Send warp proof request to peer.
 	SendWarpProofRequest { peer_id: PeerId, request: WarpProofRequest<B> },
+	/// Drop a warp proof request the peer did not answer in time and report the peer.
+	CancelWarpProofRequest { peer_id: PeerId, rep: ReputationChange },
 	/// Peer misbehaved. Disconnect, report it and cancel the block request to it.
 	DropPeer(BadPeer),
 	/// Import blocks.
@@ -246,8 +264,22 @@ pub struct ChainSync<B: BlockT, Client> {
 	///
 	/// Will be `None` after `self.warp_sync` is `Some(_)`.
 	warp_sync_config: Option<WarpSyncConfig<B>>,
+	/// Warp sync behaviour applied when warp sync is started.
+	warp_sync_options: WarpSyncOptions,
 	/// A temporary storage for warp sync target block until warp sync is initialized.
 	warp_sync_target_block_header: Option<B::Header>,
+	/// Outcomes of warp sync target block downloads, per peer.
+	///
+	/// Kept across disconnects, so a peer that served a bad target block is not preferred
+	/// again if it reconnects.
+	warp_target_block_stats: HashMap<PeerId, WarpTargetBlockStats>,
+	/// Peers that served a warp proof which failed verification, with the block the proof
+	/// started at.
+	warp_proof_failed_peers: HashMap<PeerId, B::Hash>,
+	/// Warp sync was aborted with [`ChainSync::abort_warp_sync`].
+	warp_sync_aborted: bool,
+	/// Peers warp proofs and the target block may be requested from. Empty allows all peers.
+	warp_sync_allowed_peers: HashSet<PeerId>,
 	/// Enable importing existing blocks. This is used used after the state download to
 	/// catch up to the latest state while re-importing blocks.
 	import_existing: bool,
@@ -257,6 +289,15 @@ pub struct ChainSync<B: BlockT, Client> {
 	actions: Vec<ChainSyncAction<B>>,
 }
 
+/// Warp sync target block responses received from a peer.
+#[derive(Debug, Default, Clone, Copy)]
+struct WarpTargetBlockStats {
+	/// Number of valid target blocks served.
+	good_blocks: u32,
+	/// Number of empty, malformed or mismatching target block responses.
+	bad_blocks: u32,
+}
+
 /// All the data we have about a Peer that we are trying to sync with
 #[derive(Debug, Clone)]
 pub(crate) struct PeerSync<B: BlockT> {
@@ -316,10 +357,12 @@ pub(crate) enum PeerSyncState<B: BlockT> {
 	DownloadingJustification(B::Hash),
 	/// Downloading state.
 	DownloadingState,
-	/// Downloading warp proof.
-	DownloadingWarpProof,
-	/// Downloading warp sync target block.
-	DownloadingWarpTargetBlock,
+	/// Downloading warp proof starting at the block hash `begin`, requested at `since`.
+	DownloadingWarpProof { begin: B::Hash, since: Instant },
+	/// Downloading warp sync target block, requested at `since`.
+	DownloadingWarpTargetBlock { since: Instant },
+	/// Downloading justifications of blocks preceding the warp sync target block.
+	DownloadingWarpFinalityHistory,
 	/// Actively downloading block history after warp sync.
 	DownloadingGap(NumberFor<B>),
 }
@@ -348,6 +391,7 @@ where
 		max_parallel_downloads: u32,
 		max_blocks_per_request: u32,
 		warp_sync_config: Option<WarpSyncConfig<B>>,
+		warp_sync_options: WarpSyncOptions,
 	) -> Result<Self, ClientError> {
 		let mut sync = Self {
 			client,
@@ -368,7 +412,12 @@ where
 			import_existing: false,
 			gap_sync: None,
 			warp_sync_config,
+			warp_sync_options,
 			warp_sync_target_block_header: None,
+			warp_target_block_stats: HashMap::new(),
+			warp_proof_failed_peers: HashMap::new(),
+			warp_sync_aborted: false,
+			warp_sync_allowed_peers: HashSet::new(),
 			actions: Vec::new(),
 		};
 
@@ -376,6 +425,33 @@ where
 		Ok(sync)
 	}
 
+	/// One-line summary of the warp sync progress, suitable for logging once per interval.
+	///
+	/// Consists of the phase, the number of peers, the downloaded proof bytes and the kind of
+	/// request in flight, if any. Returns `None` if there is no warp sync progress to report.
+	pub fn warp_sync_status_line(&self) -> Option<String> {
+		let progress = self.status().warp_sync?;
+		let in_flight = self
+			.peers
+			.values()
+			.find_map(|peer| match peer.state {
+				PeerSyncState::DownloadingWarpProof { .. } => Some("warp proof"),
+				PeerSyncState::DownloadingWarpTargetBlock { .. } => Some("target block"),
+				PeerSyncState::DownloadingWarpFinalityHistory => Some("finality history"),
+				PeerSyncState::DownloadingState => Some("state"),
+				PeerSyncState::DownloadingGap(_) => Some("block history"),
+				_ => None,
+			})
+			.unwrap_or("none");
+
+		Some(format!(
+			"{}, {} peers, {:.2} MiB, in flight: {in_flight}",
+			progress.phase,
+			self.peers.len(),
+			(progress.total_bytes as f32) / (1024f32 * 1024f32),
+		))
+	}
+
 	/// Get peer's best hash & number.
 	pub fn peer_info(&self, peer_id: &PeerId) -> Option<PeerInfo<B>> {
 		self.peers
@@ -411,12 +487,18 @@ where
 			(_, _, Some(gap_sync)) => Some(WarpSyncProgress {
 				phase: WarpSyncPhase::DownloadingBlocks(gap_sync.best_queued_number),
 				total_bytes: 0,
+				milestone: None,
+				received_bytes: 0,
+				remaining_bytes: None,
 			}),
 			(None, SyncMode::Warp, _) => Some(WarpSyncProgress {
 				phase: WarpSyncPhase::AwaitingPeers {
-					required_peers: MIN_PEERS_TO_START_WARP_SYNC,
+					required_peers: self.warp_sync_options.min_peers,
 				},
 				total_bytes: 0,
+				milestone: None,
+				received_bytes: 0,
+				remaining_bytes: None,
 			}),
 			(Some(sync), _, _) => Some(sync.progress()),
 			_ => None,
@@ -451,6 +533,15 @@ where
 		self.peers.len()
 	}
 
+	/// Get the number of in-flight warp proof and warp target block requests, in this order.
+	pub fn warp_sync_in_flight_requests(&self) -> (usize, usize) {
+		self.peers.values().fold((0, 0), |(proofs, blocks), peer| match peer.state {
+			PeerSyncState::DownloadingWarpProof { .. } => (proofs + 1, blocks),
+			PeerSyncState::DownloadingWarpTargetBlock { .. } => (proofs, blocks + 1),
+			_ => (proofs, blocks),
+		})
+	}
+
 	/// Notify syncing state machine that a new sync peer has connected.
 	pub fn new_peer(&mut self, peer_id: PeerId, best_hash: B::Hash, best_number: NumberFor<B>) {
 		match self.new_peer_inner(peer_id, best_hash, best_number) {
@@ -555,16 +646,28 @@ where
 				);
 
 				if let SyncMode::Warp = self.mode {
-					if self.peers.len() >= MIN_PEERS_TO_START_WARP_SYNC && self.warp_sync.is_none()
+					if self.peers.len() >= self.warp_sync_options.min_peers &&
+						self.warp_sync.is_none()
 					{
 						log::debug!(target: LOG_TARGET, "Starting warp state sync.");
 
 						if let Some(config) = self.warp_sync_config.take() {
-							let mut warp_sync = WarpSync::new(self.client.clone(), config);
-							if let Some(header) = self.warp_sync_target_block_header.take() {
-								warp_sync.set_target_block(header);
+							match WarpSync::new_with_options(
+								self.client.clone(),
+								config,
+								self.warp_sync_options.clone(),
+							) {
+								Ok(mut warp_sync) => {
+									if let Some(header) = self.warp_sync_target_block_header.take()
+									{
+										warp_sync.set_target_block(header);
+									}
+									self.warp_sync = Some(warp_sync);
+								},
+								Err(err) => {
+									error!(target: LOG_TARGET, "Refusing to start warp sync: {err}");
+								},
 							}
-							self.warp_sync = Some(warp_sync);
 						}
 					}
 				}
@@ -598,6 +701,23 @@ where
 		self.on_block_queued(best_hash, best_number);
 	}
 
+	/// Update the best block of a connected peer.
+	///
+	/// Peers are only eligible for requests up to their best block, e.g. a peer that connected
+	/// below the warp sync target block can be asked for it once it has caught up.
+	pub fn update_peer_best_block(
+		&mut self,
+		peer_id: &PeerId,
+		best_hash: B::Hash,
+		best_number: NumberFor<B>,
+	) {
+		if let Some(peer) = self.peers.get_mut(peer_id) {
+			peer.best_hash = best_hash;
+			peer.best_number = best_number;
+			self.allowed_requests.add(peer_id);
+		}
+	}
+
 	/// Request extra justification.
 	pub fn request_justification(&mut self, hash: &B::Hash, number: NumberFor<B>) {
 		let client = &self.client;
@@ -886,22 +1006,43 @@ where
 							return Ok(())
 						}
 					},
-					PeerSyncState::DownloadingWarpTargetBlock => {
+					PeerSyncState::DownloadingWarpTargetBlock { .. } => {
 						peer.state = PeerSyncState::Available;
 						if let Some(warp_sync) = &mut self.warp_sync {
+							let stats = self.warp_target_block_stats.entry(*peer_id).or_default();
 							if blocks.len() == 1 {
-								validate_blocks::<B>(&blocks, peer_id, Some(request))?;
+								if let Err(bad_peer) =
+									validate_blocks::<B>(&blocks, peer_id, Some(request))
+								{
+									stats.bad_blocks += 1;
+									return Err(bad_peer)
+								}
 								match warp_sync.import_target_block(
 									blocks.pop().expect("`blocks` len checked above."),
 								) {
-									warp::TargetBlockImportResult::Success => return Ok(()),
-									warp::TargetBlockImportResult::BadResponse =>
-										return Err(BadPeer(*peer_id, rep::VERIFICATION_FAIL)),
+									warp::TargetBlockImportResult::Success => {
+										stats.good_blocks += 1;
+										return Ok(())
+									},
+									warp::TargetBlockImportResult::WrongHeader => {
+										stats.bad_blocks += 1;
+										return Err(BadPeer(*peer_id, rep::WRONG_TARGET_HEADER))
+									},
+									warp::TargetBlockImportResult::MissingBody => {
+										stats.bad_blocks += 1;
+										return Err(BadPeer(*peer_id, rep::MISSING_BODY))
+									},
+									warp::TargetBlockImportResult::BadResponse => {
+										stats.bad_blocks += 1;
+										return Err(BadPeer(*peer_id, rep::VERIFICATION_FAIL))
+									},
 								}
 							} else if blocks.is_empty() {
 								debug!(target: LOG_TARGET, "Empty block response from {peer_id}");
+								stats.bad_blocks += 1;
 								return Err(BadPeer(*peer_id, rep::NO_BLOCK))
 							} else {
+								stats.bad_blocks += 1;
 								debug!(
 									target: LOG_TARGET,
 									"Too many blocks ({}) in warp target block response from {}",
@@ -919,10 +1060,37 @@ where
 							return Ok(())
 						}
 					},
+					PeerSyncState::DownloadingWarpFinalityHistory => {
+						peer.state = PeerSyncState::Available;
+						if let Some(warp_sync) = &mut self.warp_sync {
+							validate_blocks::<B>(&blocks, peer_id, Some(request))?;
+							let blocks = blocks
+								.into_iter()
+								.map(|mut b| {
+									b.justifications = b.justifications.take().or_else(|| {
+										legacy_justification_mapping(b.justification.take())
+									});
+									b
+								})
+								.collect();
+							match warp_sync.import_finality_history(blocks) {
+								warp::FinalityHistoryImportResult::Success => return Ok(()),
+								warp::FinalityHistoryImportResult::BadResponse =>
+									return Err(BadPeer(*peer_id, rep::VERIFICATION_FAIL)),
+							}
+						} else {
+							debug!(
+								target: LOG_TARGET,
+								"Logic error: we think we are downloading warp finality history from {}, but no warp sync is happening.",
+								peer_id,
+							);
+							return Ok(())
+						}
+					},
 					PeerSyncState::Available |
 					PeerSyncState::DownloadingJustification(..) |
 					PeerSyncState::DownloadingState |
-					PeerSyncState::DownloadingWarpProof => Vec::new(),
+					PeerSyncState::DownloadingWarpProof { .. } => Vec::new(),
 				}
 			} else {
 				// When request.is_none() this is a block announcement. Just accept blocks.
@@ -1098,9 +1266,7 @@ where
 		}
 
 		if is_best {
-			// update their best block
-			peer.best_number = number;
-			peer.best_hash = hash;
+			self.update_peer_best_block(&peer_id, hash, number);
 		}
 
 		// If the announced block is the best they have and is not ahead of us, our common number
@@ -1465,20 +1631,59 @@ where
 
 		if self.allowed_requests.is_empty() ||
 			sync.is_complete() ||
+			self.peers.iter().any(|(_, peer)| {
+				matches!(peer.state, PeerSyncState::DownloadingWarpTargetBlock { .. })
+			}) {
+			// Only one pending warp target block request is allowed.
+			return None
+		}
+
+		if let Some((target_number, request)) = sync.next_target_block_request() {
+			// Find a peer that has a block with the target number, preferring peers that served
+			// the fewest bad and the most good target blocks so far, then the best synced peers.
+			// Remaining ties are broken by peer id, to not depend on the `HashMap` order.
+			let stats = &self.warp_target_block_stats;
+			let allowed_peers = &self.warp_sync_allowed_peers;
+			let (id, peer) = self
+				.peers
+				.iter_mut()
+				.filter(|(id, peer)| {
+					peer.state.is_available() &&
+						peer.best_number >= target_number &&
+						Self::is_warp_sync_allowed_peer(allowed_peers, id)
+				})
+				.min_by_key(|(id, peer)| {
+					let stats = stats.get(*id).copied().unwrap_or_default();
+					(stats.bad_blocks, Reverse(stats.good_blocks), Reverse(peer.best_number), **id)
+				})?;
+			trace!(target: LOG_TARGET, "New warp target block request for {id}");
+			peer.state = PeerSyncState::DownloadingWarpTargetBlock { since: Instant::now() };
+			self.allowed_requests.clear();
+			return Some((*id, request))
+		}
+
+		None
+	}
+
+	/// Generate block request for downloading the finality history preceding the warp sync target
+	/// block.
+	fn warp_finality_history_request(&mut self) -> Option<(PeerId, BlockRequest<B>)> {
+		let sync = &self.warp_sync.as_ref()?;
+
+		if self.allowed_requests.is_empty() ||
 			self.peers
 				.iter()
-				.any(|(_, peer)| peer.state == PeerSyncState::DownloadingWarpTargetBlock)
+				.any(|(_, peer)| peer.state == PeerSyncState::DownloadingWarpFinalityHistory)
 		{
-			// Only one pending warp target block request is allowed.
+			// Only one pending finality history request is allowed.
 			return None
 		}
 
-		if let Some((target_number, request)) = sync.next_target_block_request() {
-			// Find a random peer that has a block with the target number.
+		if let Some((target_number, request)) = sync.next_finality_history_request() {
 			for (id, peer) in self.peers.iter_mut() {
 				if peer.state.is_available() && peer.best_number >= target_number {
-					trace!(target: LOG_TARGET, "New warp target block request for {id}");
-					peer.state = PeerSyncState::DownloadingWarpTargetBlock;
+					trace!(target: LOG_TARGET, "New warp finality history request for {id}");
+					peer.state = PeerSyncState::DownloadingWarpFinalityHistory;
 					self.allowed_requests.clear();
 					return Some((*id, request))
 				}
@@ -1567,6 +1772,7 @@ where
 		if self.mode == SyncMode::Warp {
 			return self
 				.warp_target_block_request()
+				.or_else(|| self.warp_finality_history_request())
 				.map_or_else(|| Vec::new(), |req| Vec::from([req]))
 		}
 
@@ -1735,36 +1941,60 @@ where
 		None
 	}
 
-	/// Get a warp proof request scheduled by sync to be sent out (if any).
-	fn warp_sync_request(&mut self) -> Option<(PeerId, WarpProofRequest<B>)> {
+	/// Get warp proof requests scheduled by sync to be sent out.
+	///
+	/// The same proof is requested from at most
+	/// [`WarpSyncOptions::max_parallel_proof_requests`] peers at a time.
+	fn warp_sync_requests(&mut self) -> Vec<(PeerId, WarpProofRequest<B>)> {
+		let mut requests = Vec::new();
 		if let Some(sync) = &self.warp_sync {
-			if self.allowed_requests.is_empty() ||
-				sync.is_complete() ||
-				self.peers
-					.iter()
-					.any(|(_, peer)| peer.state == PeerSyncState::DownloadingWarpProof)
-			{
-				// Only one pending state request is allowed.
-				return None
+			let in_flight = self
+				.peers
+				.values()
+				.filter(|peer| matches!(peer.state, PeerSyncState::DownloadingWarpProof { .. }))
+				.count();
+			let slots =
+				self.warp_sync_options.max_parallel_proof_requests.saturating_sub(in_flight);
+			if self.allowed_requests.is_empty() || sync.is_complete() || slots == 0 {
+				return requests
 			}
 			if let Some(request) = sync.next_warp_proof_request() {
-				let mut targets: Vec<_> = self.peers.values().map(|p| p.best_number).collect();
-				if !targets.is_empty() {
-					targets.sort();
-					let median = targets[targets.len() / 2];
-					// Find a random peer that is synced as much as peer majority.
-					for (id, peer) in self.peers.iter_mut() {
-						if peer.state.is_available() && peer.best_number >= median {
-							trace!(target: LOG_TARGET, "New WarpProofRequest for {id}");
-							peer.state = PeerSyncState::DownloadingWarpProof;
-							self.allowed_requests.clear();
-							return Some((*id, request))
+				if let Some(median) = self.warp_peer_selection_threshold() {
+					let allowed_peers = &self.warp_sync_allowed_peers;
+					let deprioritize_failed = sync.warp_proof_failures() >=
+						self.warp_sync_options.proof_failures_before_deprioritizing;
+					let failed_peers = &self.warp_proof_failed_peers;
+					let recently_failed = |id: &PeerId| {
+						deprioritize_failed && failed_peers.get(id) == Some(&request.begin)
+					};
+					// Find random peers that are synced as much as peer majority, only falling back
+					// to peers that already served a bad proof for this request.
+					for include_failed in [false, true] {
+						for (id, peer) in self.peers.iter_mut() {
+							if requests.len() == slots {
+								break
+							}
+							if peer.state.is_available() &&
+								peer.best_number >= median &&
+								Self::is_warp_sync_allowed_peer(allowed_peers, id) &&
+								(include_failed || !recently_failed(id))
+							{
+								trace!(target: LOG_TARGET, "New WarpProofRequest for {id}");
+								peer.state = PeerSyncState::DownloadingWarpProof {
+									begin: request.begin,
+									since: Instant::now(),
+								};
+								requests.push((*id, request.clone()));
+							}
 						}
 					}
+					if !requests.is_empty() {
+						self.allowed_requests.clear();
+					}
 				}
 			}
 		}
-		None
+		requests
 	}
 
 	#[must_use]
@@ -1840,20 +2070,34 @@ where
 
 	/// Submit a warp proof response received.
 	pub fn on_warp_sync_response(&mut self, peer_id: &PeerId, response: EncodedProof) {
+		let mut requested_begin = None;
 		if let Some(peer) = self.peers.get_mut(peer_id) {
-			if let PeerSyncState::DownloadingWarpProof = peer.state {
+			if let PeerSyncState::DownloadingWarpProof { begin, .. } = peer.state {
 				peer.state = PeerSyncState::Available;
 				self.allowed_requests.set_all();
+				requested_begin = Some(begin);
 			}
 		}
+		let best_seen = self.median_seen();
 		let import_result = if let Some(sync) = &mut self.warp_sync {
+			// When requesting proofs from several peers in parallel, another peer might have
+			// answered the same request already.
+			if requested_begin.map_or(false, |begin| {
+				sync.next_warp_proof_request().map(|request| request.begin) != Some(begin)
+			}) {
+				debug!(target: LOG_TARGET, "Ignoring redundant warp proof response from {peer_id}");
+				return
+			}
 			debug!(
 				target: LOG_TARGET,
 				"Importing warp proof data from {}, {} bytes.",
 				peer_id,
 				response.0.len(),
 			);
-			sync.import_warp_proof(response)
+			sync.import_warp_proof_with_best_seen(response, best_seen)
+		} else if self.warp_sync_aborted {
+			debug!(target: LOG_TARGET, "Ignored warp sync response from {peer_id} after abort");
+			return
 		} else {
 			debug!(target: LOG_TARGET, "Ignored obsolete warp sync response from {peer_id}");
 			self.actions
@@ -1862,14 +2106,111 @@ where
 		};
 
 		match import_result {
-			WarpProofImportResult::Success => {},
+			WarpProofImportResult::Success => self.warp_proof_failed_peers.clear(),
 			WarpProofImportResult::BadResponse => {
 				debug!(target: LOG_TARGET, "Bad proof data received from {peer_id}");
-				self.actions.push(ChainSyncAction::DropPeer(BadPeer(*peer_id, rep::BAD_BLOCK)));
+				if let Some(begin) = requested_begin {
+					self.warp_proof_failed_peers.insert(*peer_id, begin);
+				}
+				self.actions
+					.push(ChainSyncAction::DropPeer(BadPeer(*peer_id, rep::BAD_WARP_PROOF)));
+			},
+			WarpProofImportResult::ImplausibleTarget => {
+				debug!(target: LOG_TARGET, "Implausible warp proof target received from {peer_id}");
+				self.actions
+					.push(ChainSyncAction::DropPeer(BadPeer(*peer_id, rep::BAD_WARP_PROOF)));
 			},
 		}
 	}
 
+	/// Only request warp proofs and the warp sync target block from `peers`, e.g. trusted
+	/// bootnodes. An empty set allows all peers again.
+	///
+	/// Requests already sent to other peers are not cancelled.
+	pub fn set_warp_sync_allowed_peers(&mut self, peers: HashSet<PeerId>) {
+		self.warp_sync_allowed_peers = peers;
+		self.allowed_requests.set_all();
+	}
+
+	fn is_warp_sync_allowed_peer(allowed_peers: &HashSet<PeerId>, peer_id: &PeerId) -> bool {
+		allowed_peers.is_empty() || allowed_peers.contains(peer_id)
+	}
+
+	/// Get the best block number peers must have reached to be asked for warp proofs, i.e. the
+	/// median best block of the peers allowed for warp sync. `None` if there are no such peers.
+	pub fn warp_peer_selection_threshold(&self) -> Option<NumberFor<B>> {
+		let mut targets: Vec<_> = self
+			.peers
+			.iter()
+			.filter(|(id, _)| Self::is_warp_sync_allowed_peer(&self.warp_sync_allowed_peers, id))
+			.map(|(_, p)| p.best_number)
+			.collect();
+		targets.sort();
+		targets.get(targets.len() / 2).copied()
+	}
+
+	/// Abort warp sync, if it is in progress or waiting for peers, and switch to full sync.
+	///
+	/// Pending warp sync requests are forgotten and responses to them arriving afterwards are
+	/// ignored, without reporting the peers.
+	pub fn abort_warp_sync(&mut self) {
+		if self.mode != SyncMode::Warp {
+			return
+		}
+		info!(target: LOG_TARGET, "Warp sync aborted, restarting block sync.");
+		self.warp_sync = None;
+		self.warp_sync_config = None;
+		self.warp_sync_target_block_header = None;
+		self.warp_proof_failed_peers.clear();
+		self.warp_sync_aborted = true;
+		self.mode = SyncMode::Full;
+		self.restart();
+	}
+
+	/// Free peers which did not answer a warp proof request within
+	/// [`WarpSyncOptions::proof_request_timeout`], so the proof can be requested elsewhere.
+	///
+	/// The stalled requests are cancelled and the peers reported, but not disconnected.
+	pub fn poll_warp_proof_timeouts(&mut self, now: Instant) {
+		let timeout = self.warp_sync_options.proof_request_timeout;
+		for (peer_id, peer) in self.peers.iter_mut() {
+			let PeerSyncState::DownloadingWarpProof { since, .. } = peer.state else { continue };
+			if now.saturating_duration_since(since) < timeout {
+				continue
+			}
+
+			debug!(target: LOG_TARGET, "Warp proof request to {peer_id} timed out");
+			peer.state = PeerSyncState::Available;
+			self.allowed_requests.set_all();
+			self.actions.push(ChainSyncAction::CancelWarpProofRequest {
+				peer_id: *peer_id,
+				rep: rep::WARP_PROOF_TIMEOUT,
+			});
+		}
+	}
+
+	/// Free the peer which did not answer the warp target block request within
+	/// [`WarpSyncOptions::target_block_request_timeout`], so the block can be requested from
+	/// another peer.
+	///
+	/// The stalled peer is counted as having served a bad target block, so the retry prefers
+	/// other peers.
+	pub fn poll_warp_target_block_timeout(&mut self, now: Instant) {
+		let timeout = self.warp_sync_options.target_block_request_timeout;
+		for (peer_id, peer) in self.peers.iter_mut() {
+			let PeerSyncState::DownloadingWarpTargetBlock { since } = peer.state else { continue };
+			if now.saturating_duration_since(since) < timeout {
+				continue
+			}
+
+			debug!(target: LOG_TARGET, "Warp target block request to {peer_id} timed out");
+			peer.state = PeerSyncState::Available;
+			self.warp_target_block_stats.entry(*peer_id).or_default().bad_blocks += 1;
+			self.allowed_requests.set_all();
+			self.actions.push(ChainSyncAction::CancelBlockRequest { peer_id: *peer_id });
+		}
+	}
+
 	/// A batch of blocks have been processed, with or without errors.
 	///
 	/// Call this when a batch of blocks have been processed by the import
@@ -2044,7 +2385,7 @@ where
 		self.actions.extend(state_request);
 
 		let warp_proof_request = self
-			.warp_sync_request()
+			.warp_sync_requests()
 			.into_iter()
 			.map(|(peer_id, request)| ChainSyncAction::SendWarpProofRequest { peer_id, request });
 		self.actions.extend(warp_proof_request);
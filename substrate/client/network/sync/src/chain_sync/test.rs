@@ -23,8 +23,9 @@ use futures::executor::block_on;
 use sc_block_builder::BlockBuilderBuilder;
 use sc_network_common::sync::message::{BlockAnnounce, BlockData, BlockState, FromBlock};
 use sp_blockchain::HeaderBackend;
+use std::time::Duration;
 use substrate_test_runtime_client::{
-	runtime::{Block, Hash, Header},
+	runtime::{Block, ExtrinsicBuilder, Hash, Header},
 	BlockBuilderExt, ClientBlockImportExt, ClientExt, DefaultTestClientBuilderExt, TestClient,
 	TestClientBuilder, TestClientBuilderExt,
 };
@@ -38,7 +39,8 @@ fn processes_empty_response_on_justification_request_for_unknown_block() {
 	let client = Arc::new(TestClientBuilder::new().build());
 	let peer_id = PeerId::random();
 
-	let mut sync = ChainSync::new(SyncMode::Full, client.clone(), 1, 64, None).unwrap();
+	let mut sync =
+		ChainSync::new(SyncMode::Full, client.clone(), 1, 64, None, Default::default()).unwrap();
 
 	let (a1_hash, a1_number) = {
 		let a1 = BlockBuilderBuilder::new(&*client)
@@ -91,7 +93,8 @@ fn processes_empty_response_on_justification_request_for_unknown_block() {
 fn restart_doesnt_affect_peers_downloading_finality_data() {
 	let mut client = Arc::new(TestClientBuilder::new().build());
 
-	let mut sync = ChainSync::new(SyncMode::Full, client.clone(), 1, 64, None).unwrap();
+	let mut sync =
+		ChainSync::new(SyncMode::Full, client.clone(), 1, 64, None, Default::default()).unwrap();
 
 	let peer_id1 = PeerId::random();
 	let peer_id2 = PeerId::random();
@@ -275,7 +278,8 @@ fn do_ancestor_search_when_common_block_to_best_qeued_gap_is_to_big() {
 	let mut client = Arc::new(TestClientBuilder::new().build());
 	let info = client.info();
 
-	let mut sync = ChainSync::new(SyncMode::Full, client.clone(), 5, 64, None).unwrap();
+	let mut sync =
+		ChainSync::new(SyncMode::Full, client.clone(), 5, 64, None, Default::default()).unwrap();
 
 	let peer_id1 = PeerId::random();
 	let peer_id2 = PeerId::random();
@@ -421,7 +425,8 @@ fn can_sync_huge_fork() {
 
 	let info = client.info();
 
-	let mut sync = ChainSync::new(SyncMode::Full, client.clone(), 5, 64, None).unwrap();
+	let mut sync =
+		ChainSync::new(SyncMode::Full, client.clone(), 5, 64, None, Default::default()).unwrap();
 
 	let finalized_block = blocks[MAX_BLOCKS_TO_LOOK_BACKWARDS as usize * 2 - 1].clone();
 	let just = (*b"TEST", Vec::new());
@@ -554,7 +559,8 @@ fn syncs_fork_without_duplicate_requests() {
 
 	let info = client.info();
 
-	let mut sync = ChainSync::new(SyncMode::Full, client.clone(), 5, 64, None).unwrap();
+	let mut sync =
+		ChainSync::new(SyncMode::Full, client.clone(), 5, 64, None, Default::default()).unwrap();
 
 	let finalized_block = blocks[MAX_BLOCKS_TO_LOOK_BACKWARDS as usize * 2 - 1].clone();
 	let just = (*b"TEST", Vec::new());
@@ -689,7 +695,8 @@ fn removes_target_fork_on_disconnect() {
 	let mut client = Arc::new(TestClientBuilder::new().build());
 	let blocks = (0..3).map(|_| build_block(&mut client, None, false)).collect::<Vec<_>>();
 
-	let mut sync = ChainSync::new(SyncMode::Full, client.clone(), 1, 64, None).unwrap();
+	let mut sync =
+		ChainSync::new(SyncMode::Full, client.clone(), 1, 64, None, Default::default()).unwrap();
 
 	let peer_id1 = PeerId::random();
 	let common_block = blocks[1].clone();
@@ -714,7 +721,9 @@ fn can_import_response_with_missing_blocks() {
 
 	let empty_client = Arc::new(TestClientBuilder::new().build());
 
-	let mut sync = ChainSync::new(SyncMode::Full, empty_client.clone(), 1, 64, None).unwrap();
+	let mut sync =
+		ChainSync::new(SyncMode::Full, empty_client.clone(), 1, 64, None, Default::default())
+			.unwrap();
 
 	let peer_id1 = PeerId::random();
 	let best_block = blocks[3].clone();
@@ -745,7 +754,8 @@ fn ancestor_search_repeat() {
 #[test]
 fn sync_restart_removes_block_but_not_justification_requests() {
 	let mut client = Arc::new(TestClientBuilder::new().build());
-	let mut sync = ChainSync::new(SyncMode::Full, client.clone(), 1, 64, None).unwrap();
+	let mut sync =
+		ChainSync::new(SyncMode::Full, client.clone(), 1, 64, None, Default::default()).unwrap();
 
 	let peers = vec![PeerId::random(), PeerId::random()];
 
@@ -887,7 +897,8 @@ fn request_across_forks() {
 		fork_blocks
 	};
 
-	let mut sync = ChainSync::new(SyncMode::Full, client.clone(), 5, 64, None).unwrap();
+	let mut sync =
+		ChainSync::new(SyncMode::Full, client.clone(), 5, 64, None, Default::default()).unwrap();
 
 	// Add the peers, all at the common ancestor 100.
 	let common_block = blocks.last().unwrap();
@@ -965,3 +976,820 @@ fn request_across_forks() {
 		assert!(sync.is_known(&block.header.parent_hash()));
 	}
 }
+
+#[test]
+fn warp_sync_starts_once_configured_minimum_of_peers_is_connected() {
+	let client = Arc::new(TestClientBuilder::new().build());
+	let mut provider = crate::warp::test::MockWarpSyncProvider::<Block>::new();
+	provider.expect_current_authorities().returning(Vec::new);
+	let config = WarpSyncConfig::WithProvider(Arc::new(provider));
+
+	let options = WarpSyncOptions { min_peers: 1, ..Default::default() };
+
+	let mut sync = ChainSync::new(SyncMode::Warp, client, 1, 64, Some(config), options).unwrap();
+
+	assert_eq!(
+		sync.status().warp_sync.unwrap().phase,
+		WarpSyncPhase::AwaitingPeers { required_peers: 1 },
+	);
+
+	sync.new_peer(PeerId::random(), Hash::repeat_byte(1), 10);
+
+	assert!(sync.warp_sync.is_some());
+	assert_eq!(sync.status().warp_sync.unwrap().phase, WarpSyncPhase::DownloadingWarpProofs);
+}
+
+#[test]
+fn warp_sync_is_not_started_with_mismatched_configured_genesis_authorities() {
+	let client = Arc::new(TestClientBuilder::new().build());
+	let authorities = |seed: u8| vec![(sp_core::ed25519::Public::from_raw([seed; 32]).into(), 1)];
+	let mut provider = crate::warp::test::MockWarpSyncProvider::<Block>::new();
+	provider.expect_current_authorities().returning(move || authorities(1));
+	let config = WarpSyncConfig::WithProvider(Arc::new(provider));
+	let options =
+		WarpSyncOptions { genesis_authorities: Some((0, authorities(2))), ..Default::default() };
+
+	let mut sync = ChainSync::new(SyncMode::Warp, client, 1, 64, Some(config), options).unwrap();
+	for _ in 0..3 {
+		sync.new_peer(PeerId::random(), Hash::repeat_byte(1), 10);
+	}
+
+	assert!(sync.warp_sync.is_none());
+	assert!(!sync
+		.actions()
+		.any(|action| matches!(action, ChainSyncAction::SendWarpProofRequest { .. })));
+}
+
+#[test]
+fn remaining_warp_proof_bytes_are_reported_with_configured_estimate() {
+	let client = Arc::new(TestClientBuilder::new().build());
+	let mut provider = crate::warp::test::MockWarpSyncProvider::<Block>::new();
+	provider.expect_current_authorities().returning(Vec::new);
+	provider.expect_verify().returning(|_, _, _| {
+		Ok(crate::warp::VerificationResult::Partial(1, Vec::new(), Hash::repeat_byte(2)))
+	});
+	let config = WarpSyncConfig::WithProvider(Arc::new(provider));
+	let options = WarpSyncOptions { estimated_proof_bytes: Some(100), ..Default::default() };
+
+	let mut sync = ChainSync::new(SyncMode::Warp, client, 1, 64, Some(config), options).unwrap();
+	for _ in 0..3 {
+		sync.new_peer(PeerId::random(), Hash::repeat_byte(1), 10);
+	}
+	assert_eq!(sync.status().warp_sync.unwrap().remaining_bytes, Some(100));
+
+	let peer_id = sync
+		.actions()
+		.find_map(|action| match action {
+			ChainSyncAction::SendWarpProofRequest { peer_id, .. } => Some(peer_id),
+			_ => None,
+		})
+		.unwrap();
+	sync.on_warp_sync_response(&peer_id, EncodedProof(vec![0; 40]));
+
+	assert_eq!(sync.status().warp_sync.unwrap().remaining_bytes, Some(60));
+}
+
+#[test]
+fn redundant_parallel_warp_proof_responses_are_ignored() {
+	let client = Arc::new(TestClientBuilder::new().build());
+	let mut provider = crate::warp::test::MockWarpSyncProvider::<Block>::new();
+	provider.expect_current_authorities().returning(Vec::new);
+	// Only the first of the two responses is verified.
+	provider.expect_verify().times(1).returning(|_, _, _| {
+		Ok(crate::warp::VerificationResult::Partial(1, Vec::new(), Hash::repeat_byte(2)))
+	});
+	let config = WarpSyncConfig::WithProvider(Arc::new(provider));
+	let options = WarpSyncOptions { max_parallel_proof_requests: 2, ..Default::default() };
+
+	let mut sync = ChainSync::new(SyncMode::Warp, client, 1, 64, Some(config), options).unwrap();
+	for _ in 0..3 {
+		sync.new_peer(PeerId::random(), Hash::repeat_byte(1), 10);
+	}
+
+	let requested = sync
+		.actions()
+		.filter_map(|action| match action {
+			ChainSyncAction::SendWarpProofRequest { peer_id, .. } => Some(peer_id),
+			_ => None,
+		})
+		.collect::<Vec<_>>();
+	assert_eq!(requested.len(), 2);
+
+	sync.on_warp_sync_response(&requested[0], EncodedProof(vec![1]));
+	sync.on_warp_sync_response(&requested[1], EncodedProof(vec![1]));
+
+	assert!(!sync.take_actions().any(|action| matches!(action, ChainSyncAction::DropPeer(_))));
+	assert!(sync.peers.values().all(|peer| peer.state.is_available()));
+}
+
+#[test]
+fn warp_sync_status_line_reports_phase_and_peers() {
+	let client = Arc::new(TestClientBuilder::new().build());
+	let mut provider = crate::warp::test::MockWarpSyncProvider::<Block>::new();
+	provider.expect_current_authorities().returning(Vec::new);
+	let config = WarpSyncConfig::WithProvider(Arc::new(provider));
+
+	let mut sync =
+		ChainSync::new(SyncMode::Warp, client, 1, 64, Some(config), Default::default()).unwrap();
+
+	let line = sync.warp_sync_status_line().unwrap();
+	assert!(line.contains("Waiting for 3 peers to be connected"), "{line}");
+	assert!(line.contains("0 peers"), "{line}");
+	assert!(line.contains("in flight: none"), "{line}");
+
+	for _ in 0..3 {
+		sync.new_peer(PeerId::random(), Hash::repeat_byte(1), 10);
+	}
+	let _ = sync.actions().count();
+
+	let line = sync.warp_sync_status_line().unwrap();
+	assert!(line.contains("Downloading finality proofs"), "{line}");
+	assert!(line.contains("3 peers"), "{line}");
+	assert!(line.contains("in flight: warp proof"), "{line}");
+}
+
+#[test]
+fn stalled_warp_proof_request_is_cancelled_and_reissued() {
+	let client = Arc::new(TestClientBuilder::new().build());
+	let mut provider = crate::warp::test::MockWarpSyncProvider::<Block>::new();
+	provider.expect_current_authorities().returning(Vec::new);
+	let config = WarpSyncConfig::WithProvider(Arc::new(provider));
+
+	let mut sync =
+		ChainSync::new(SyncMode::Warp, client, 1, 64, Some(config), Default::default()).unwrap();
+	sync.warp_sync_options.proof_request_timeout = Duration::from_secs(10);
+	for _ in 0..3 {
+		sync.new_peer(PeerId::random(), Hash::repeat_byte(1), 10);
+	}
+
+	let warp_proof_requests = |sync: &mut ChainSync<Block, TestClient>| {
+		sync.actions()
+			.filter_map(|action| match action {
+				ChainSyncAction::SendWarpProofRequest { peer_id, .. } => Some(peer_id),
+				_ => None,
+			})
+			.collect::<Vec<_>>()
+	};
+	let stalled = warp_proof_requests(&mut sync);
+	assert_eq!(stalled.len(), 1);
+
+	// The peer goes silent, but the request did not time out yet.
+	sync.poll_warp_proof_timeouts(Instant::now());
+	assert!(warp_proof_requests(&mut sync).is_empty());
+
+	sync.poll_warp_proof_timeouts(Instant::now() + Duration::from_secs(11));
+	let actions = sync.take_actions().collect::<Vec<_>>();
+	assert!(matches!(
+		&actions[..],
+		[ChainSyncAction::CancelWarpProofRequest { peer_id, rep }]
+			if *peer_id == stalled[0] && *rep == rep::WARP_PROOF_TIMEOUT
+	));
+	assert!(sync.peers[&stalled[0]].state.is_available());
+
+	assert_eq!(warp_proof_requests(&mut sync).len(), 1);
+}
+
+/// Target block at height 10 with an empty body.
+fn target_block_10() -> Header {
+	Header::new(
+		10,
+		<HashingFor<Block> as sp_runtime::traits::Hash>::ordered_trie_root(
+			Vec::new(),
+			sp_runtime::StateVersion::V0,
+		),
+		Default::default(),
+		Hash::zero(),
+		Default::default(),
+	)
+}
+
+/// Warp sync provider whose first proof completes with [`target_block_10`] as target block.
+fn provider_completing_at_block_10() -> WarpSyncConfig<Block> {
+	let mut provider = crate::warp::test::MockWarpSyncProvider::<Block>::new();
+	provider.expect_current_authorities().returning(Vec::new);
+	provider.expect_verify().returning(|_, _, _| {
+		Ok(crate::warp::VerificationResult::Complete(1, Vec::new(), target_block_10()))
+	});
+	WarpSyncConfig::WithProvider(Arc::new(provider))
+}
+
+/// Warp sync with peers at #1000, whose warp proofs lead to a target block at #10.
+fn warp_sync_with_old_proof_target(max_target_lag: Option<u32>) -> Vec<ChainSyncAction<Block>> {
+	let client = Arc::new(TestClientBuilder::new().build());
+	let config = provider_completing_at_block_10();
+
+	let options = WarpSyncOptions { max_target_lag, ..Default::default() };
+
+	let mut sync = ChainSync::new(SyncMode::Warp, client, 1, 64, Some(config), options).unwrap();
+	for _ in 0..3 {
+		sync.new_peer(PeerId::random(), Hash::repeat_byte(1), 1000);
+	}
+	let peer_id = sync
+		.actions()
+		.find_map(|action| match action {
+			ChainSyncAction::SendWarpProofRequest { peer_id, .. } => Some(peer_id),
+			_ => None,
+		})
+		.unwrap();
+
+	sync.on_warp_sync_response(&peer_id, EncodedProof(vec![1]));
+	sync.take_actions().collect()
+}
+
+#[test]
+fn warp_proof_with_implausibly_old_target_is_rejected() {
+	let actions = warp_sync_with_old_proof_target(Some(100));
+
+	assert!(actions.iter().any(|action| matches!(
+		action,
+		ChainSyncAction::DropPeer(BadPeer(_, reputation)) if *reputation == rep::BAD_WARP_PROOF
+	)));
+}
+
+#[test]
+fn warp_proof_target_lag_is_not_checked_by_default() {
+	let actions = warp_sync_with_old_proof_target(None);
+
+	assert!(!actions.iter().any(|action| matches!(action, ChainSyncAction::DropPeer(_))));
+}
+
+#[test]
+fn warp_target_block_retry_avoids_peer_that_served_bad_block() {
+	let client = Arc::new(TestClientBuilder::new().build());
+	let config = provider_completing_at_block_10();
+
+	let mut sync =
+		ChainSync::new(SyncMode::Warp, client, 1, 64, Some(config), Default::default()).unwrap();
+	for _ in 0..3 {
+		sync.new_peer(PeerId::random(), Hash::repeat_byte(1), 1000);
+	}
+	let proof_peer = sync
+		.actions()
+		.find_map(|action| match action {
+			ChainSyncAction::SendWarpProofRequest { peer_id, .. } => Some(peer_id),
+			_ => None,
+		})
+		.unwrap();
+	sync.on_warp_sync_response(&proof_peer, EncodedProof(vec![1]));
+
+	let target_block_request = |sync: &mut ChainSync<Block, TestClient>| {
+		sync.actions()
+			.find_map(|action| match action {
+				ChainSyncAction::SendBlockRequest { peer_id, request } => Some((peer_id, request)),
+				_ => None,
+			})
+			.unwrap()
+	};
+
+	// The first selected peer answers with an empty response.
+	let (bad_peer, request) = target_block_request(&mut sync);
+	sync.on_block_response(bad_peer, request, Vec::new());
+
+	let (retry_peer, _) = target_block_request(&mut sync);
+	assert_ne!(retry_peer, bad_peer);
+}
+
+#[test]
+fn warp_target_block_with_tampered_body_is_rejected() {
+	let client = Arc::new(TestClientBuilder::new().build());
+	let config = provider_completing_at_block_10();
+
+	let mut sync =
+		ChainSync::new(SyncMode::Warp, client, 1, 64, Some(config), Default::default()).unwrap();
+	for _ in 0..3 {
+		sync.new_peer(PeerId::random(), Hash::repeat_byte(1), 1000);
+	}
+	let proof_peer = sync
+		.actions()
+		.find_map(|action| match action {
+			ChainSyncAction::SendWarpProofRequest { peer_id, .. } => Some(peer_id),
+			_ => None,
+		})
+		.unwrap();
+	sync.on_warp_sync_response(&proof_peer, EncodedProof(vec![1]));
+
+	let target_block_request = |sync: &mut ChainSync<Block, TestClient>| {
+		sync.actions()
+			.find_map(|action| match action {
+				ChainSyncAction::SendBlockRequest { peer_id, request } => Some((peer_id, request)),
+				_ => None,
+			})
+			.unwrap()
+	};
+
+	// The header matches the target block, but the body does not match its extrinsics root.
+	let (bad_peer, request) = target_block_request(&mut sync);
+	let header = target_block_10();
+	let tampered = BlockData::<Block> {
+		hash: header.hash(),
+		header: Some(header),
+		body: Some(vec![ExtrinsicBuilder::new_include_data(vec![1]).build()]),
+		indexed_body: None,
+		receipt: None,
+		message_queue: None,
+		justification: None,
+		justifications: None,
+	};
+	sync.on_block_response(bad_peer, request, vec![tampered]);
+	assert!(sync.take_actions().any(|action| matches!(
+		action,
+		ChainSyncAction::DropPeer(BadPeer(peer_id, reputation))
+			if peer_id == bad_peer && reputation == rep::BAD_BLOCK
+	)));
+
+	let (retry_peer, _) = target_block_request(&mut sync);
+	assert_ne!(retry_peer, bad_peer);
+}
+
+#[test]
+fn warp_sync_completes_despite_peer_serving_bad_proofs() {
+	let client = Arc::new(TestClientBuilder::new().build());
+	let mut provider = crate::warp::test::MockWarpSyncProvider::<Block>::new();
+	provider.expect_current_authorities().returning(Vec::new);
+	provider.expect_verify().returning(|proof, _, _| {
+		if proof.0 != vec![1] {
+			return Err("bad proof".into())
+		}
+		let target = Header::new(
+			10,
+			Default::default(),
+			Default::default(),
+			Hash::zero(),
+			Default::default(),
+		);
+		Ok(crate::warp::VerificationResult::Complete(1, Vec::new(), target))
+	});
+	let config = WarpSyncConfig::WithProvider(Arc::new(provider));
+
+	let mut sync =
+		ChainSync::new(SyncMode::Warp, client, 1, 64, Some(config), Default::default()).unwrap();
+	sync.warp_sync_options.min_peers = 2;
+	sync.warp_sync_options.proof_failures_before_deprioritizing = 1;
+	let malicious = PeerId::random();
+	let honest = PeerId::random();
+	sync.new_peer(malicious, Hash::repeat_byte(1), 1000);
+	sync.new_peer(honest, Hash::repeat_byte(1), 1000);
+
+	let mut malicious_requests = 0;
+	let mut target_block_requested = false;
+	for _ in 0..4 {
+		for action in sync.actions().collect::<Vec<_>>() {
+			match action {
+				ChainSyncAction::SendWarpProofRequest { peer_id, .. } => {
+					let proof = if peer_id == malicious {
+						malicious_requests += 1;
+						vec![0]
+					} else {
+						vec![1]
+					};
+					sync.on_warp_sync_response(&peer_id, EncodedProof(proof));
+				},
+				ChainSyncAction::SendBlockRequest { .. } => target_block_requested = true,
+				_ => {},
+			}
+		}
+	}
+
+	// Once the malicious peer failed, the proof is requested from the honest peer instead.
+	assert!(malicious_requests <= 1);
+	assert!(target_block_requested);
+}
+
+#[test]
+fn aborted_warp_sync_ignores_in_flight_proof_responses() {
+	let client = Arc::new(TestClientBuilder::new().build());
+	let config = provider_completing_at_block_10();
+
+	let mut sync =
+		ChainSync::new(SyncMode::Warp, client, 1, 64, Some(config), Default::default()).unwrap();
+	for _ in 0..3 {
+		sync.new_peer(PeerId::random(), Hash::repeat_byte(1), 1000);
+	}
+	let peer_id = sync
+		.actions()
+		.find_map(|action| match action {
+			ChainSyncAction::SendWarpProofRequest { peer_id, .. } => Some(peer_id),
+			_ => None,
+		})
+		.unwrap();
+
+	sync.abort_warp_sync();
+	assert!(sync.status().warp_sync.is_none());
+
+	sync.on_warp_sync_response(&peer_id, EncodedProof(vec![1]));
+	let actions = sync.actions().collect::<Vec<_>>();
+	assert!(!actions.iter().any(|action| matches!(
+		action,
+		ChainSyncAction::DropPeer(_) | ChainSyncAction::SendWarpProofRequest { .. }
+	)));
+}
+
+#[test]
+fn stalled_warp_target_block_request_is_retried_with_another_peer() {
+	let client = Arc::new(TestClientBuilder::new().build());
+	let config = provider_completing_at_block_10();
+
+	let mut sync =
+		ChainSync::new(SyncMode::Warp, client, 1, 64, Some(config), Default::default()).unwrap();
+	sync.warp_sync_options.target_block_request_timeout = Duration::from_secs(10);
+	for _ in 0..3 {
+		sync.new_peer(PeerId::random(), Hash::repeat_byte(1), 1000);
+	}
+	let proof_peer = sync
+		.actions()
+		.find_map(|action| match action {
+			ChainSyncAction::SendWarpProofRequest { peer_id, .. } => Some(peer_id),
+			_ => None,
+		})
+		.unwrap();
+	sync.on_warp_sync_response(&proof_peer, EncodedProof(vec![1]));
+
+	let target_block_requests = |sync: &mut ChainSync<Block, TestClient>| {
+		sync.actions()
+			.filter_map(|action| match action {
+				ChainSyncAction::SendBlockRequest { peer_id, request } => Some((peer_id, request)),
+				_ => None,
+			})
+			.collect::<Vec<_>>()
+	};
+	let stalled = target_block_requests(&mut sync);
+	assert_eq!(stalled.len(), 1);
+	let stalled = stalled[0].0;
+
+	// The peer goes silent, but the request did not time out yet.
+	sync.poll_warp_target_block_timeout(Instant::now());
+	assert!(target_block_requests(&mut sync).is_empty());
+
+	sync.poll_warp_target_block_timeout(Instant::now() + Duration::from_secs(11));
+	let actions = sync.take_actions().collect::<Vec<_>>();
+	assert!(matches!(
+		&actions[..],
+		[ChainSyncAction::CancelBlockRequest { peer_id }] if *peer_id == stalled
+	));
+
+	let mut retried = target_block_requests(&mut sync);
+	assert_eq!(retried.len(), 1);
+	let (peer_id, request) = retried.pop().unwrap();
+	assert_ne!(peer_id, stalled);
+
+	let header = target_block_10();
+	let block = BlockData {
+		hash: header.hash(),
+		header: Some(header),
+		body: Some(Vec::new()),
+		indexed_body: None,
+		receipt: None,
+		message_queue: None,
+		justification: None,
+		justifications: None,
+	};
+	sync.on_block_response(peer_id, request, vec![block]);
+
+	assert!(matches!(
+		sync.status().warp_sync.map(|progress| progress.phase),
+		Some(WarpSyncPhase::DownloadingState)
+	));
+}
+
+#[test]
+fn warp_peer_selection_threshold_is_median_best_number() {
+	let client = Arc::new(TestClientBuilder::new().build());
+	let config = provider_completing_at_block_10();
+
+	let mut sync =
+		ChainSync::new(SyncMode::Warp, client, 1, 64, Some(config), Default::default()).unwrap();
+	assert_eq!(sync.warp_peer_selection_threshold(), None);
+
+	let peers = (0..4).map(|_| PeerId::random()).collect::<Vec<_>>();
+	for (peer_id, best_number) in peers.iter().zip([10, 30, 20, 50]) {
+		sync.new_peer(*peer_id, Hash::repeat_byte(1), best_number);
+	}
+	assert_eq!(sync.warp_peer_selection_threshold(), Some(30));
+
+	// Only peers allowed for warp sync are taken into account.
+	sync.set_warp_sync_allowed_peers(HashSet::from([peers[0], peers[2]]));
+	assert_eq!(sync.warp_peer_selection_threshold(), Some(20));
+}
+
+#[test]
+fn warp_sync_requests_are_only_sent_to_allowed_peers() {
+	let client = Arc::new(TestClientBuilder::new().build());
+	let config = provider_completing_at_block_10();
+
+	let mut sync =
+		ChainSync::new(SyncMode::Warp, client, 1, 64, Some(config), Default::default()).unwrap();
+	let trusted = PeerId::random();
+	sync.set_warp_sync_allowed_peers(HashSet::from([trusted]));
+	for _ in 0..3 {
+		sync.new_peer(PeerId::random(), Hash::repeat_byte(1), 20);
+	}
+	// None of the connected peers is allowed.
+	assert!(!sync
+		.actions()
+		.any(|action| matches!(action, ChainSyncAction::SendWarpProofRequest { .. })));
+
+	sync.new_peer(trusted, Hash::repeat_byte(1), 10);
+	let proof_peer = sync
+		.actions()
+		.find_map(|action| match action {
+			ChainSyncAction::SendWarpProofRequest { peer_id, .. } => Some(peer_id),
+			_ => None,
+		})
+		.unwrap();
+	assert_eq!(proof_peer, trusted);
+
+	sync.on_warp_sync_response(&proof_peer, EncodedProof(vec![1]));
+	let target_block_peer = sync
+		.actions()
+		.find_map(|action| match action {
+			ChainSyncAction::SendBlockRequest { peer_id, .. } => Some(peer_id),
+			_ => None,
+		})
+		.unwrap();
+	assert_eq!(target_block_peer, trusted);
+}
+
+#[test]
+fn warp_target_block_is_not_requested_from_disallowed_peers() {
+	let client = Arc::new(TestClientBuilder::new().build());
+	let config = provider_completing_at_block_10();
+
+	let mut sync =
+		ChainSync::new(SyncMode::Warp, client, 1, 64, Some(config), Default::default()).unwrap();
+	let trusted = PeerId::random();
+	sync.new_peer(trusted, Hash::repeat_byte(1), 5);
+	for _ in 0..2 {
+		sync.new_peer(PeerId::random(), Hash::repeat_byte(1), 20);
+	}
+	sync.set_warp_sync_allowed_peers(HashSet::from([trusted]));
+	let proof_peer = sync
+		.actions()
+		.find_map(|action| match action {
+			ChainSyncAction::SendWarpProofRequest { peer_id, .. } => Some(peer_id),
+			_ => None,
+		})
+		.unwrap();
+	sync.on_warp_sync_response(&proof_peer, EncodedProof(vec![1]));
+
+	// Only disallowed peers have the target block.
+	assert!(!sync
+		.actions()
+		.any(|action| matches!(action, ChainSyncAction::SendBlockRequest { .. })));
+}
+
+#[test]
+fn in_flight_warp_sync_requests_are_counted() {
+	let client = Arc::new(TestClientBuilder::new().build());
+	let config = provider_completing_at_block_10();
+
+	let mut sync =
+		ChainSync::new(SyncMode::Warp, client, 1, 64, Some(config), Default::default()).unwrap();
+	for _ in 0..3 {
+		sync.new_peer(PeerId::random(), Hash::repeat_byte(1), 20);
+	}
+	assert_eq!(sync.warp_sync_in_flight_requests(), (0, 0));
+
+	let proof_peer = sync
+		.actions()
+		.find_map(|action| match action {
+			ChainSyncAction::SendWarpProofRequest { peer_id, .. } => Some(peer_id),
+			_ => None,
+		})
+		.unwrap();
+	assert_eq!(sync.warp_sync_in_flight_requests(), (1, 0));
+
+	sync.on_warp_sync_response(&proof_peer, EncodedProof(vec![1]));
+	assert_eq!(sync.warp_sync_in_flight_requests(), (0, 0));
+	assert!(sync
+		.actions()
+		.any(|action| matches!(action, ChainSyncAction::SendBlockRequest { .. })));
+	assert_eq!(sync.warp_sync_in_flight_requests(), (0, 1));
+}
+
+#[test]
+fn peer_catching_up_to_warp_target_block_becomes_selectable() {
+	let client = Arc::new(TestClientBuilder::new().build());
+	let config = provider_completing_at_block_10();
+
+	let mut sync =
+		ChainSync::new(SyncMode::Warp, client, 1, 64, Some(config), Default::default()).unwrap();
+	let peers = (0..3).map(|_| PeerId::random()).collect::<Vec<_>>();
+	for peer_id in &peers {
+		sync.new_peer(*peer_id, Hash::repeat_byte(1), 5);
+	}
+	let proof_peer = sync
+		.actions()
+		.find_map(|action| match action {
+			ChainSyncAction::SendWarpProofRequest { peer_id, .. } => Some(peer_id),
+			_ => None,
+		})
+		.unwrap();
+	sync.on_warp_sync_response(&proof_peer, EncodedProof(vec![1]));
+
+	// No peer has the target block yet.
+	assert!(!sync
+		.actions()
+		.any(|action| matches!(action, ChainSyncAction::SendBlockRequest { .. })));
+
+	sync.update_peer_best_block(&peers[1], Hash::repeat_byte(2), 10);
+	let target_block_peer = sync
+		.actions()
+		.find_map(|action| match action {
+			ChainSyncAction::SendBlockRequest { peer_id, .. } => Some(peer_id),
+			_ => None,
+		})
+		.unwrap();
+	assert_eq!(target_block_peer, peers[1]);
+}
+
+#[test]
+fn warp_target_block_is_requested_from_best_synced_peer() {
+	let client = Arc::new(TestClientBuilder::new().build());
+	let config = provider_completing_at_block_10();
+
+	let mut sync =
+		ChainSync::new(SyncMode::Warp, client, 1, 64, Some(config), Default::default()).unwrap();
+	let best_peer = PeerId::random();
+	sync.new_peer(PeerId::random(), Hash::repeat_byte(1), 20);
+	sync.new_peer(best_peer, Hash::repeat_byte(1), 40);
+	sync.new_peer(PeerId::random(), Hash::repeat_byte(1), 30);
+	let proof_peer = sync
+		.actions()
+		.find_map(|action| match action {
+			ChainSyncAction::SendWarpProofRequest { peer_id, .. } => Some(peer_id),
+			_ => None,
+		})
+		.unwrap();
+	sync.on_warp_sync_response(&proof_peer, EncodedProof(vec![1]));
+
+	let target_block_peer = sync
+		.actions()
+		.find_map(|action| match action {
+			ChainSyncAction::SendBlockRequest { peer_id, .. } => Some(peer_id),
+			_ => None,
+		})
+		.unwrap();
+	assert_eq!(target_block_peer, best_peer);
+}
+
+/// Every warp sync misbehaviour branch drops the peer with its own reputation change.
+mod warp_sync_penalties {
+	use super::*;
+
+	/// Warp sync that imported a proof completing at [`target_block_10`] and requested the target
+	/// block from the returned peer.
+	fn sync_downloading_target_block(
+		verify_target_justifications: bool,
+	) -> (ChainSync<Block, TestClient>, PeerId, BlockRequest<Block>) {
+		let client = Arc::new(TestClientBuilder::new().build());
+		let options = WarpSyncOptions { verify_target_justifications, ..Default::default() };
+		let mut sync = ChainSync::new(
+			SyncMode::Warp,
+			client,
+			1,
+			64,
+			Some(provider_completing_at_block_10()),
+			options,
+		)
+		.unwrap();
+		for _ in 0..3 {
+			sync.new_peer(PeerId::random(), Hash::repeat_byte(1), 1000);
+		}
+		let proof_peer = sync
+			.actions()
+			.find_map(|action| match action {
+				ChainSyncAction::SendWarpProofRequest { peer_id, .. } => Some(peer_id),
+				_ => None,
+			})
+			.unwrap();
+		sync.on_warp_sync_response(&proof_peer, EncodedProof(vec![1]));
+
+		let (peer_id, request) = sync
+			.actions()
+			.find_map(|action| match action {
+				ChainSyncAction::SendBlockRequest { peer_id, request } => Some((peer_id, request)),
+				_ => None,
+			})
+			.unwrap();
+		(sync, peer_id, request)
+	}
+
+	fn target_block_data(body: Option<Vec<<Block as BlockT>::Extrinsic>>) -> BlockData<Block> {
+		let header = target_block_10();
+		BlockData::<Block> {
+			hash: header.hash(),
+			header: Some(header),
+			body,
+			indexed_body: None,
+			receipt: None,
+			message_queue: None,
+			justification: None,
+			justifications: None,
+		}
+	}
+
+	fn assert_dropped(
+		sync: &mut ChainSync<Block, TestClient>,
+		peer: PeerId,
+		expected: ReputationChange,
+	) {
+		let actions = sync.take_actions().collect::<Vec<_>>();
+		assert!(
+			actions.iter().any(|action| matches!(
+				action,
+				ChainSyncAction::DropPeer(BadPeer(peer_id, reputation))
+					if *peer_id == peer && *reputation == expected
+			)),
+			"expected {peer} to be dropped with {expected:?}, got {actions:?}",
+		);
+	}
+
+	#[test]
+	fn proof_response_without_warp_sync_is_not_requested() {
+		let client = Arc::new(TestClientBuilder::new().build());
+		let mut sync =
+			ChainSync::new(SyncMode::Full, client, 1, 64, None, Default::default()).unwrap();
+		let peer_id = PeerId::random();
+		sync.new_peer(peer_id, Hash::repeat_byte(1), 1000);
+
+		sync.on_warp_sync_response(&peer_id, EncodedProof(vec![1]));
+		assert_dropped(&mut sync, peer_id, rep::NOT_REQUESTED);
+	}
+
+	#[test]
+	fn unverifiable_proof_is_a_bad_warp_proof() {
+		let client = Arc::new(TestClientBuilder::new().build());
+		let mut provider = crate::warp::test::MockWarpSyncProvider::<Block>::new();
+		provider.expect_current_authorities().returning(Vec::new);
+		provider.expect_verify().returning(|_, _, _| Err("bad proof".into()));
+		let config = WarpSyncConfig::WithProvider(Arc::new(provider));
+
+		let mut sync =
+			ChainSync::new(SyncMode::Warp, client, 1, 64, Some(config), Default::default())
+				.unwrap();
+		for _ in 0..3 {
+			sync.new_peer(PeerId::random(), Hash::repeat_byte(1), 1000);
+		}
+		let peer_id = sync
+			.actions()
+			.find_map(|action| match action {
+				ChainSyncAction::SendWarpProofRequest { peer_id, .. } => Some(peer_id),
+				_ => None,
+			})
+			.unwrap();
+
+		sync.on_warp_sync_response(&peer_id, EncodedProof(vec![1]));
+		assert_dropped(&mut sync, peer_id, rep::BAD_WARP_PROOF);
+	}
+
+	#[test]
+	fn proof_response_in_target_block_phase_is_a_bad_warp_proof() {
+		let (mut sync, peer_id, _) = sync_downloading_target_block(false);
+
+		sync.on_warp_sync_response(&peer_id, EncodedProof(vec![1]));
+		assert_dropped(&mut sync, peer_id, rep::BAD_WARP_PROOF);
+	}
+
+	#[test]
+	fn implausibly_old_proof_target_is_a_bad_warp_proof() {
+		let actions = warp_sync_with_old_proof_target(Some(100));
+
+		assert!(actions.iter().any(|action| matches!(
+			action,
+			ChainSyncAction::DropPeer(BadPeer(_, reputation)) if *reputation == rep::BAD_WARP_PROOF
+		)));
+	}
+
+	#[test]
+	fn empty_target_block_response_is_no_block() {
+		let (mut sync, peer_id, request) = sync_downloading_target_block(false);
+
+		sync.on_block_response(peer_id, request, Vec::new());
+		assert_dropped(&mut sync, peer_id, rep::NO_BLOCK);
+	}
+
+	#[test]
+	fn several_target_blocks_are_not_requested() {
+		let (mut sync, peer_id, request) = sync_downloading_target_block(false);
+
+		let blocks = vec![target_block_data(Some(Vec::new())), target_block_data(Some(Vec::new()))];
+		sync.on_block_response(peer_id, request, blocks);
+		assert_dropped(&mut sync, peer_id, rep::NOT_REQUESTED);
+	}
+
+	#[test]
+	fn target_block_without_body_is_a_bad_response() {
+		let (mut sync, peer_id, request) = sync_downloading_target_block(false);
+
+		sync.on_block_response(peer_id, request, vec![target_block_data(None)]);
+		assert_dropped(&mut sync, peer_id, rep::BAD_RESPONSE);
+	}
+
+	#[test]
+	fn target_block_without_justifications_fails_verification() {
+		let (mut sync, peer_id, request) = sync_downloading_target_block(true);
+
+		sync.on_block_response(peer_id, request, vec![target_block_data(Some(Vec::new()))]);
+		assert_dropped(&mut sync, peer_id, rep::VERIFICATION_FAIL);
+	}
+}
@@ -35,7 +35,7 @@ use crate::{
 	types::{
 		BadPeer, ExtendedPeerInfo, OpaqueStateRequest, OpaqueStateResponse, PeerRequest, SyncEvent,
 	},
-	warp::{EncodedProof, WarpProofRequest, WarpSyncParams},
+	warp::{EncodedProof, WarpProofRequest, WarpSyncOptions, WarpSyncParams},
 };
 
 use codec::{Decode, DecodeAll, Encode};
@@ -120,6 +120,9 @@ const INITIAL_EVICTION_WAIT_PERIOD: Duration = Duration::from_secs(2 * 60);
 /// Maximum allowed size for a block announce.
 const MAX_BLOCK_ANNOUNCE_SIZE: u64 = 1024 * 1024;
 
+/// Interval at which the warp sync progress is logged.
+const WARP_SYNC_STATUS_LOG_INTERVAL: Duration = Duration::from_secs(5);
+
 mod rep {
 	use sc_network::ReputationChange as Rep;
 	/// Peer has different genesis.
@@ -325,6 +328,9 @@ pub struct SyncingEngine<B: BlockT, Client> {
 	/// Instant when the last notification was sent or received.
 	last_notification_io: Instant,
 
+	/// Instant when the warp sync progress was last logged.
+	last_warp_sync_status_log: Instant,
+
 	/// Pending responses
 	pending_responses: PendingResponses<B>,
 
@@ -361,6 +367,7 @@ where
 		fork_id: &Option<String>,
 		block_announce_validator: Box<dyn BlockAnnounceValidator<B> + Send>,
 		warp_sync_params: Option<WarpSyncParams<B>>,
+		warp_sync_options: WarpSyncOptions,
 		network_service: service::network::NetworkServiceHandle,
 		import_queue: Box<dyn ImportQueueService<B>>,
 		block_downloader: Arc<dyn BlockDownloader<B>>,
@@ -461,6 +468,7 @@ where
 			max_parallel_downloads,
 			max_blocks_per_request,
 			warp_sync_config,
+			warp_sync_options,
 		)?;
 
 		let block_announce_protocol_name = block_announce_config.protocol_name().clone();
@@ -516,6 +524,7 @@ where
 				syncing_started: None,
 				peer_store_handle,
 				last_notification_io: Instant::now(),
+				last_warp_sync_status_log: Instant::now(),
 				metrics: if let Some(r) = metrics_registry {
 					match Metrics::register(r, is_major_syncing.clone()) {
 						Ok(metrics) => Some(metrics),
@@ -753,6 +762,12 @@ where
 					request,
 				);
 			},
+			ChainSyncAction::CancelWarpProofRequest { peer_id, rep } => {
+				let removed = self.pending_responses.remove(&peer_id);
+				self.network_service.report_peer(peer_id, rep);
+
+				trace!(target: LOG_TARGET, "Processed {action:?}, response removed: {removed}.");
+			},
 			ChainSyncAction::DropPeer(BadPeer(peer_id, rep)) => {
 				self.pending_responses.remove(&peer_id);
 				self.network_service
@@ -786,6 +801,15 @@ where
 
 	fn perform_periodic_actions(&mut self) {
 		self.report_metrics();
+		self.chain_sync.poll_warp_proof_timeouts(Instant::now());
+		self.chain_sync.poll_warp_target_block_timeout(Instant::now());
+
+		if self.last_warp_sync_status_log.elapsed() >= WARP_SYNC_STATUS_LOG_INTERVAL {
+			self.last_warp_sync_status_log = Instant::now();
+			if let Some(status_line) = self.chain_sync.warp_sync_status_line() {
+				log::debug!(target: LOG_TARGET, "Warp sync: {status_line}");
+			}
+		}
 
 		// if `SyncingEngine` has just started, don't evict seemingly inactive peers right away
 		// as they may not have produced blocks not because they've disconnected but because
@@ -888,6 +912,12 @@ where
 			ToServiceCommand::NumSyncRequests(tx) => {
 				let _ = tx.send(self.chain_sync.num_sync_requests());
 			},
+			ToServiceCommand::WarpSyncInFlightRequests(tx) => {
+				let _ = tx.send(self.chain_sync.warp_sync_in_flight_requests());
+			},
+			ToServiceCommand::SetWarpSyncAllowedPeers(peers) =>
+				self.chain_sync.set_warp_sync_allowed_peers(peers),
+			ToServiceCommand::AbortWarpSync => self.chain_sync.abort_warp_sync(),
 			ToServiceCommand::PeersInfo(tx) => {
 				let peers_info = self
 					.peers
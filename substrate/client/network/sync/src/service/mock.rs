@@ -30,7 +30,10 @@ use sc_network::{
 use sc_network_common::role::ObservedRole;
 use sp_runtime::traits::{Block as BlockT, NumberFor};
 
-use std::collections::HashSet;
+use std::{
+	collections::{HashMap, HashSet},
+	time::Duration,
+};
 
 mockall::mock! {
 	pub ChainSyncInterface<B: BlockT> {
@@ -86,6 +89,8 @@ mockall::mock! {
 		fn add_known_address(&self, peer_id: PeerId, addr: Multiaddr);
 		fn report_peer(&self, peer_id: PeerId, cost_benefit: ReputationChange);
 		fn peer_reputation(&self, peer_id: &PeerId) -> i32;
+		fn pin_reputation(&self, peer_id: PeerId, value: i32);
+		fn unpin_reputation(&self, peer_id: PeerId);
 		fn disconnect_peer(&self, peer_id: PeerId, protocol: ProtocolName);
 		fn accept_unreserved_peers(&self);
 		fn deny_unreserved_peers(&self);
@@ -96,6 +101,10 @@ mockall::mock! {
 			protocol: ProtocolName,
 			peers: HashSet<Multiaddr>,
 		) -> Result<(), String>;
+		fn set_reserved_peers_multi(
+			&self,
+			sets: HashMap<ProtocolName, HashSet<Multiaddr>>,
+		) -> Result<(), String>;
 		fn add_peers_to_reserved_set(
 			&self,
 			protocol: ProtocolName,
@@ -107,6 +116,10 @@ mockall::mock! {
 			peers: Vec<PeerId>
 		) -> Result<(), String>;
 		fn sync_num_connected(&self) -> usize;
+		fn connected_peers(&self, protocol: ProtocolName) -> Vec<PeerId>;
+		fn num_connected(&self, protocol: ProtocolName) -> usize;
+		fn reserved_peers(&self, protocol: ProtocolName) -> Result<HashSet<PeerId>, String>;
+		fn peer_protocols(&self, peer_id: &PeerId) -> Vec<ProtocolName>;
 		fn peer_role(&self, peer_id: PeerId, handshake: Vec<u8>) -> Option<ObservedRole>;
 	}
 
@@ -127,6 +140,23 @@ mockall::mock! {
 			tx: oneshot::Sender<Result<Vec<u8>, RequestFailure>>,
 			connect: IfDisconnected,
 		);
+		async fn request_with_timeout(
+			&self,
+			target: PeerId,
+			protocol: ProtocolName,
+			request: Vec<u8>,
+			timeout: Option<Duration>,
+			connect: IfDisconnected,
+		) -> Result<Vec<u8>, RequestFailure>;
+		fn start_request_with_timeout(
+			&self,
+			target: PeerId,
+			protocol: ProtocolName,
+			request: Vec<u8>,
+			timeout: Option<Duration>,
+			tx: oneshot::Sender<Result<Vec<u8>, RequestFailure>>,
+			connect: IfDisconnected,
+		);
 	}
 
 	impl NetworkNotification for Network {
@@ -148,6 +148,8 @@ impl NetworkServiceProvider {
 mod tests {
 	use super::*;
 	use crate::service::mock::MockNetwork;
+	use codec::Encode;
+	use sc_network::RequestError;
 
 	// typical pattern in `Protocol` code where peer is disconnected
 	// and then reported
@@ -179,4 +181,90 @@ mod tests {
 		handle.disconnect_peer(peer, proto_clone);
 		handle.report_peer(peer, change);
 	}
+
+	#[test]
+	fn typed_request_decodes_response() {
+		let mut mock_network = MockNetwork::new();
+		mock_network
+			.expect_request()
+			.withf(|_, _, request, _| request == &7u32.encode())
+			.returning(|_, _, _, _| Ok((7u32, true).encode()));
+		mock_network
+			.expect_request()
+			.withf(|_, _, request, _| request == &8u32.encode())
+			.returning(|_, _, _, _| Ok(vec![1, 2]));
+		let network = Arc::new(mock_network);
+
+		let response = futures::executor::block_on(network.request_typed::<_, (u32, bool)>(
+			PeerId::random(),
+			ProtocolName::from("test-protocol"),
+			7u32,
+			IfDisconnected::ImmediateError,
+		));
+		assert_eq!(response.unwrap(), (7, true));
+
+		// Too short to decode as `(u32, bool)`.
+		let response = futures::executor::block_on(network.request_typed::<_, (u32, bool)>(
+			PeerId::random(),
+			ProtocolName::from("test-protocol"),
+			8u32,
+			IfDisconnected::ImmediateError,
+		));
+		assert!(matches!(response, Err(RequestError::InvalidResponse(_))));
+	}
+
+	#[test]
+	fn peer_protocols_are_forwarded_through_arc() {
+		let peer = PeerId::random();
+
+		let mut mock_network = MockNetwork::new();
+		mock_network.expect_peer_protocols().returning(move |peer_id| {
+			if *peer_id == peer {
+				vec![ProtocolName::from("/sync/1"), ProtocolName::from("/grandpa/1")]
+			} else {
+				Vec::new()
+			}
+		});
+
+		let network: Arc<dyn NetworkPeers> = Arc::new(mock_network);
+		assert_eq!(
+			network.peer_protocols(&peer),
+			vec![ProtocolName::from("/sync/1"), ProtocolName::from("/grandpa/1")],
+		);
+		assert!(network.peer_protocols(&PeerId::random()).is_empty());
+	}
+
+	#[test]
+	fn start_requests_returns_independent_receivers_in_target_order() {
+		let pending = Arc::new(std::sync::Mutex::new(Vec::new()));
+		let pending_clone = pending.clone();
+
+		let mut mock_network = MockNetwork::new();
+		mock_network
+			.expect_start_request()
+			.times(3)
+			.returning(move |target, _, _, tx, _| {
+				pending_clone.lock().unwrap().push((target, tx));
+			});
+
+		let targets = vec![PeerId::random(), PeerId::random(), PeerId::random()];
+		let mut receivers = mock_network.start_requests(
+			targets.clone(),
+			ProtocolName::from("test-protocol"),
+			b"request".to_vec(),
+			IfDisconnected::ImmediateError,
+		);
+		assert_eq!(receivers.len(), targets.len());
+
+		// Answer the targets in reverse order, each receiver must only resolve once its own
+		// target answered.
+		while let Some((target, tx)) = pending.lock().unwrap().pop() {
+			let index = targets.iter().position(|t| *t == target).unwrap();
+			assert!(receivers[index].try_recv().unwrap().is_none());
+
+			tx.send(Ok(target.to_bytes())).unwrap();
+			assert_eq!(receivers[index].try_recv().unwrap().unwrap().unwrap(), target.to_bytes());
+			assert!(receivers[..index].iter_mut().all(|rx| rx.try_recv().unwrap().is_none()));
+		}
+	}
 }
@@ -27,6 +27,7 @@ use sc_utils::mpsc::{tracing_unbounded, TracingUnboundedSender};
 use sp_runtime::traits::{Block as BlockT, NumberFor};
 
 use std::{
+	collections::HashSet,
 	pin::Pin,
 	sync::{
 		atomic::{AtomicBool, AtomicUsize, Ordering},
@@ -56,6 +57,9 @@ pub enum ToServiceCommand<B: BlockT> {
 	NumQueuedBlocks(oneshot::Sender<u32>),
 	NumDownloadedBlocks(oneshot::Sender<usize>),
 	NumSyncRequests(oneshot::Sender<usize>),
+	WarpSyncInFlightRequests(oneshot::Sender<(usize, usize)>),
+	SetWarpSyncAllowedPeers(HashSet<PeerId>),
+	AbortWarpSync,
 	PeersInfo(oneshot::Sender<Vec<(PeerId, ExtendedPeerInfo<B>)>>),
 	OnBlockFinalized(B::Hash, B::Header),
 	// Status {
@@ -131,6 +135,25 @@ impl<B: BlockT> SyncingService<B> {
 		rx.await
 	}
 
+	/// Get the number of in-flight warp proof and warp target block requests, in this order.
+	pub async fn warp_sync_in_flight_requests(&self) -> Result<(usize, usize), oneshot::Canceled> {
+		let (tx, rx) = oneshot::channel();
+		let _ = self.tx.unbounded_send(ToServiceCommand::WarpSyncInFlightRequests(tx));
+
+		rx.await
+	}
+
+	/// Only request warp proofs and the warp sync target block from `peers`, e.g. trusted
+	/// bootnodes. An empty set allows all peers again.
+	pub fn set_warp_sync_allowed_peers(&self, peers: HashSet<PeerId>) {
+		let _ = self.tx.unbounded_send(ToServiceCommand::SetWarpSyncAllowedPeers(peers));
+	}
+
+	/// Abort warp sync, if it is in progress or waiting for peers, and fall back to full sync.
+	pub fn abort_warp_sync(&self) {
+		let _ = self.tx.unbounded_send(ToServiceCommand::AbortWarpSync);
+	}
+
 	/// Get peer information.
 	pub async fn peers_info(
 		&self,
@@ -155,6 +178,14 @@ impl<B: BlockT> SyncingService<B> {
 
 		rx.await.map_err(|_| ())
 	}
+
+	/// Get the flag telling whether we are major syncing.
+	///
+	/// The flag is updated by `SyncingEngine` and is meant to be shared with the network through
+	/// [`sc_network::config::Params::is_major_syncing`].
+	pub fn major_syncing_flag(&self) -> Arc<AtomicBool> {
+		self.is_major_syncing.clone()
+	}
 }
 
 impl<B: BlockT + 'static> NetworkSyncForkRequest<B::Hash, NumberFor<B>> for SyncingService<B> {
@@ -64,6 +64,7 @@ pub fn generate_protocol_config<Hash: AsRef<[u8]>>(
 		max_response_size: 16 * 1024 * 1024,
 		request_timeout: Duration::from_secs(40),
 		inbound_queue: None,
+		max_bandwidth_bps: None,
 	}
 }
 
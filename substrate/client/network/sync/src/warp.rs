@@ -32,12 +32,38 @@ use sc_network_common::sync::message::{
 	BlockAttributes, BlockData, BlockRequest, Direction, FromBlock,
 };
 use sp_blockchain::HeaderBackend;
-use sp_runtime::traits::{Block as BlockT, Header, NumberFor, Zero};
-use std::{fmt, sync::Arc};
+use sp_runtime::{
+	traits::{Block as BlockT, Header, NumberFor, One, SaturatedConversion, Saturating, Zero},
+	Justifications,
+};
+use std::{collections::BTreeMap, fmt, sync::Arc, time::Duration};
 
 /// Log target for this file.
 const LOG_TARGET: &'static str = "sync";
 
+/// Default maximum number of justifications collected while downloading the finality history.
+const DEFAULT_MAX_FINALITY_HISTORY: usize = 1024;
+
+/// Default maximum number of blocks preceding the target block downloaded while collecting the
+/// finality history.
+const DEFAULT_MAX_FINALITY_HISTORY_BLOCKS: u32 = 16_384;
+
+/// Default number of peers that need to be connected before warp sync is started.
+const DEFAULT_MIN_PEERS: usize = 3;
+
+/// Default time after which an unanswered warp proof request is given up on.
+const DEFAULT_PROOF_REQUEST_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Default time after which an unanswered target block request is given up on.
+const DEFAULT_TARGET_BLOCK_REQUEST_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Default number of consecutive bad proofs after which the peers that served them are
+/// deprioritized.
+const DEFAULT_PROOF_FAILURES_BEFORE_DEPRIORITIZING: u32 = 2;
+
+/// Number of blocks requested at once while downloading the finality history.
+const FINALITY_HISTORY_BLOCKS_PER_REQUEST: u32 = 64;
+
 /// Scale-encoded warp sync proof response.
 pub struct EncodedProof(pub Vec<u8>);
 
@@ -48,6 +74,23 @@ pub struct WarpProofRequest<B: BlockT> {
 	pub begin: B::Hash,
 }
 
+/// Reason for warp sync refusing to start.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum WarpSyncStartError {
+	/// The genesis authorities reported by the warp sync provider don't match the ones passed in
+	/// [`WarpSyncOptions::genesis_authorities`].
+	GenesisAuthoritiesMismatch,
+}
+
+impl fmt::Display for WarpSyncStartError {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		match self {
+			Self::GenesisAuthoritiesMismatch =>
+				write!(f, "Warp sync provider returned unexpected genesis authorities"),
+		}
+	}
+}
+
 /// Proof verification result.
 pub enum VerificationResult<Block: BlockT> {
 	/// Proof is valid, but the target was not reached.
@@ -71,9 +114,30 @@ pub trait WarpSyncProvider<Block: BlockT>: Send + Sync {
 		set_id: SetId,
 		authorities: AuthorityList,
 	) -> Result<VerificationResult<Block>, Box<dyn std::error::Error + Send + Sync>>;
+	/// Verify that `justifications` prove the finality of `header` against the given set of
+	/// authorities.
+	///
+	/// Only used if [`WarpSyncOptions::verify_target_justifications`] is enabled. Rejects all
+	/// justifications by default.
+	fn verify_justifications(
+		&self,
+		_header: &Block::Header,
+		_justifications: &Justifications,
+		_set_id: SetId,
+		_authorities: AuthorityList,
+	) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+		Err("Verifying justifications is not supported by this warp sync provider".into())
+	}
 	/// Get current list of authorities. This is supposed to be genesis authorities when starting
 	/// sync.
 	fn current_authorities(&self) -> AuthorityList;
+	/// Named milestones of the chain history, e.g. era boundaries, each identified by the first
+	/// authority set it applies to.
+	///
+	/// Only used to report which milestone warp sync has reached. Empty by default.
+	fn milestones(&self) -> Vec<(SetId, String)> {
+		Vec::new()
+	}
 }
 
 /// Reported warp sync phase.
@@ -87,6 +151,8 @@ pub enum WarpSyncPhase<Block: BlockT> {
 	DownloadingWarpProofs,
 	/// Downloading target block.
 	DownloadingTargetBlock,
+	/// Downloading justifications of finalized blocks preceding the target block.
+	DownloadingFinalityHistory,
 	/// Downloading state data.
 	DownloadingState,
 	/// Importing state.
@@ -103,6 +169,7 @@ impl<Block: BlockT> fmt::Display for WarpSyncPhase<Block> {
 			Self::AwaitingTargetBlock => write!(f, "Waiting for target block to be received"),
 			Self::DownloadingWarpProofs => write!(f, "Downloading finality proofs"),
 			Self::DownloadingTargetBlock => write!(f, "Downloading target block"),
+			Self::DownloadingFinalityHistory => write!(f, "Downloading finality history"),
 			Self::DownloadingState => write!(f, "Downloading state"),
 			Self::ImportingState => write!(f, "Importing state"),
 			Self::DownloadingBlocks(n) => write!(f, "Downloading block history (#{})", n),
@@ -117,6 +184,19 @@ pub struct WarpSyncProgress<Block: BlockT> {
 	pub phase: WarpSyncPhase<Block>,
 	/// Total bytes downloaded so far.
 	pub total_bytes: u64,
+	/// Name of the last [`WarpSyncProvider::milestones`] entry reached by the verified warp
+	/// proofs, if any.
+	pub milestone: Option<String>,
+	/// Total bytes of the complete responses received so far, including warp proofs that failed
+	/// verification.
+	///
+	/// Unlike `total_bytes`, also updated when a warp proof is rejected. Responses are only
+	/// counted once fully received, the progress of an in-flight download is not known, as the
+	/// request-response protocol only delivers complete responses.
+	pub received_bytes: u64,
+	/// Number of bytes the current phase is still expected to download, see
+	/// [`WarpSync::remaining_bytes`].
+	pub remaining_bytes: Option<u64>,
 }
 
 /// The different types of warp syncing, passed to `build_network`.
@@ -139,6 +219,84 @@ pub enum WarpSyncConfig<Block: BlockT> {
 	WaitForTarget,
 }
 
+/// Optional warp sync behaviour accepted by [`WarpSync::new_with_options`].
+///
+/// Nodes configure it through [`SyncingEngine::new`](crate::engine::SyncingEngine::new).
+#[derive(Debug, Clone)]
+pub struct WarpSyncOptions {
+	/// Once the target block is downloaded, also download the justifications of the finalized
+	/// blocks between the warp sync start and the target block.
+	pub collect_finality_history: bool,
+	/// Maximum number of justifications collected when `collect_finality_history` is enabled.
+	pub max_finality_history: usize,
+	/// Maximum number of blocks preceding the target block downloaded when
+	/// `collect_finality_history` is enabled.
+	///
+	/// Only the most recent blocks before the target are downloaded, older justifications are not
+	/// collected.
+	pub max_finality_history_blocks: u32,
+	/// Verify the justifications of the target block against the authority set proven by the
+	/// warp proofs before accepting it, instead of leaving it to the import pipeline.
+	///
+	/// Has no effect if warp proofs are not downloaded. Requires a provider that implements
+	/// [`WarpSyncProvider::verify_justifications`].
+	pub verify_target_justifications: bool,
+	/// Genesis authority set and its id to start verifying warp proofs from.
+	///
+	/// If set, warp sync refuses to start unless the provider reports the same genesis
+	/// authorities. Otherwise the provider's authorities are trusted as-is and verification
+	/// starts at set id 0.
+	pub genesis_authorities: Option<(SetId, AuthorityList)>,
+	/// Number of peers that need to be connected before warp sync is started.
+	///
+	/// Lowering this is mostly useful for small private or development networks.
+	pub min_peers: usize,
+	/// Maximum number of peers the same warp proof is requested from at a time.
+	///
+	/// The first valid response is imported, redundant responses to the same request are
+	/// ignored. Requesting from several peers can speed up warp sync on high-latency links, at
+	/// the cost of extra bandwidth.
+	pub max_parallel_proof_requests: usize,
+	/// Time after which a warp proof request that was not answered is cancelled, and the proof
+	/// requested from another peer.
+	pub proof_request_timeout: Duration,
+	/// Time after which the target block request that was not answered is cancelled, and the
+	/// block requested from another peer.
+	pub target_block_request_timeout: Duration,
+	/// Expected total size of the warp proofs, e.g. as seen by an earlier warp sync of the same
+	/// chain.
+	///
+	/// Only used to report [`WarpSync::remaining_bytes`].
+	pub estimated_proof_bytes: Option<u64>,
+	/// Reject warp proofs whose target block is more than this many blocks behind the best block
+	/// seen from peers.
+	///
+	/// Disabled by default: a stall of finality legitimately leaves the target far behind.
+	pub max_target_lag: Option<u32>,
+	/// Number of consecutive warp proofs starting at the same block that may fail verification
+	/// before the peers that served them are only asked again if no other peer is available.
+	pub proof_failures_before_deprioritizing: u32,
+}
+
+impl Default for WarpSyncOptions {
+	fn default() -> Self {
+		Self {
+			collect_finality_history: false,
+			max_finality_history: DEFAULT_MAX_FINALITY_HISTORY,
+			max_finality_history_blocks: DEFAULT_MAX_FINALITY_HISTORY_BLOCKS,
+			verify_target_justifications: false,
+			genesis_authorities: None,
+			min_peers: DEFAULT_MIN_PEERS,
+			max_parallel_proof_requests: 1,
+			proof_request_timeout: DEFAULT_PROOF_REQUEST_TIMEOUT,
+			target_block_request_timeout: DEFAULT_TARGET_BLOCK_REQUEST_TIMEOUT,
+			estimated_proof_bytes: None,
+			max_target_lag: None,
+			proof_failures_before_deprioritizing: DEFAULT_PROOF_FAILURES_BEFORE_DEPRIORITIZING,
+		}
+	}
+}
+
 impl<Block: BlockT> WarpSyncParams<Block> {
 	/// Split `WarpSyncParams` into `WarpSyncConfig` and warp sync target block header receiver.
 	pub fn split(
@@ -160,12 +318,21 @@ enum Phase<B: BlockT, Client> {
 		authorities: AuthorityList,
 		last_hash: B::Hash,
 		warp_sync_provider: Arc<dyn WarpSyncProvider<B>>,
+		/// Number of consecutive proofs starting at `last_hash` that failed verification.
+		failures: u32,
 	},
 	/// Waiting for target block to be set externally if we skip warp proofs downloading,
 	/// and start straight from the target block (used by parachains warp sync).
 	PendingTargetBlock,
 	/// Downloading target block.
 	TargetBlock(B::Header),
+	/// Downloading justifications of finalized blocks up to the target block.
+	FinalityHistory {
+		target_header: B::Header,
+		target_body: Option<Vec<B::Extrinsic>>,
+		target_justifications: Option<Justifications>,
+		next: NumberFor<B>,
+	},
 	/// Downloading state.
 	State(StateSync<B, Client>),
 }
@@ -176,21 +343,44 @@ pub enum WarpProofImportResult {
 	Success,
 	/// Bad proof.
 	BadResponse,
+	/// Valid proof, but its target block is implausibly far behind the best block seen from
+	/// peers.
+	ImplausibleTarget,
 }
 
 /// Import target block result.
 pub enum TargetBlockImportResult {
 	/// Import was successful.
 	Success,
+	/// The block header is missing or is not the target block header.
+	WrongHeader,
+	/// The block body is missing.
+	MissingBody,
 	/// Invalid block.
 	BadResponse,
 }
 
+/// Import finality history result.
+pub enum FinalityHistoryImportResult {
+	/// Import was successful.
+	Success,
+	/// Invalid blocks.
+	BadResponse,
+}
+
 /// Warp sync state machine. Accumulates warp proofs and state.
 pub struct WarpSync<B: BlockT, Client> {
 	phase: Phase<B, Client>,
 	client: Arc<Client>,
 	total_proof_bytes: u64,
+	/// Size of all completely received warp proofs, whether or not they passed verification.
+	received_proof_bytes: u64,
+	options: WarpSyncOptions,
+	finality_history: BTreeMap<NumberFor<B>, (B::Hash, Justifications)>,
+	/// Authority set proven by the warp proofs, and the provider that verified them.
+	proven_authority_set: Option<(SetId, AuthorityList, Arc<dyn WarpSyncProvider<B>>)>,
+	/// Milestones of the warp sync provider, sorted by set id.
+	milestones: Vec<(SetId, String)>,
 }
 
 impl<B, Client> WarpSync<B, Client>
@@ -202,20 +392,59 @@ where
 	/// authorities. Alternatively we can pass a target block when we want to skip downloading
 	/// proofs, in this case we will continue polling until the target block is known.
 	pub fn new(client: Arc<Client>, warp_sync_config: WarpSyncConfig<B>) -> Self {
+		Self::new_with_options(client, warp_sync_config, WarpSyncOptions::default())
+			.expect("Genesis authorities are not checked with default options; qed")
+	}
+
+	/// Create a new instance with non-default [`WarpSyncOptions`].
+	///
+	/// Fails if the options contain genesis authorities that the provider disagrees with.
+	pub fn new_with_options(
+		client: Arc<Client>,
+		warp_sync_config: WarpSyncConfig<B>,
+		options: WarpSyncOptions,
+	) -> Result<Self, WarpSyncStartError> {
 		let last_hash = client.hash(Zero::zero()).unwrap().expect("Genesis header always exists");
-		match warp_sync_config {
+		let mut milestones = Vec::new();
+		let phase = match warp_sync_config {
 			WarpSyncConfig::WithProvider(warp_sync_provider) => {
-				let phase = Phase::WarpProof {
-					set_id: 0,
-					authorities: warp_sync_provider.current_authorities(),
+				let authorities = warp_sync_provider.current_authorities();
+				let set_id = match &options.genesis_authorities {
+					Some((set_id, expected)) if *expected == authorities => *set_id,
+					Some(_) => {
+						error!(
+							target: LOG_TARGET,
+							"Warp sync provider returned unexpected genesis authorities: {:?}",
+							authorities,
+						);
+						return Err(WarpSyncStartError::GenesisAuthoritiesMismatch)
+					},
+					None => 0,
+				};
+				milestones = warp_sync_provider.milestones();
+				milestones.sort_by_key(|(set_id, _)| *set_id);
+
+				Phase::WarpProof {
+					set_id,
+					authorities,
 					last_hash,
 					warp_sync_provider: warp_sync_provider.clone(),
-				};
-				Self { client, phase, total_proof_bytes: 0 }
+					failures: 0,
+				}
 			},
-			WarpSyncConfig::WaitForTarget =>
-				Self { client, phase: Phase::PendingTargetBlock, total_proof_bytes: 0 },
-		}
+			WarpSyncConfig::WaitForTarget => Phase::PendingTargetBlock,
+		};
+
+		Ok(Self {
+			client,
+			phase,
+			total_proof_bytes: 0,
+			received_proof_bytes: 0,
+			options,
+			finality_history: BTreeMap::new(),
+			proven_authority_set: None,
+			milestones,
+		})
 	}
 
 	/// Set target block externally in case we skip warp proof downloading.
@@ -235,7 +464,10 @@ where
 	///  Validate and import a state response.
 	pub fn import_state(&mut self, response: StateResponse) -> ImportResult<B> {
 		match &mut self.phase {
-			Phase::WarpProof { .. } | Phase::TargetBlock(_) | Phase::PendingTargetBlock { .. } => {
+			Phase::WarpProof { .. } |
+			Phase::TargetBlock(_) |
+			Phase::PendingTargetBlock { .. } |
+			Phase::FinalityHistory { .. } => {
 				log::debug!(target: "sync", "Unexpected state response");
 				ImportResult::BadResponse
 			},
@@ -245,15 +477,32 @@ where
 
 	///  Validate and import a warp proof response.
 	pub fn import_warp_proof(&mut self, response: EncodedProof) -> WarpProofImportResult {
+		self.import_warp_proof_with_best_seen(response, None)
+	}
+
+	/// Like [`Self::import_warp_proof`], but also check the target block of a complete proof
+	/// against `best_seen`, the best block number seen from peers.
+	///
+	/// See [`WarpSyncOptions::max_target_lag`].
+	pub fn import_warp_proof_with_best_seen(
+		&mut self,
+		response: EncodedProof,
+		best_seen: Option<NumberFor<B>>,
+	) -> WarpProofImportResult {
 		match &mut self.phase {
-			Phase::State(_) | Phase::TargetBlock(_) | Phase::PendingTargetBlock { .. } => {
+			Phase::State(_) |
+			Phase::TargetBlock(_) |
+			Phase::PendingTargetBlock { .. } |
+			Phase::FinalityHistory { .. } => {
 				log::debug!(target: "sync", "Unexpected warp proof response");
 				WarpProofImportResult::BadResponse
 			},
-			Phase::WarpProof { set_id, authorities, last_hash, warp_sync_provider } =>
+			Phase::WarpProof { set_id, authorities, last_hash, warp_sync_provider, failures } => {
+				self.received_proof_bytes += response.0.len() as u64;
 				match warp_sync_provider.verify(&response, *set_id, authorities.clone()) {
 					Err(e) => {
 						log::debug!(target: "sync", "Bad warp proof response: {}", e);
+						*failures += 1;
 						WarpProofImportResult::BadResponse
 					},
 					Ok(VerificationResult::Partial(new_set_id, new_authorities, new_last_hash)) => {
@@ -261,23 +510,43 @@ where
 						*set_id = new_set_id;
 						*authorities = new_authorities;
 						*last_hash = new_last_hash;
+						*failures = 0;
 						self.total_proof_bytes += response.0.len() as u64;
 						WarpProofImportResult::Success
 					},
-					Ok(VerificationResult::Complete(new_set_id, _, header)) => {
+					Ok(VerificationResult::Complete(new_set_id, new_authorities, header)) => {
+						if let (Some(lag), Some(best_seen)) =
+							(self.options.max_target_lag, best_seen)
+						{
+							if header.number().saturating_add(lag.into()) < best_seen {
+								log::debug!(
+									target: "sync",
+									"Warp proof target #{} is implausibly far behind #{}",
+									header.number(),
+									best_seen,
+								);
+								return WarpProofImportResult::ImplausibleTarget
+							}
+						}
 						log::debug!(target: "sync", "Verified complete proof, set_id={:?}", new_set_id);
 						self.total_proof_bytes += response.0.len() as u64;
+						self.proven_authority_set =
+							Some((new_set_id, new_authorities, warp_sync_provider.clone()));
 						self.phase = Phase::TargetBlock(header);
 						WarpProofImportResult::Success
 					},
-				},
+				}
+			},
 		}
 	}
 
 	/// Import the target block body.
 	pub fn import_target_block(&mut self, block: BlockData<B>) -> TargetBlockImportResult {
-		match &mut self.phase {
-			Phase::WarpProof { .. } | Phase::State(_) | Phase::PendingTargetBlock { .. } => {
+		match &self.phase {
+			Phase::WarpProof { .. } |
+			Phase::State(_) |
+			Phase::PendingTargetBlock { .. } |
+			Phase::FinalityHistory { .. } => {
 				log::debug!(target: "sync", "Unexpected target block response");
 				TargetBlockImportResult::BadResponse
 			},
@@ -285,41 +554,201 @@ where
 				if let Some(block_header) = &block.header {
 					if block_header == header {
 						if block.body.is_some() {
-							let state_sync = StateSync::new(
-								self.client.clone(),
-								header.clone(),
-								block.body,
-								block.justifications,
-								false,
-							);
-							self.phase = Phase::State(state_sync);
+							if !self.verify_target_justifications(header, &block.justifications) {
+								return TargetBlockImportResult::BadResponse
+							}
+
+							let header = header.clone();
+							if self.options.collect_finality_history {
+								let next = self
+									.client
+									.info()
+									.finalized_number
+									.saturating_add(One::one())
+									.max(header.number().saturating_sub(
+										self.options.max_finality_history_blocks.into(),
+									));
+								self.phase = Phase::FinalityHistory {
+									target_header: header,
+									target_body: block.body,
+									target_justifications: block.justifications,
+									next,
+								};
+								self.maybe_finish_finality_history();
+							} else {
+								self.start_state_sync(header, block.body, block.justifications);
+							}
 							TargetBlockImportResult::Success
 						} else {
 							log::debug!(
 								target: "sync",
 								"Importing target block failed: missing body.",
 							);
-							TargetBlockImportResult::BadResponse
+							TargetBlockImportResult::MissingBody
 						}
 					} else {
 						log::debug!(
 							target: "sync",
 							"Importing target block failed: different header.",
 						);
-						TargetBlockImportResult::BadResponse
+						TargetBlockImportResult::WrongHeader
 					}
 				} else {
 					log::debug!(target: "sync", "Importing target block failed: missing header.");
-					TargetBlockImportResult::BadResponse
+					TargetBlockImportResult::WrongHeader
 				},
 		}
 	}
 
+	/// Check the target block justifications against the proven authority set, if enabled.
+	fn verify_target_justifications(
+		&self,
+		header: &B::Header,
+		justifications: &Option<Justifications>,
+	) -> bool {
+		if !self.options.verify_target_justifications {
+			return true
+		}
+
+		let Some((set_id, authorities, warp_sync_provider)) = &self.proven_authority_set else {
+			// The target block was set externally and is trusted.
+			return true
+		};
+
+		let Some(justifications) = justifications else {
+			log::debug!(target: "sync", "Importing target block failed: missing justifications.");
+			return false
+		};
+
+		match warp_sync_provider.verify_justifications(
+			header,
+			justifications,
+			*set_id,
+			authorities.clone(),
+		) {
+			Ok(()) => true,
+			Err(e) => {
+				log::debug!(
+					target: "sync",
+					"Importing target block failed: bad justifications: {}",
+					e,
+				);
+				false
+			},
+		}
+	}
+
+	/// Import a batch of blocks downloaded while collecting the finality history.
+	///
+	/// The blocks must be consecutive, start at the requested block and precede the target block.
+	pub fn import_finality_history(
+		&mut self,
+		blocks: Vec<BlockData<B>>,
+	) -> FinalityHistoryImportResult {
+		let Phase::FinalityHistory { target_header, next, .. } = &mut self.phase else {
+			log::debug!(target: "sync", "Unexpected finality history response");
+			return FinalityHistoryImportResult::BadResponse
+		};
+
+		if blocks.is_empty() {
+			log::debug!(target: "sync", "Importing finality history failed: empty response.");
+			return FinalityHistoryImportResult::BadResponse
+		}
+
+		let mut expected = *next;
+		for block in &blocks {
+			match &block.header {
+				Some(header)
+					if *header.number() == expected && expected < *target_header.number() =>
+					expected += One::one(),
+				_ => {
+					log::debug!(
+						target: "sync",
+						"Importing finality history failed: unexpected block {:?}.",
+						block.hash,
+					);
+					return FinalityHistoryImportResult::BadResponse
+				},
+			}
+		}
+		*next = expected;
+
+		let room = self.options.max_finality_history.saturating_sub(self.finality_history.len());
+		self.finality_history.extend(
+			blocks
+				.into_iter()
+				.filter_map(|block| {
+					let number = *block.header.as_ref()?.number();
+					Some((number, (block.hash, block.justifications?)))
+				})
+				.take(room),
+		);
+
+		self.maybe_finish_finality_history();
+		FinalityHistoryImportResult::Success
+	}
+
+	/// Stop downloading the finality history and continue with the state download, keeping the
+	/// justifications collected so far.
+	pub fn skip_finality_history(&mut self) {
+		if let Phase::FinalityHistory { .. } = self.phase {
+			log::debug!(target: "sync", "Finality history download cancelled.");
+			self.finish_finality_history();
+		}
+	}
+
+	/// Justifications of finalized blocks collected before the target block, keyed by block
+	/// number. Only populated if [`WarpSyncOptions::collect_finality_history`] is enabled.
+	pub fn finality_history(&self) -> &BTreeMap<NumberFor<B>, (B::Hash, Justifications)> {
+		&self.finality_history
+	}
+
+	/// Move on to the state download if the finality history is complete or the limit of
+	/// collected justifications is reached.
+	fn maybe_finish_finality_history(&mut self) {
+		let Phase::FinalityHistory { target_header, next, .. } = &self.phase else { return };
+
+		if *next >= *target_header.number() ||
+			self.finality_history.len() >= self.options.max_finality_history
+		{
+			log::debug!(
+				target: "sync",
+				"Collected {} justifications of finality history.",
+				self.finality_history.len(),
+			);
+			self.finish_finality_history();
+		}
+	}
+
+	fn finish_finality_history(&mut self) {
+		let phase = std::mem::replace(&mut self.phase, Phase::PendingTargetBlock);
+		let Phase::FinalityHistory { target_header, target_body, target_justifications, .. } =
+			phase
+		else {
+			self.phase = phase;
+			return
+		};
+
+		self.start_state_sync(target_header, target_body, target_justifications);
+	}
+
+	fn start_state_sync(
+		&mut self,
+		header: B::Header,
+		body: Option<Vec<B::Extrinsic>>,
+		justifications: Option<Justifications>,
+	) {
+		let state_sync = StateSync::new(self.client.clone(), header, body, justifications, false);
+		self.phase = Phase::State(state_sync);
+	}
+
 	/// Produce next state request.
 	pub fn next_state_request(&self) -> Option<StateRequest> {
 		match &self.phase {
-			Phase::WarpProof { .. } | Phase::TargetBlock(_) | Phase::PendingTargetBlock { .. } =>
-				None,
+			Phase::WarpProof { .. } |
+			Phase::TargetBlock(_) |
+			Phase::PendingTargetBlock { .. } |
+			Phase::FinalityHistory { .. } => None,
 			Phase::State(sync) => Some(sync.next_request()),
 		}
 	}
@@ -328,14 +757,36 @@ where
 	pub fn next_warp_proof_request(&self) -> Option<WarpProofRequest<B>> {
 		match &self.phase {
 			Phase::WarpProof { last_hash, .. } => Some(WarpProofRequest { begin: *last_hash }),
-			Phase::TargetBlock(_) | Phase::State(_) | Phase::PendingTargetBlock { .. } => None,
+			Phase::TargetBlock(_) |
+			Phase::State(_) |
+			Phase::PendingTargetBlock { .. } |
+			Phase::FinalityHistory { .. } => None,
 		}
 	}
 
+	/// Produce next finality history request.
+	pub fn next_finality_history_request(&self) -> Option<(NumberFor<B>, BlockRequest<B>)> {
+		let Phase::FinalityHistory { target_header, next, .. } = &self.phase else { return None };
+
+		let remaining: u32 = target_header.number().saturating_sub(*next).saturated_into();
+		let request = BlockRequest::<B> {
+			id: 0,
+			fields: BlockAttributes::HEADER | BlockAttributes::JUSTIFICATION,
+			from: FromBlock::Number(*next),
+			direction: Direction::Ascending,
+			max: Some(remaining.min(FINALITY_HISTORY_BLOCKS_PER_REQUEST)),
+		};
+
+		Some((*target_header.number(), request))
+	}
+
 	/// Produce next target block request.
 	pub fn next_target_block_request(&self) -> Option<(NumberFor<B>, BlockRequest<B>)> {
 		match &self.phase {
-			Phase::WarpProof { .. } | Phase::State(_) | Phase::PendingTargetBlock { .. } => None,
+			Phase::WarpProof { .. } |
+			Phase::State(_) |
+			Phase::PendingTargetBlock { .. } |
+			Phase::FinalityHistory { .. } => None,
 			Phase::TargetBlock(header) => {
 				let request = BlockRequest::<B> {
 					id: 0,
@@ -353,8 +804,10 @@ where
 	/// Return target block hash if it is known.
 	pub fn target_block_hash(&self) -> Option<B::Hash> {
 		match &self.phase {
-			Phase::WarpProof { .. } | Phase::TargetBlock(_) | Phase::PendingTargetBlock { .. } =>
-				None,
+			Phase::WarpProof { .. } |
+			Phase::TargetBlock(_) |
+			Phase::PendingTargetBlock { .. } |
+			Phase::FinalityHistory { .. } => None,
 			Phase::State(s) => Some(s.target()),
 		}
 	}
@@ -364,6 +817,7 @@ where
 		match &self.phase {
 			Phase::WarpProof { .. } | Phase::PendingTargetBlock { .. } => None,
 			Phase::TargetBlock(header) => Some(*header.number()),
+			Phase::FinalityHistory { target_header, .. } => Some(*target_header.number()),
 			Phase::State(s) => Some(s.target_block_num()),
 		}
 	}
@@ -371,26 +825,75 @@ where
 	/// Check if the state is complete.
 	pub fn is_complete(&self) -> bool {
 		match &self.phase {
-			Phase::WarpProof { .. } | Phase::TargetBlock(_) | Phase::PendingTargetBlock { .. } =>
-				false,
+			Phase::WarpProof { .. } |
+			Phase::TargetBlock(_) |
+			Phase::PendingTargetBlock { .. } |
+			Phase::FinalityHistory { .. } => false,
 			Phase::State(sync) => sync.is_complete(),
 		}
 	}
 
+	/// Returns the number of bytes the current phase is still expected to download.
+	///
+	/// Only known while downloading warp proofs with
+	/// [`WarpSyncOptions::estimated_proof_bytes`] set, `0` once the estimate is exceeded. The size
+	/// of the target block is not known before it is downloaded.
+	pub fn remaining_bytes(&self) -> Option<u64> {
+		match self.phase {
+			Phase::WarpProof { .. } => self
+				.options
+				.estimated_proof_bytes
+				.map(|estimate| estimate.saturating_sub(self.total_proof_bytes)),
+			Phase::PendingTargetBlock |
+			Phase::TargetBlock(_) |
+			Phase::FinalityHistory { .. } |
+			Phase::State(_) => None,
+		}
+	}
+
+	/// Number of consecutive warp proofs for the current request that failed verification.
+	pub fn warp_proof_failures(&self) -> u32 {
+		match self.phase {
+			Phase::WarpProof { failures, .. } => failures,
+			Phase::PendingTargetBlock |
+			Phase::TargetBlock(_) |
+			Phase::FinalityHistory { .. } |
+			Phase::State(_) => 0,
+		}
+	}
+
 	/// Returns state sync estimated progress (percentage, bytes)
 	pub fn progress(&self) -> WarpSyncProgress<B> {
+		let milestone = self.milestone();
+		let remaining_bytes = self.remaining_bytes();
 		match &self.phase {
 			Phase::WarpProof { .. } => WarpSyncProgress {
 				phase: WarpSyncPhase::DownloadingWarpProofs,
 				total_bytes: self.total_proof_bytes,
+				milestone,
+				received_bytes: self.received_proof_bytes,
+				remaining_bytes,
 			},
 			Phase::TargetBlock(_) => WarpSyncProgress {
 				phase: WarpSyncPhase::DownloadingTargetBlock,
 				total_bytes: self.total_proof_bytes,
+				milestone,
+				received_bytes: self.received_proof_bytes,
+				remaining_bytes,
 			},
 			Phase::PendingTargetBlock { .. } => WarpSyncProgress {
 				phase: WarpSyncPhase::AwaitingTargetBlock,
 				total_bytes: self.total_proof_bytes,
+				milestone,
+				received_bytes: self.received_proof_bytes,
+				remaining_bytes,
+			},
+			Phase::FinalityHistory { .. } => WarpSyncProgress {
+				phase: WarpSyncPhase::DownloadingFinalityHistory,
+				total_bytes: self.total_proof_bytes,
+				milestone,
+				received_bytes: self.received_proof_bytes,
+				remaining_bytes,
 			},
 			Phase::State(sync) => WarpSyncProgress {
 				phase: if self.is_complete() {
@@ -399,7 +902,764 @@ where
 					WarpSyncPhase::DownloadingState
 				},
 				total_bytes: self.total_proof_bytes + sync.progress().size,
+				milestone,
+				received_bytes: self.received_proof_bytes + sync.progress().size,
+				remaining_bytes,
+			},
+		}
+	}
+
+	/// Name of the last milestone whose authority set has been reached by the warp proofs.
+	fn milestone(&self) -> Option<String> {
+		let set_id = match (&self.phase, &self.proven_authority_set) {
+			(Phase::WarpProof { set_id, .. }, _) | (_, Some((set_id, _, _))) => *set_id,
+			_ => return None,
+		};
+
+		self.milestones
+			.iter()
+			.take_while(|(milestone_set_id, _)| *milestone_set_id <= set_id)
+			.last()
+			.map(|(_, name)| name.clone())
+	}
+}
+
+#[cfg(test)]
+pub(crate) mod test {
+	use super::*;
+	use quickcheck::{Arbitrary, Gen, QuickCheck};
+	use sp_runtime::traits::Header as HeaderT;
+	use substrate_test_runtime_client::{
+		runtime::{Block, Hash, Header},
+		DefaultTestClientBuilderExt, TestClient, TestClientBuilder, TestClientBuilderExt,
+	};
+
+	mockall::mock! {
+		pub WarpSyncProvider<B: BlockT> {}
+
+		impl<B: BlockT> super::WarpSyncProvider<B> for WarpSyncProvider<B> {
+			fn generate(
+				&self,
+				start: B::Hash,
+			) -> Result<EncodedProof, Box<dyn std::error::Error + Send + Sync>>;
+			fn verify(
+				&self,
+				proof: &EncodedProof,
+				set_id: SetId,
+				authorities: AuthorityList,
+			) -> Result<VerificationResult<B>, Box<dyn std::error::Error + Send + Sync>>;
+			fn verify_justifications(
+				&self,
+				header: &B::Header,
+				justifications: &Justifications,
+				set_id: SetId,
+				authorities: AuthorityList,
+			) -> Result<(), Box<dyn std::error::Error + Send + Sync>>;
+			fn current_authorities(&self) -> AuthorityList;
+		}
+	}
+
+	fn header(number: u64) -> Header {
+		Header::new(
+			number,
+			Default::default(),
+			Default::default(),
+			Hash::repeat_byte(number as u8),
+			Default::default(),
+		)
+	}
+
+	fn target_block(header: &Header) -> BlockData<Block> {
+		BlockData::<Block> {
+			hash: header.hash(),
+			header: Some(header.clone()),
+			body: Some(Vec::new()),
+			indexed_body: None,
+			receipt: None,
+			message_queue: None,
+			justification: None,
+			justifications: None,
+		}
+	}
+
+	fn history_block(number: u64, justified: bool) -> BlockData<Block> {
+		let header = header(number);
+		BlockData::<Block> {
+			hash: header.hash(),
+			header: Some(header),
+			body: None,
+			indexed_body: None,
+			receipt: None,
+			message_queue: None,
+			justification: None,
+			justifications: justified.then(|| Justifications::from((*b"FRNK", vec![number as u8]))),
+		}
+	}
+
+	fn warp_sync_in_target_block_phase(
+		options: WarpSyncOptions,
+		target: &Header,
+	) -> WarpSync<Block, TestClient> {
+		let client = Arc::new(TestClientBuilder::new().build());
+		let mut warp_sync =
+			WarpSync::new_with_options(client, WarpSyncConfig::WaitForTarget, options).unwrap();
+		warp_sync.set_target_block(target.clone());
+
+		warp_sync
+	}
+
+	/// Create a warp sync whose warp proofs have been fully verified with `target` as target block.
+	fn warp_sync_with_proven_target(
+		options: WarpSyncOptions,
+		target: &Header,
+		mut provider: MockWarpSyncProvider<Block>,
+	) -> WarpSync<Block, TestClient> {
+		let client = Arc::new(TestClientBuilder::new().build());
+		provider.expect_current_authorities().returning(Vec::new);
+		let proven_target = target.clone();
+		provider.expect_verify().returning(move |_, _, _| {
+			Ok(VerificationResult::Complete(1, Vec::new(), proven_target.clone()))
+		});
+
+		let mut warp_sync = WarpSync::new_with_options(
+			client,
+			WarpSyncConfig::WithProvider(Arc::new(provider)),
+			options,
+		)
+		.unwrap();
+		assert!(matches!(
+			warp_sync.import_warp_proof(EncodedProof(Vec::new())),
+			WarpProofImportResult::Success
+		));
+
+		warp_sync
+	}
+
+	#[test]
+	fn target_justifications_are_not_verified_by_default() {
+		let target = header(5);
+		let mut provider = MockWarpSyncProvider::<Block>::new();
+		provider.expect_verify_justifications().never();
+		let mut warp_sync = warp_sync_with_proven_target(Default::default(), &target, provider);
+
+		let mut block = target_block(&target);
+		block.justifications = Some(Justifications::from((*b"FRNK", vec![2])));
+		assert!(matches!(warp_sync.import_target_block(block), TargetBlockImportResult::Success));
+	}
+
+	#[test]
+	fn target_justifications_are_verified_when_enabled() {
+		let target = header(5);
+		let mut provider = MockWarpSyncProvider::<Block>::new();
+		provider
+			.expect_verify_justifications()
+			.returning(|_, justifications, set_id, _| {
+				assert_eq!(set_id, 1);
+				if justifications.get(*b"FRNK") == Some(&vec![1]) {
+					Ok(())
+				} else {
+					Err("Invalid justification".into())
+				}
+			});
+		let options = WarpSyncOptions { verify_target_justifications: true, ..Default::default() };
+		let mut warp_sync = warp_sync_with_proven_target(options, &target, provider);
+
+		// Justifications are required.
+		assert!(matches!(
+			warp_sync.import_target_block(target_block(&target)),
+			TargetBlockImportResult::BadResponse
+		));
+
+		let mut block = target_block(&target);
+		block.justifications = Some(Justifications::from((*b"FRNK", vec![2])));
+		assert!(matches!(
+			warp_sync.import_target_block(block),
+			TargetBlockImportResult::BadResponse
+		));
+		assert!(warp_sync.next_state_request().is_none());
+
+		let mut block = target_block(&target);
+		block.justifications = Some(Justifications::from((*b"FRNK", vec![1])));
+		assert!(matches!(warp_sync.import_target_block(block), TargetBlockImportResult::Success));
+		assert!(warp_sync.next_state_request().is_some());
+	}
+
+	/// Provider that relies on the default [`WarpSyncProvider::verify_justifications`].
+	struct ProviderWithoutJustifications(Header);
+
+	impl WarpSyncProvider<Block> for ProviderWithoutJustifications {
+		fn generate(
+			&self,
+			_start: Hash,
+		) -> Result<EncodedProof, Box<dyn std::error::Error + Send + Sync>> {
+			unimplemented!()
+		}
+		fn verify(
+			&self,
+			_proof: &EncodedProof,
+			_set_id: SetId,
+			_authorities: AuthorityList,
+		) -> Result<VerificationResult<Block>, Box<dyn std::error::Error + Send + Sync>> {
+			Ok(VerificationResult::Complete(1, Vec::new(), self.0.clone()))
+		}
+		fn current_authorities(&self) -> AuthorityList {
+			Vec::new()
+		}
+	}
+
+	#[test]
+	fn target_justifications_are_rejected_without_provider_support() {
+		let target = header(5);
+		let client = Arc::new(TestClientBuilder::new().build());
+		let options = WarpSyncOptions { verify_target_justifications: true, ..Default::default() };
+		let provider = Arc::new(ProviderWithoutJustifications(target.clone()));
+		let mut warp_sync =
+			WarpSync::new_with_options(client, WarpSyncConfig::WithProvider(provider), options)
+				.unwrap();
+		assert!(matches!(
+			warp_sync.import_warp_proof(EncodedProof(Vec::new())),
+			WarpProofImportResult::Success
+		));
+
+		let mut block = target_block(&target);
+		block.justifications = Some(Justifications::from((*b"FRNK", vec![1])));
+		assert!(matches!(
+			warp_sync.import_target_block(block),
+			TargetBlockImportResult::BadResponse
+		));
+		assert!(warp_sync.next_state_request().is_none());
+	}
+
+	#[test]
+	fn target_block_import_failures_are_distinguished() {
+		let target = header(5);
+		let mut warp_sync =
+			warp_sync_with_proven_target(Default::default(), &target, MockWarpSyncProvider::new());
+
+		assert!(matches!(
+			warp_sync.import_target_block(target_block(&header(6))),
+			TargetBlockImportResult::WrongHeader
+		));
+
+		let mut block = target_block(&target);
+		block.header = None;
+		assert!(matches!(
+			warp_sync.import_target_block(block),
+			TargetBlockImportResult::WrongHeader
+		));
+
+		let mut block = target_block(&target);
+		block.body = None;
+		assert!(matches!(
+			warp_sync.import_target_block(block),
+			TargetBlockImportResult::MissingBody
+		));
+
+		assert!(matches!(
+			warp_sync.import_target_block(target_block(&target)),
+			TargetBlockImportResult::Success
+		));
+	}
+
+	#[test]
+	fn finality_history_is_not_collected_by_default() {
+		let target = header(5);
+		let mut warp_sync = warp_sync_in_target_block_phase(Default::default(), &target);
+
+		assert!(matches!(
+			warp_sync.import_target_block(target_block(&target)),
+			TargetBlockImportResult::Success
+		));
+		assert!(warp_sync.next_finality_history_request().is_none());
+		assert!(warp_sync.next_state_request().is_some());
+		assert!(warp_sync.finality_history().is_empty());
+	}
+
+	#[test]
+	fn finality_history_is_collected_when_enabled() {
+		let target = header(5);
+		let options = WarpSyncOptions { collect_finality_history: true, ..Default::default() };
+		let mut warp_sync = warp_sync_in_target_block_phase(options, &target);
+
+		assert!(matches!(
+			warp_sync.import_target_block(target_block(&target)),
+			TargetBlockImportResult::Success
+		));
+		assert!(matches!(warp_sync.progress().phase, WarpSyncPhase::DownloadingFinalityHistory));
+		assert!(warp_sync.next_state_request().is_none());
+
+		let (target_number, request) = warp_sync.next_finality_history_request().unwrap();
+		assert_eq!(target_number, 5);
+		assert_eq!(request.from, FromBlock::Number(1));
+		assert_eq!(request.max, Some(4));
+
+		let blocks = (1..5).map(|n| history_block(n, n % 2 == 0)).collect();
+		assert!(matches!(
+			warp_sync.import_finality_history(blocks),
+			FinalityHistoryImportResult::Success
+		));
+
+		assert_eq!(warp_sync.finality_history().keys().copied().collect::<Vec<_>>(), vec![2, 4]);
+		assert!(warp_sync.next_finality_history_request().is_none());
+		assert!(warp_sync.next_state_request().is_some());
+	}
+
+	#[test]
+	fn finality_history_is_bounded() {
+		let target = header(5);
+		let options = WarpSyncOptions {
+			collect_finality_history: true,
+			max_finality_history: 1,
+			..Default::default()
+		};
+		let mut warp_sync = warp_sync_in_target_block_phase(options, &target);
+		warp_sync.import_target_block(target_block(&target));
+
+		let blocks = vec![history_block(1, true), history_block(2, true)];
+		assert!(matches!(
+			warp_sync.import_finality_history(blocks),
+			FinalityHistoryImportResult::Success
+		));
+
+		assert_eq!(warp_sync.finality_history().len(), 1);
+		assert!(warp_sync.next_state_request().is_some());
+	}
+
+	#[test]
+	fn finality_history_download_is_bounded() {
+		let target = header(100);
+		let options = WarpSyncOptions {
+			collect_finality_history: true,
+			max_finality_history_blocks: 10,
+			..Default::default()
+		};
+		let mut warp_sync = warp_sync_in_target_block_phase(options, &target);
+		warp_sync.import_target_block(target_block(&target));
+
+		let (_, request) = warp_sync.next_finality_history_request().unwrap();
+		assert_eq!(request.from, FromBlock::Number(90));
+		assert_eq!(request.max, Some(10));
+
+		let blocks = (90..100).map(|n| history_block(n, true)).collect();
+		assert!(matches!(
+			warp_sync.import_finality_history(blocks),
+			FinalityHistoryImportResult::Success
+		));
+
+		assert_eq!(warp_sync.finality_history().len(), 10);
+		assert!(warp_sync.next_finality_history_request().is_none());
+		assert!(warp_sync.next_state_request().is_some());
+	}
+
+	#[test]
+	fn finality_history_rejects_unexpected_blocks() {
+		let target = header(5);
+		let options = WarpSyncOptions { collect_finality_history: true, ..Default::default() };
+		let mut warp_sync = warp_sync_in_target_block_phase(options, &target);
+		warp_sync.import_target_block(target_block(&target));
+
+		// Gap between blocks.
+		let blocks = vec![history_block(1, true), history_block(3, true)];
+		assert!(matches!(
+			warp_sync.import_finality_history(blocks),
+			FinalityHistoryImportResult::BadResponse
+		));
+		// Blocks past the target.
+		let blocks = (1..7).map(|n| history_block(n, false)).collect();
+		assert!(matches!(
+			warp_sync.import_finality_history(blocks),
+			FinalityHistoryImportResult::BadResponse
+		));
+		assert!(warp_sync.finality_history().is_empty());
+	}
+
+	#[test]
+	fn finality_history_can_be_skipped() {
+		let target = header(5);
+		let options = WarpSyncOptions { collect_finality_history: true, ..Default::default() };
+		let mut warp_sync = warp_sync_in_target_block_phase(options, &target);
+		warp_sync.import_target_block(target_block(&target));
+
+		warp_sync.skip_finality_history();
+
+		assert!(warp_sync.next_finality_history_request().is_none());
+		assert!(warp_sync.next_state_request().is_some());
+	}
+
+	/// Request produced by [`WarpSync`] that its owner has to perform.
+	#[derive(Debug)]
+	enum WarpSyncAction {
+		WarpProof(WarpProofRequest<Block>),
+		TargetBlock(BlockRequest<Block>),
+		FinalityHistory(BlockRequest<Block>),
+		State(StateRequest),
+	}
+
+	/// Simulated result of performing a [`WarpSyncAction`].
+	#[derive(Clone, Copy, Debug)]
+	enum SimulatedOutcome {
+		/// The peer answered with a valid and complete response.
+		Valid,
+		/// The peer answered with a valid response covering only part of the request.
+		Partial,
+		/// The peer answered with a response that fails verification.
+		Invalid,
+		/// The request failed, nothing is fed back to warp sync.
+		Failed,
+	}
+
+	impl Arbitrary for SimulatedOutcome {
+		fn arbitrary(g: &mut Gen) -> Self {
+			*g.choose(&[Self::Valid, Self::Partial, Self::Invalid, Self::Failed]).unwrap()
+		}
+	}
+
+	/// The action warp sync currently wants its owner to perform.
+	fn next_action(warp_sync: &WarpSync<Block, TestClient>) -> Option<WarpSyncAction> {
+		if let Some(request) = warp_sync.next_warp_proof_request() {
+			return Some(WarpSyncAction::WarpProof(request))
+		}
+		if let Some((_, request)) = warp_sync.next_target_block_request() {
+			return Some(WarpSyncAction::TargetBlock(request))
+		}
+		if let Some((_, request)) = warp_sync.next_finality_history_request() {
+			return Some(WarpSyncAction::FinalityHistory(request))
+		}
+		warp_sync.next_state_request().map(WarpSyncAction::State)
+	}
+
+	/// Feed the simulated `outcome` of `action` back into `warp_sync`, with `target` being the
+	/// block the warp proofs lead to.
+	fn apply_action_result(
+		warp_sync: &mut WarpSync<Block, TestClient>,
+		action: WarpSyncAction,
+		outcome: SimulatedOutcome,
+		target: &Header,
+	) {
+		use SimulatedOutcome::*;
+
+		match (action, outcome) {
+			(_, Failed) => {},
+			(WarpSyncAction::WarpProof(_), outcome) => {
+				let proof = match outcome {
+					Valid => vec![1],
+					Partial => vec![0],
+					_ => vec![2],
+				};
+				warp_sync.import_warp_proof(EncodedProof(proof));
 			},
+			(WarpSyncAction::TargetBlock(_), outcome) => {
+				let mut block = match outcome {
+					Valid => target_block(target),
+					_ => target_block(&header(*target.number() + 1)),
+				};
+				block.justifications = Some(Justifications::from((*b"FRNK", vec![1])));
+				warp_sync.import_target_block(block);
+			},
+			(WarpSyncAction::FinalityHistory(request), outcome) => {
+				let FromBlock::Number(from) = request.from else {
+					panic!("Finality history is requested by number")
+				};
+				let count = match outcome {
+					Valid => request.max.unwrap_or(1) as u64,
+					_ => 1,
+				};
+				let mut blocks: Vec<_> =
+					(from..from + count).map(|n| history_block(n, true)).collect();
+				if let Invalid = outcome {
+					blocks = vec![history_block(*target.number() + 1, true)];
+				}
+				warp_sync.import_finality_history(blocks);
+			},
+			(WarpSyncAction::State(_), _) => {
+				// Only invalid state responses can be simulated without a full state proof.
+				warp_sync.import_state(StateResponse::default());
+			},
+		}
+	}
+
+	fn phase_rank(warp_sync: &WarpSync<Block, TestClient>) -> u8 {
+		match warp_sync.progress().phase {
+			WarpSyncPhase::DownloadingWarpProofs => 0,
+			WarpSyncPhase::DownloadingTargetBlock => 1,
+			WarpSyncPhase::DownloadingFinalityHistory => 2,
+			WarpSyncPhase::DownloadingState => 3,
+			phase => panic!("Unexpected warp sync phase: {phase}"),
+		}
+	}
+
+	#[test]
+	fn random_action_outcomes_drive_warp_sync_to_state_download() {
+		fn property(
+			outcomes: Vec<SimulatedOutcome>,
+			collect_finality_history: bool,
+			verify_target_justifications: bool,
+		) {
+			let target = header(5);
+			let proven_target = target.clone();
+			let mut provider = MockWarpSyncProvider::<Block>::new();
+			provider.expect_current_authorities().returning(Vec::new);
+			provider.expect_verify().returning(move |proof, set_id, _| match proof.0[..] {
+				[0] => Ok(VerificationResult::Partial(set_id + 1, Vec::new(), Hash::random())),
+				[1] =>
+					Ok(VerificationResult::Complete(set_id + 1, Vec::new(), proven_target.clone())),
+				_ => Err("Invalid proof".into()),
+			});
+			provider.expect_verify_justifications().returning(|_, _, _, _| Ok(()));
+
+			let options = WarpSyncOptions {
+				collect_finality_history,
+				verify_target_justifications,
+				..Default::default()
+			};
+			let client = Arc::new(TestClientBuilder::new().build());
+			let mut warp_sync = WarpSync::new_with_options(
+				client,
+				WarpSyncConfig::WithProvider(Arc::new(provider)),
+				options,
+			)
+			.unwrap();
+
+			// Whatever happens, warp sync never moves backwards and always has something to do.
+			let mut rank = phase_rank(&warp_sync);
+			for outcome in outcomes {
+				let action = next_action(&warp_sync).expect("Warp sync always has an action");
+				apply_action_result(&mut warp_sync, action, outcome, &target);
+
+				let new_rank = phase_rank(&warp_sync);
+				assert!(new_rank >= rank);
+				rank = new_rank;
+			}
+
+			// Once peers behave, warp sync reaches the state download.
+			for _ in 0..10 {
+				let action = next_action(&warp_sync).expect("Warp sync always has an action");
+				if let WarpSyncAction::State(_) = action {
+					assert_eq!(warp_sync.target_block_number(), Some(5));
+					return
+				}
+				apply_action_result(&mut warp_sync, action, SimulatedOutcome::Valid, &target);
+			}
+			panic!("Warp sync did not reach the state download");
 		}
+
+		QuickCheck::new().quickcheck(property as fn(Vec<SimulatedOutcome>, bool, bool))
+	}
+
+	fn authorities(seeds: &[u8]) -> AuthorityList {
+		seeds
+			.iter()
+			.map(|seed| (sp_core::ed25519::Public::from_raw([*seed; 32]).into(), 1))
+			.collect()
+	}
+
+	fn provider_with_genesis_authorities(genesis: AuthorityList) -> MockWarpSyncProvider<Block> {
+		let mut provider = MockWarpSyncProvider::<Block>::new();
+		provider.expect_current_authorities().returning(move || genesis.clone());
+		provider
+	}
+
+	#[test]
+	fn warp_sync_refuses_to_start_with_mismatched_genesis_authorities() {
+		let client = Arc::new(TestClientBuilder::new().build());
+		let provider = provider_with_genesis_authorities(authorities(&[1, 2]));
+		let options = WarpSyncOptions {
+			genesis_authorities: Some((0, authorities(&[1, 3]))),
+			..Default::default()
+		};
+
+		let res = WarpSync::<Block, _>::new_with_options(
+			client,
+			WarpSyncConfig::WithProvider(Arc::new(provider)),
+			options,
+		);
+
+		assert!(matches!(res, Err(WarpSyncStartError::GenesisAuthoritiesMismatch)));
+	}
+
+	#[test]
+	fn warp_sync_starts_from_matching_genesis_authorities() {
+		let client = Arc::new(TestClientBuilder::new().build());
+		let mut provider = provider_with_genesis_authorities(authorities(&[1, 2]));
+		provider.expect_verify().returning(|_, set_id, set| {
+			assert_eq!(set_id, 7);
+			assert_eq!(set, authorities(&[1, 2]));
+			Err("Invalid proof".into())
+		});
+		let options = WarpSyncOptions {
+			genesis_authorities: Some((7, authorities(&[1, 2]))),
+			..Default::default()
+		};
+
+		let mut warp_sync = WarpSync::<Block, _>::new_with_options(
+			client,
+			WarpSyncConfig::WithProvider(Arc::new(provider)),
+			options,
+		)
+		.unwrap();
+
+		assert!(warp_sync.next_warp_proof_request().is_some());
+		assert!(matches!(
+			warp_sync.import_warp_proof(EncodedProof(Vec::new())),
+			WarpProofImportResult::BadResponse
+		));
+	}
+
+	#[test]
+	fn remaining_bytes_decrease_as_proofs_are_imported() {
+		let client = Arc::new(TestClientBuilder::new().build());
+		let mut provider = provider_with_genesis_authorities(authorities(&[1]));
+		provider
+			.expect_verify()
+			.returning(|_, set_id, set| Ok(VerificationResult::Partial(set_id, set, Hash::zero())));
+		let options = WarpSyncOptions { estimated_proof_bytes: Some(100), ..Default::default() };
+
+		let mut warp_sync = WarpSync::<Block, _>::new_with_options(
+			client,
+			WarpSyncConfig::WithProvider(Arc::new(provider)),
+			options,
+		)
+		.unwrap();
+
+		assert_eq!(warp_sync.remaining_bytes(), Some(100));
+		for remaining in [60, 20, 0] {
+			warp_sync.import_warp_proof(EncodedProof(vec![0; 40]));
+			assert_eq!(warp_sync.remaining_bytes(), Some(remaining));
+		}
+	}
+
+	#[test]
+	fn remaining_bytes_are_unknown_without_estimate() {
+		let client = Arc::new(TestClientBuilder::new().build());
+		let warp_sync = WarpSync::<Block, _>::new(
+			client,
+			WarpSyncConfig::WithProvider(Arc::new(provider_with_genesis_authorities(Vec::new()))),
+		);
+
+		assert_eq!(warp_sync.remaining_bytes(), None);
+	}
+
+	#[test]
+	fn downloaded_state_bytes_are_reported_in_progress() {
+		let client = TestClientBuilder::new().build();
+		let genesis_hash = client.info().genesis_hash;
+		let genesis = client.header(genesis_hash).unwrap().unwrap();
+		let mut warp_sync =
+			warp_sync_with_proven_target(Default::default(), &genesis, MockWarpSyncProvider::new());
+		assert!(matches!(
+			warp_sync.import_target_block(target_block(&genesis)),
+			TargetBlockImportResult::Success
+		));
+
+		let progress = warp_sync.progress();
+		assert!(matches!(progress.phase, WarpSyncPhase::DownloadingState));
+		assert_eq!(progress.total_bytes, 0);
+
+		let (proof, _) = client.read_proof_collection(genesis_hash, &[], usize::MAX).unwrap();
+		let proof = proof.encode();
+		let proof_bytes = proof.len() as u64;
+		assert!(matches!(
+			warp_sync.import_state(StateResponse { entries: Vec::new(), proof }),
+			ImportResult::Import(..)
+		));
+
+		// Both the proof and the imported keys are accounted for.
+		let progress = warp_sync.progress();
+		assert!(matches!(progress.phase, WarpSyncPhase::ImportingState));
+		assert!(progress.total_bytes > proof_bytes);
+	}
+
+	#[test]
+	fn rejected_warp_proofs_only_count_as_received() {
+		let client = Arc::new(TestClientBuilder::new().build());
+		let mut provider = MockWarpSyncProvider::<Block>::new();
+		provider.expect_current_authorities().returning(Vec::new);
+		provider.expect_verify().returning(|proof, set_id, _| match proof.0[..] {
+			[0, ..] => Err("Invalid proof".into()),
+			_ => Ok(VerificationResult::Partial(set_id + 1, Vec::new(), Hash::random())),
+		});
+		let mut warp_sync = WarpSync::new(client, WarpSyncConfig::WithProvider(Arc::new(provider)));
+
+		assert!(matches!(
+			warp_sync.import_warp_proof(EncodedProof(vec![0; 100])),
+			WarpProofImportResult::BadResponse
+		));
+		let progress = warp_sync.progress();
+		assert_eq!((progress.received_bytes, progress.total_bytes), (100, 0));
+
+		assert!(matches!(
+			warp_sync.import_warp_proof(EncodedProof(vec![1; 30])),
+			WarpProofImportResult::Success
+		));
+		let progress = warp_sync.progress();
+		assert_eq!((progress.received_bytes, progress.total_bytes), (130, 30));
+	}
+
+	/// Provider whose proofs each move two authority sets forward, with a milestone at sets 3
+	/// and 6.
+	struct MilestoneProvider;
+
+	impl WarpSyncProvider<Block> for MilestoneProvider {
+		fn generate(
+			&self,
+			_start: Hash,
+		) -> Result<EncodedProof, Box<dyn std::error::Error + Send + Sync>> {
+			unimplemented!()
+		}
+
+		fn verify(
+			&self,
+			_proof: &EncodedProof,
+			set_id: SetId,
+			_authorities: AuthorityList,
+		) -> Result<VerificationResult<Block>, Box<dyn std::error::Error + Send + Sync>> {
+			Ok(VerificationResult::Partial(set_id + 2, Vec::new(), Hash::random()))
+		}
+
+		fn verify_justifications(
+			&self,
+			_header: &Header,
+			_justifications: &Justifications,
+			_set_id: SetId,
+			_authorities: AuthorityList,
+		) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+			Ok(())
+		}
+
+		fn current_authorities(&self) -> AuthorityList {
+			Vec::new()
+		}
+
+		fn milestones(&self) -> Vec<(SetId, String)> {
+			vec![(6, "era 2".into()), (3, "era 1".into())]
+		}
+	}
+
+	#[test]
+	fn progress_reports_last_passed_milestone() {
+		let client = Arc::new(TestClientBuilder::new().build());
+		let mut warp_sync =
+			WarpSync::new(client, WarpSyncConfig::WithProvider(Arc::new(MilestoneProvider)));
+
+		let mut milestones = vec![warp_sync.progress().milestone];
+		for _ in 0..4 {
+			assert!(matches!(
+				warp_sync.import_warp_proof(EncodedProof(Vec::new())),
+				WarpProofImportResult::Success
+			));
+			milestones.push(warp_sync.progress().milestone);
+		}
+
+		// Proven sets are 0, 2, 4, 6 and 8.
+		assert_eq!(
+			milestones,
+			vec![
+				None,
+				None,
+				Some("era 1".to_string()),
+				Some("era 2".to_string()),
+				Some("era 2".to_string())
+			]
+		);
 	}
 }
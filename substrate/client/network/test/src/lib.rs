@@ -67,7 +67,8 @@ use sc_network_sync::{
 	service::{network::NetworkServiceProvider, syncing_service::SyncingService},
 	state_request_handler::StateRequestHandler,
 	warp::{
-		AuthorityList, EncodedProof, SetId, VerificationResult, WarpSyncParams, WarpSyncProvider,
+		AuthorityList, EncodedProof, SetId, VerificationResult, WarpSyncOptions, WarpSyncParams,
+		WarpSyncProvider,
 	},
 	warp_request_handler,
 };
@@ -699,6 +700,8 @@ pub struct FullPeerConfig {
 	pub storage_chain: bool,
 	/// Optional target block header to sync to
 	pub target_block: Option<<Block as BlockT>::Header>,
+	/// Warp sync behaviour.
+	pub warp_sync_options: WarpSyncOptions,
 }
 
 #[async_trait::async_trait]
@@ -896,6 +899,7 @@ pub trait TestNetFactory: Default + Sized + Send {
 				&fork_id,
 				block_announce_validator,
 				Some(warp_sync_params),
+				config.warp_sync_options,
 				chain_sync_network_handle,
 				import_queue.service(),
 				block_relay_params.downloader,
@@ -937,6 +941,7 @@ pub trait TestNetFactory: Default + Sized + Send {
 			fork_id,
 			metrics_registry: None,
 			block_announce_config,
+			is_major_syncing: sync_service.major_syncing_flag(),
 		})
 		.unwrap();
 
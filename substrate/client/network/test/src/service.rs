@@ -22,10 +22,11 @@ use libp2p::{Multiaddr, PeerId};
 use sc_consensus::{ImportQueue, Link};
 use sc_network::{
 	config::{self, FullNetworkConfiguration, MultiaddrWithPeerId, ProtocolId, TransportConfig},
-	event::Event,
+	event::{DhtEvent, Event},
 	peer_store::PeerStore,
 	service::traits::{NotificationEvent, ValidationResult},
-	NetworkEventStream, NetworkPeers, NetworkService, NetworkStateInfo, NetworkWorker,
+	ConnectivityStatus, DhtError, KademliaKey, NetworkDHTProvider, NetworkEventStream,
+	NetworkPeers, NetworkService, NetworkStateInfo, NetworkStatusProvider, NetworkWorker,
 	NotificationService,
 };
 use sc_network_common::role::Roles;
@@ -43,7 +44,14 @@ use substrate_test_runtime_client::{
 	TestClientBuilder, TestClientBuilderExt as _,
 };
 
-use std::{sync::Arc, time::Duration};
+use std::{
+	collections::{HashMap, HashSet},
+	sync::{
+		atomic::{AtomicBool, Ordering},
+		Arc,
+	},
+	time::Duration,
+};
 
 type TestNetworkWorker = NetworkWorker<TestBlock, TestHash>;
 type TestNetworkService = NetworkService<TestBlock, TestHash>;
@@ -81,6 +89,7 @@ struct TestNetworkBuilder {
 	chain_sync_network: Option<(NetworkServiceProvider, NetworkServiceHandle)>,
 	notification_protocols: Vec<config::NonDefaultSetConfig>,
 	config: Option<config::NetworkConfiguration>,
+	is_major_syncing: Option<Arc<AtomicBool>>,
 }
 
 impl TestNetworkBuilder {
@@ -94,6 +103,7 @@ impl TestNetworkBuilder {
 			chain_sync_network: None,
 			notification_protocols: Vec::new(),
 			config: None,
+			is_major_syncing: None,
 		}
 	}
 
@@ -117,6 +127,11 @@ impl TestNetworkBuilder {
 		self
 	}
 
+	pub fn with_major_syncing_flag(mut self, is_major_syncing: Arc<AtomicBool>) -> Self {
+		self.is_major_syncing = Some(is_major_syncing);
+		self
+	}
+
 	pub fn build(mut self) -> (TestNetwork, Option<Box<dyn NotificationService>>) {
 		let client = self.client.as_mut().map_or(
 			Arc::new(TestClientBuilder::with_default_backend().build_with_longest_chain().0),
@@ -199,6 +214,7 @@ impl TestNetworkBuilder {
 			&None,
 			Box::new(sp_consensus::block_validation::DefaultBlockAnnounceValidator),
 			None,
+			Default::default(),
 			chain_sync_network_handle,
 			import_queue.service(),
 			block_relay_params.downloader,
@@ -251,6 +267,9 @@ impl TestNetworkBuilder {
 			protocol_id,
 			fork_id,
 			metrics_registry: None,
+			is_major_syncing: self
+				.is_major_syncing
+				.unwrap_or_else(|| chain_sync_service.major_syncing_flag()),
 		})
 		.unwrap();
 
@@ -816,3 +835,464 @@ async fn ensure_public_addresses_consistent_with_transport_not_memory() {
 		.0
 		.start_network();
 }
+
+#[tokio::test]
+async fn protocol_negotiation_failure_is_reported() {
+	sp_tracing::try_init_simple();
+	// Node 1 only supports a newer version of `PROTOCOL_NAME`, without a fallback to it.
+	const NEW_PROTOCOL_NAME: &str = "/foo/2";
+
+	let listen_addr = config::build_multiaddr![Memory(rand::random::<u64>())];
+	let (config, _handle1) = config::NonDefaultSetConfig::new(
+		NEW_PROTOCOL_NAME.into(),
+		Vec::new(),
+		1024 * 1024,
+		None,
+		Default::default(),
+	);
+	let (network1, _) = TestNetworkBuilder::new()
+		.with_notification_protocol(config)
+		.with_config(config::NetworkConfiguration {
+			listen_addresses: vec![listen_addr.clone()],
+			transport: TransportConfig::MemoryOnly,
+			..config::NetworkConfiguration::new_local()
+		})
+		.build();
+	let (node1, _) = network1.start_network();
+
+	let (network2, _handle2) = TestNetworkBuilder::new()
+		.with_set_config(config::SetConfig {
+			reserved_nodes: vec![MultiaddrWithPeerId {
+				multiaddr: listen_addr,
+				peer_id: node1.local_peer_id(),
+			}],
+			..Default::default()
+		})
+		.build();
+	let (_node2, mut events2) = network2.start_network();
+
+	let event = tokio::time::timeout(Duration::from_secs(10), async {
+		loop {
+			if let Event::ProtocolNegotiationFailed { peer, protocol, .. } =
+				events2.next().await.unwrap()
+			{
+				break (peer, protocol)
+			}
+		}
+	})
+	.await
+	.expect("Negotiation failure is reported");
+
+	assert_eq!(event, (node1.local_peer_id(), PROTOCOL_NAME.into()));
+}
+
+#[tokio::test]
+async fn connected_peers_reflects_open_substreams() {
+	let (node1, handle1, node2, handle2) = build_nodes_one_proto();
+	let (mut handle1, mut handle2) = (handle1.unwrap(), handle2.unwrap());
+
+	assert!(node1.connected_peers(PROTOCOL_NAME.into()).is_empty());
+
+	tokio::spawn(async move {
+		while let Some(event) = handle2.next_event().await {
+			if let NotificationEvent::ValidateInboundSubstream { result_tx, .. } = event {
+				result_tx.send(ValidationResult::Accept).unwrap();
+			}
+		}
+	});
+
+	loop {
+		match handle1.next_event().await.unwrap() {
+			NotificationEvent::ValidateInboundSubstream { result_tx, .. } => {
+				result_tx.send(ValidationResult::Accept).unwrap();
+			},
+			NotificationEvent::NotificationStreamOpened { .. } => break,
+			_ => {},
+		};
+	}
+
+	// Queried through `Arc<T>` like any other user of the trait.
+	let network: Arc<dyn NetworkPeers> = node1.clone();
+	assert_eq!(network.connected_peers(PROTOCOL_NAME.into()), vec![node2.local_peer_id()]);
+	assert!(network.connected_peers("/unknown".into()).is_empty());
+
+	node1.disconnect_peer(node2.local_peer_id(), PROTOCOL_NAME.into());
+	loop {
+		if let NotificationEvent::NotificationStreamClosed { .. } =
+			handle1.next_event().await.unwrap()
+		{
+			break
+		}
+	}
+	assert!(node1.connected_peers(PROTOCOL_NAME.into()).is_empty());
+}
+
+#[tokio::test]
+async fn peer_protocols_reflects_open_substreams() {
+	let (node1, handle1, node2, handle2) = build_nodes_one_proto();
+	let (mut handle1, mut handle2) = (handle1.unwrap(), handle2.unwrap());
+
+	assert!(node1.peer_protocols(&node2.local_peer_id()).is_empty());
+
+	tokio::spawn(async move {
+		while let Some(event) = handle2.next_event().await {
+			if let NotificationEvent::ValidateInboundSubstream { result_tx, .. } = event {
+				result_tx.send(ValidationResult::Accept).unwrap();
+			}
+		}
+	});
+
+	loop {
+		match handle1.next_event().await.unwrap() {
+			NotificationEvent::ValidateInboundSubstream { result_tx, .. } => {
+				result_tx.send(ValidationResult::Accept).unwrap();
+			},
+			NotificationEvent::NotificationStreamOpened { .. } => break,
+			_ => {},
+		};
+	}
+
+	assert!(node1.peer_protocols(&node2.local_peer_id()).contains(&PROTOCOL_NAME.into()));
+	assert!(node1.peer_protocols(&PeerId::random()).is_empty());
+
+	node1.disconnect_peer(node2.local_peer_id(), PROTOCOL_NAME.into());
+	loop {
+		if let NotificationEvent::NotificationStreamClosed { .. } =
+			handle1.next_event().await.unwrap()
+		{
+			break
+		}
+	}
+	assert!(!node1.peer_protocols(&node2.local_peer_id()).contains(&PROTOCOL_NAME.into()));
+}
+
+#[tokio::test]
+async fn num_connected_counts_open_substreams() {
+	let (node1, handle1, node2, handle2) = build_nodes_one_proto();
+	let (mut handle1, mut handle2) = (handle1.unwrap(), handle2.unwrap());
+
+	assert_eq!(node1.num_connected(PROTOCOL_NAME.into()), 0);
+
+	tokio::spawn(async move {
+		while let Some(event) = handle2.next_event().await {
+			if let NotificationEvent::ValidateInboundSubstream { result_tx, .. } = event {
+				result_tx.send(ValidationResult::Accept).unwrap();
+			}
+		}
+	});
+
+	loop {
+		match handle1.next_event().await.unwrap() {
+			NotificationEvent::ValidateInboundSubstream { result_tx, .. } => {
+				result_tx.send(ValidationResult::Accept).unwrap();
+			},
+			NotificationEvent::NotificationStreamOpened { .. } => break,
+			_ => {},
+		};
+	}
+
+	assert_eq!(node1.num_connected(PROTOCOL_NAME.into()), 1);
+	assert_eq!(node1.num_connected("/unknown/1".into()), 0);
+
+	node1.disconnect_peer(node2.local_peer_id(), PROTOCOL_NAME.into());
+	loop {
+		if let NotificationEvent::NotificationStreamClosed { .. } =
+			handle1.next_event().await.unwrap()
+		{
+			break
+		}
+	}
+	assert_eq!(node1.num_connected(PROTOCOL_NAME.into()), 0);
+}
+
+#[tokio::test]
+async fn status_splits_connected_peers_by_direction() {
+	const SECOND_PROTOCOL_NAME: &str = "/bar/1";
+
+	let protocols = |set_config: config::SetConfig| {
+		[PROTOCOL_NAME, SECOND_PROTOCOL_NAME].map(|name| {
+			config::NonDefaultSetConfig::new(
+				name.into(),
+				Vec::new(),
+				1024 * 1024,
+				None,
+				set_config.clone(),
+			)
+		})
+	};
+	// Accepts the substreams of `handle` and reports when the first one is open.
+	let accept_substreams = |mut handle: Box<dyn NotificationService>| {
+		let (opened_tx, opened_rx) = futures::channel::oneshot::channel();
+		tokio::spawn(async move {
+			let mut opened_tx = Some(opened_tx);
+			while let Some(event) = handle.next_event().await {
+				match event {
+					NotificationEvent::ValidateInboundSubstream { result_tx, .. } => {
+						result_tx.send(ValidationResult::Accept).unwrap();
+					},
+					NotificationEvent::NotificationStreamOpened { .. } =>
+						if let Some(opened_tx) = opened_tx.take() {
+							let _ = opened_tx.send(());
+						},
+					_ => {},
+				}
+			}
+		});
+		opened_rx
+	};
+
+	let listen_addr = config::build_multiaddr![Memory(rand::random::<u64>())];
+	let [(config1a, handle1a), (config1b, handle1b)] = protocols(Default::default());
+	let (network1, _) = TestNetworkBuilder::new()
+		.with_notification_protocol(config1a)
+		.with_notification_protocol(config1b)
+		.with_listen_addresses(vec![listen_addr.clone()])
+		.build();
+	let (node1, _) = network1.start_network();
+
+	let [(config2a, handle2a), (config2b, handle2b)] = protocols(config::SetConfig {
+		reserved_nodes: vec![MultiaddrWithPeerId {
+			multiaddr: listen_addr,
+			peer_id: node1.local_peer_id(),
+		}],
+		..Default::default()
+	});
+	let (network2, _) = TestNetworkBuilder::new()
+		.with_notification_protocol(config2a)
+		.with_notification_protocol(config2b)
+		.build();
+	let (node2, _) = network2.start_network();
+
+	let opened = [handle1a, handle1b, handle2a, handle2b].map(accept_substreams);
+	for opened in opened {
+		opened.await.unwrap();
+	}
+
+	// Both protocols are open, but each node is still counted once. `node2` has `node1` as a
+	// reserved peer and dials it.
+	let status = node1.status().await.unwrap();
+	assert_eq!((status.num_connected_inbound, status.num_connected_outbound), (1, 0));
+
+	let status = node2.status().await.unwrap();
+	assert_eq!((status.num_connected_inbound, status.num_connected_outbound), (0, 1));
+}
+
+#[tokio::test]
+async fn missing_dht_value_is_reported_to_start_get_value_caller() {
+	sp_tracing::try_init_simple();
+
+	let (network, _) = TestNetworkBuilder::new().build();
+	let (node, _) = network.start_network();
+
+	let result = tokio::time::timeout(
+		Duration::from_secs(10),
+		node.start_get_value(&KademliaKey::new(b"missing")),
+	)
+	.await
+	.expect("Lookup completes");
+
+	assert_eq!(result, Ok(Err(DhtError::ValueNotFound)));
+}
+
+#[tokio::test]
+async fn connectivity_report_distinguishes_connected_and_isolated_nodes() {
+	let (network, _) = TestNetworkBuilder::new().build();
+	let (isolated, _) = network.start_network();
+
+	let report = isolated.connectivity_report().await.unwrap();
+	assert_eq!(report.num_connected_peers, 0);
+	assert_eq!(report.num_dht_peers, 0);
+	assert_eq!(report.summary(), ConnectivityStatus::Fail);
+
+	let (node1, handle1, node2, handle2) = build_nodes_one_proto();
+	let (mut handle1, mut handle2) = (handle1.unwrap(), handle2.unwrap());
+
+	tokio::spawn(async move {
+		while let Some(event) = handle1.next_event().await {
+			if let NotificationEvent::ValidateInboundSubstream { result_tx, .. } = event {
+				result_tx.send(ValidationResult::Accept).unwrap();
+			}
+		}
+	});
+
+	loop {
+		match handle2.next_event().await.unwrap() {
+			NotificationEvent::ValidateInboundSubstream { result_tx, .. } => {
+				result_tx.send(ValidationResult::Accept).unwrap();
+			},
+			NotificationEvent::NotificationStreamOpened { .. } => break,
+			_ => {},
+		};
+	}
+
+	// `node2` has `node1` as its only reserved peer.
+	let report = node2.connectivity_report().await.unwrap();
+	assert_eq!(report.num_connected_peers, 1);
+	assert_eq!(report.num_reserved_peers, 1);
+	assert_eq!(report.num_connected_reserved_peers, 1);
+	assert_ne!(report.summary(), ConnectivityStatus::Fail);
+
+	let report = node1.connectivity_report().await.unwrap();
+	assert_eq!(report.num_connected_peers, 1);
+	assert_eq!(report.num_reserved_peers, 0);
+	assert_ne!(report.summary(), ConnectivityStatus::Fail);
+}
+
+#[tokio::test]
+async fn flush_dht_puts_waits_for_put_to_complete() {
+	sp_tracing::try_init_simple();
+
+	let (network, _) = TestNetworkBuilder::new().build();
+	let (node, _) = network.start_network();
+	let mut events = node.event_stream("test");
+
+	// Nothing to wait for.
+	assert_eq!(node.flush_dht_puts(Duration::from_secs(10)).await, Ok(()));
+
+	let key = KademliaKey::new(b"key");
+	node.put_value(key.clone(), b"value".to_vec());
+	assert_eq!(node.flush_dht_puts(Duration::from_secs(10)).await, Ok(()));
+
+	// The put has been acknowledged by the time the flush resolved.
+	let acknowledged = std::iter::from_fn(|| events.next().now_or_never().flatten()).any(|event| {
+		matches!(
+			event,
+			Event::Dht(DhtEvent::ValuePut(k) | DhtEvent::ValuePutFailed(k)) if k == key
+		)
+	});
+	assert!(acknowledged);
+}
+
+#[tokio::test]
+async fn flush_dht_puts_recovers_from_timed_out_flush() {
+	sp_tracing::try_init_simple();
+
+	let (network, _) = TestNetworkBuilder::new().build();
+	let (node, _) = network.start_network();
+
+	node.put_value(KademliaKey::new(b"key"), b"value".to_vec());
+	assert_eq!(node.flush_dht_puts(Duration::ZERO).await, Err(()));
+
+	// A new flush only waits for the put started before it.
+	assert_eq!(node.flush_dht_puts(Duration::from_secs(10)).await, Ok(()));
+}
+
+#[tokio::test]
+async fn get_providers_reports_when_no_provider_is_found() {
+	sp_tracing::try_init_simple();
+
+	let (network, _) = TestNetworkBuilder::new().build();
+	let (node, _) = network.start_network();
+	let mut events = node.event_stream("test");
+
+	let key = KademliaKey::new(b"key");
+	node.get_providers(key.clone());
+
+	let event = tokio::time::timeout(Duration::from_secs(10), async {
+		loop {
+			match events.next().await.unwrap() {
+				Event::Dht(event @ DhtEvent::ProvidersFound(..)) |
+				Event::Dht(event @ DhtEvent::ProvidersNotFound(_)) => break event,
+				_ => {},
+			}
+		}
+	})
+	.await
+	.expect("Provider lookup result is reported");
+
+	assert!(matches!(event, DhtEvent::ProvidersNotFound(k) if k == key));
+}
+
+#[tokio::test]
+async fn set_reserved_peers_multi_is_not_applied_partially() {
+	let client = TestClientBuilder::with_default_backend().build_with_longest_chain().0;
+	let genesis_hash = client.hash(Zero::zero()).ok().flatten().unwrap();
+	let sync_protocol = format!("/{genesis_hash:x}/block-announces/1");
+
+	let (network, _handle) = TestNetworkBuilder::new().build();
+	let (node, _) = network.start_network();
+
+	let reserved = PeerId::random();
+	let valid = MultiaddrWithPeerId {
+		multiaddr: config::build_multiaddr![Memory(rand::random::<u64>())],
+		peer_id: reserved,
+	}
+	.concat();
+	let sets = HashMap::from([(sync_protocol.clone().into(), HashSet::from([valid]))]);
+	node.set_reserved_peers_multi(sets).unwrap();
+	assert_eq!(node.sync_reserved_peers().await.unwrap(), vec![reserved]);
+
+	// Missing the `/p2p/` component.
+	let invalid = config::build_multiaddr![Memory(rand::random::<u64>())];
+	let sets = HashMap::from([
+		(sync_protocol.clone().into(), HashSet::new()),
+		(PROTOCOL_NAME.into(), HashSet::from([invalid])),
+	]);
+	assert!(node.set_reserved_peers_multi(sets).is_err());
+	assert_eq!(node.sync_reserved_peers().await.unwrap(), vec![reserved]);
+
+	// The local peer ID can't be a reserved peer.
+	let local = MultiaddrWithPeerId {
+		multiaddr: config::build_multiaddr![Memory(rand::random::<u64>())],
+		peer_id: node.local_peer_id(),
+	}
+	.concat();
+	let sets = HashMap::from([
+		(sync_protocol.into(), HashSet::new()),
+		(PROTOCOL_NAME.into(), HashSet::from([local])),
+	]);
+	assert!(node.set_reserved_peers_multi(sets).is_err());
+	assert_eq!(node.sync_reserved_peers().await.unwrap(), vec![reserved]);
+}
+
+#[tokio::test]
+async fn reserved_peers_reflects_configured_set() {
+	let (config, _handle) = config::NonDefaultSetConfig::new(
+		PROTOCOL_NAME.into(),
+		Vec::new(),
+		1024 * 1024,
+		None,
+		Default::default(),
+	);
+	let (network, _) = TestNetworkBuilder::new().with_notification_protocol(config).build();
+	let (node, _) = network.start_network();
+
+	assert!(node.reserved_peers(PROTOCOL_NAME.into()).unwrap().is_empty());
+	assert!(node.reserved_peers("/unknown/1".into()).is_err());
+
+	let (first, second) = (PeerId::random(), PeerId::random());
+	let addr = |peer_id| {
+		MultiaddrWithPeerId {
+			multiaddr: config::build_multiaddr![Memory(rand::random::<u64>())],
+			peer_id,
+		}
+		.concat()
+	};
+
+	node.set_reserved_peers(PROTOCOL_NAME.into(), HashSet::from([addr(first)]))
+		.unwrap();
+	node.add_peers_to_reserved_set(PROTOCOL_NAME.into(), HashSet::from([addr(second)]))
+		.unwrap();
+	assert_eq!(node.reserved_peers(PROTOCOL_NAME.into()).unwrap(), HashSet::from([first, second]));
+
+	node.remove_peers_from_reserved_set(PROTOCOL_NAME.into(), vec![first]).unwrap();
+	assert_eq!(node.reserved_peers(PROTOCOL_NAME.into()).unwrap(), HashSet::from([second]));
+}
+
+#[tokio::test]
+async fn is_major_syncing_reflects_shared_flag() {
+	sp_tracing::try_init_simple();
+
+	let is_major_syncing = Arc::new(AtomicBool::new(false));
+	let (network, _) =
+		TestNetworkBuilder::new().with_major_syncing_flag(is_major_syncing.clone()).build();
+	let (node, _) = network.start_network();
+
+	assert!(!node.is_major_syncing());
+
+	is_major_syncing.store(true, Ordering::Relaxed);
+	assert!(node.is_major_syncing());
+
+	is_major_syncing.store(false, Ordering::Relaxed);
+	assert!(!node.is_major_syncing());
+}
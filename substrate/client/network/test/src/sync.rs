@@ -18,6 +18,7 @@
 
 use super::*;
 use futures::Future;
+use sc_network_sync::warp::WarpSyncPhase;
 use sp_consensus::{block_validation::Validation, BlockOrigin};
 use sp_runtime::Justifications;
 use substrate_test_runtime::Header;
@@ -1249,6 +1250,119 @@ async fn warp_sync() {
 	.await;
 }
 
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn warp_sync_with_parallel_proof_requests() {
+	sp_tracing::try_init_simple();
+	let mut net = TestNet::new(0);
+	// Create 3 synced peers and 1 peer requesting warp proofs from all of them at once.
+	net.add_full_peer_with_config(Default::default());
+	net.add_full_peer_with_config(Default::default());
+	net.add_full_peer_with_config(Default::default());
+	net.add_full_peer_with_config(FullPeerConfig {
+		sync_mode: SyncMode::Warp,
+		warp_sync_options: WarpSyncOptions { max_parallel_proof_requests: 3, ..Default::default() },
+		..Default::default()
+	});
+	net.peer(0).push_blocks(64, false);
+	net.peer(1).push_blocks(64, false);
+	net.peer(2).push_blocks(64, false);
+
+	net.run_until_sync().await;
+	assert!(!net.peer(3).client().has_state_at(&BlockId::Number(1)));
+	assert!(net.peer(3).client().has_state_at(&BlockId::Number(64)));
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn warp_sync_from_single_peer() {
+	sp_tracing::try_init_simple();
+	let mut net = TestNet::new(0);
+	// Create 1 synced peer and 1 peer allowed to warp sync from it alone.
+	net.add_full_peer_with_config(Default::default());
+	net.add_full_peer_with_config(FullPeerConfig {
+		sync_mode: SyncMode::Warp,
+		warp_sync_options: WarpSyncOptions { min_peers: 1, ..Default::default() },
+		..Default::default()
+	});
+	net.peer(0).push_blocks(64, false);
+
+	net.run_until_sync().await;
+	assert!(!net.peer(1).client().has_state_at(&BlockId::Number(1)));
+	assert!(net.peer(1).client().has_state_at(&BlockId::Number(64)));
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn warp_sync_waits_for_configured_min_peers() {
+	sp_tracing::try_init_simple();
+	let mut net = TestNet::new(0);
+	// Create 1 synced peer and 1 peer waiting for a second peer before warp syncing.
+	net.add_full_peer_with_config(Default::default());
+	net.add_full_peer_with_config(FullPeerConfig {
+		sync_mode: SyncMode::Warp,
+		warp_sync_options: WarpSyncOptions { min_peers: 2, ..Default::default() },
+		..Default::default()
+	});
+	net.peer(0).push_blocks(64, false);
+
+	net.run_until_connected().await;
+	net.run_until_idle().await;
+
+	let status = net.peer(1).sync_service().status().await.unwrap();
+	assert_eq!(
+		status.warp_sync.map(|progress| progress.phase),
+		Some(WarpSyncPhase::AwaitingPeers { required_peers: 2 }),
+	);
+	assert_eq!(net.peer(1).sync_service().warp_sync_in_flight_requests().await.unwrap(), (0, 0));
+	assert!(!net.peer(1).client().has_state_at(&BlockId::Number(64)));
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn warp_proofs_are_only_requested_from_allowed_peers() {
+	sp_tracing::try_init_simple();
+	let mut net = TestNet::new(0);
+	net.add_full_peer_with_config(Default::default());
+	net.add_full_peer_with_config(FullPeerConfig {
+		sync_mode: SyncMode::Warp,
+		warp_sync_options: WarpSyncOptions { min_peers: 1, ..Default::default() },
+		..Default::default()
+	});
+	net.peer(0).push_blocks(64, false);
+	// The only synced peer is not allowed to serve warp proofs.
+	net.peer(1)
+		.sync_service()
+		.set_warp_sync_allowed_peers([PeerId::random()].into());
+
+	net.run_until_connected().await;
+	net.run_until_idle().await;
+
+	let status = net.peer(1).sync_service().status().await.unwrap();
+	assert_eq!(
+		status.warp_sync.map(|progress| progress.phase),
+		Some(WarpSyncPhase::DownloadingWarpProofs),
+	);
+	assert_eq!(net.peer(1).sync_service().warp_sync_in_flight_requests().await.unwrap(), (0, 0));
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn aborted_warp_sync_falls_back_to_full_sync() {
+	sp_tracing::try_init_simple();
+	let mut net = TestNet::new(0);
+	// Create 1 synced peer and 1 peer waiting for more peers to warp sync.
+	net.add_full_peer_with_config(Default::default());
+	net.add_full_peer_with_config(FullPeerConfig {
+		sync_mode: SyncMode::Warp,
+		warp_sync_options: WarpSyncOptions { min_peers: 2, ..Default::default() },
+		..Default::default()
+	});
+	net.peer(0).push_blocks(64, false);
+	net.run_until_connected().await;
+
+	net.peer(1).sync_service().abort_warp_sync();
+
+	net.run_until_sync().await;
+	assert!(net.peer(1).client().has_state_at(&BlockId::Number(1)));
+	assert!(net.peer(1).client().has_state_at(&BlockId::Number(64)));
+}
+
 #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
 async fn warp_sync_to_target_block() {
 	sp_tracing::try_init_simple();
@@ -225,7 +225,7 @@ mod tests {
 		ObservedRole, ReputationChange,
 	};
 	use sp_core::offchain::{storage::OffchainDb, DbExternalities, Externalities, StorageKind};
-	use std::time::SystemTime;
+	use std::{collections::HashMap, time::SystemTime};
 
 	pub(super) struct TestNetwork();
 
@@ -250,6 +250,14 @@ mod tests {
 			unimplemented!()
 		}
 
+		fn pin_reputation(&self, _peer_id: PeerId, _value: i32) {
+			unimplemented!()
+		}
+
+		fn unpin_reputation(&self, _peer_id: PeerId) {
+			unimplemented!()
+		}
+
 		fn disconnect_peer(&self, _peer_id: PeerId, _protocol: ProtocolName) {
 			unimplemented!();
 		}
@@ -278,6 +286,13 @@ mod tests {
 			unimplemented!();
 		}
 
+		fn set_reserved_peers_multi(
+			&self,
+			_sets: HashMap<ProtocolName, HashSet<Multiaddr>>,
+		) -> Result<(), String> {
+			unimplemented!();
+		}
+
 		fn add_peers_to_reserved_set(
 			&self,
 			_protocol: ProtocolName,
@@ -298,6 +313,22 @@ mod tests {
 			unimplemented!();
 		}
 
+		fn connected_peers(&self, _protocol: ProtocolName) -> Vec<PeerId> {
+			unimplemented!();
+		}
+
+		fn num_connected(&self, _protocol: ProtocolName) -> usize {
+			unimplemented!();
+		}
+
+		fn reserved_peers(&self, _protocol: ProtocolName) -> Result<HashSet<PeerId>, String> {
+			unimplemented!();
+		}
+
+		fn peer_protocols(&self, _peer_id: &PeerId) -> Vec<ProtocolName> {
+			unimplemented!();
+		}
+
 		fn peer_role(&self, _peer_id: PeerId, _handshake: Vec<u8>) -> Option<ObservedRole> {
 			None
 		}
@@ -315,6 +346,10 @@ mod tests {
 		fn listen_addresses(&self) -> Vec<Multiaddr> {
 			Vec::new()
 		}
+
+		fn is_major_syncing(&self) -> bool {
+			false
+		}
 	}
 
 	fn offchain_api() -> (Api, AsyncApi) {
@@ -337,7 +337,10 @@ mod tests {
 	use sc_transaction_pool_api::{InPoolTransaction, TransactionPool};
 	use sp_consensus::BlockOrigin;
 	use sp_runtime::traits::Block as BlockT;
-	use std::{collections::HashSet, sync::Arc};
+	use std::{
+		collections::{HashMap, HashSet},
+		sync::Arc,
+	};
 	use substrate_test_runtime_client::{
 		runtime::{
 			substrate_test_pallet::pallet::Call as PalletCall, ExtrinsicBuilder, RuntimeCall,
@@ -359,6 +362,10 @@ mod tests {
 		fn listen_addresses(&self) -> Vec<Multiaddr> {
 			Vec::new()
 		}
+
+		fn is_major_syncing(&self) -> bool {
+			false
+		}
 	}
 
 	impl NetworkPeers for TestNetwork {
@@ -382,6 +389,14 @@ mod tests {
 			unimplemented!()
 		}
 
+		fn pin_reputation(&self, _peer_id: PeerId, _value: i32) {
+			unimplemented!()
+		}
+
+		fn unpin_reputation(&self, _peer_id: PeerId) {
+			unimplemented!()
+		}
+
 		fn disconnect_peer(&self, _peer_id: PeerId, _protocol: ProtocolName) {
 			unimplemented!();
 		}
@@ -410,6 +425,13 @@ mod tests {
 			unimplemented!();
 		}
 
+		fn set_reserved_peers_multi(
+			&self,
+			_sets: HashMap<ProtocolName, HashSet<Multiaddr>>,
+		) -> Result<(), String> {
+			unimplemented!();
+		}
+
 		fn add_peers_to_reserved_set(
 			&self,
 			_protocol: ProtocolName,
@@ -430,6 +452,22 @@ mod tests {
 			unimplemented!();
 		}
 
+		fn connected_peers(&self, _protocol: ProtocolName) -> Vec<PeerId> {
+			unimplemented!();
+		}
+
+		fn num_connected(&self, _protocol: ProtocolName) -> usize {
+			unimplemented!();
+		}
+
+		fn reserved_peers(&self, _protocol: ProtocolName) -> Result<HashSet<PeerId>, String> {
+			unimplemented!();
+		}
+
+		fn peer_protocols(&self, _peer_id: &PeerId) -> Vec<ProtocolName> {
+			unimplemented!();
+		}
+
 		fn peer_role(&self, _peer_id: PeerId, _handshake: Vec<u8>) -> Option<ObservedRole> {
 			None
 		}
@@ -50,10 +50,14 @@ use sc_network_bitswap::BitswapRequestHandler;
 use sc_network_common::role::Roles;
 use sc_network_light::light_client_requests::handler::LightClientRequestHandler;
 use sc_network_sync::{
-	block_relay_protocol::BlockRelayParams, block_request_handler::BlockRequestHandler,
-	engine::SyncingEngine, service::network::NetworkServiceProvider,
-	state_request_handler::StateRequestHandler, warp::WarpSyncParams,
-	warp_request_handler::RequestHandler as WarpSyncRequestHandler, SyncingService,
+	block_relay_protocol::BlockRelayParams,
+	block_request_handler::BlockRequestHandler,
+	engine::SyncingEngine,
+	service::network::NetworkServiceProvider,
+	state_request_handler::StateRequestHandler,
+	warp::{WarpSyncOptions, WarpSyncParams},
+	warp_request_handler::RequestHandler as WarpSyncRequestHandler,
+	SyncingService,
 };
 use sc_rpc::{
 	author::AuthorApiServer,
@@ -717,6 +721,8 @@ pub struct BuildNetworkParams<'a, TBl: BlockT, TExPool, TImpQu, TCl> {
 		Option<Box<dyn FnOnce(Arc<TCl>) -> Box<dyn BlockAnnounceValidator<TBl> + Send> + Send>>,
 	/// Optional warp sync params.
 	pub warp_sync_params: Option<WarpSyncParams<TBl>>,
+	/// Warp sync behaviour, only used if `warp_sync_params` is set.
+	pub warp_sync_options: WarpSyncOptions,
 	/// User specified block relay params. If not specified, the default
 	/// block request handler will be used.
 	pub block_relay: Option<BlockRelayParams<TBl>>,
@@ -758,6 +764,7 @@ where
 		import_queue,
 		block_announce_validator_builder,
 		warp_sync_params,
+		warp_sync_options,
 		block_relay,
 	} = params;
 
@@ -897,6 +904,7 @@ where
 		&config.chain_spec.fork_id().map(ToOwned::to_owned),
 		block_announce_validator,
 		warp_sync_params,
+		warp_sync_options,
 		chain_sync_network_handle,
 		import_queue.service(),
 		block_downloader,
@@ -923,6 +931,7 @@ where
 		fork_id: config.chain_spec.fork_id().map(ToOwned::to_owned),
 		metrics_registry: config.prometheus_config.as_ref().map(|config| config.registry.clone()),
 		block_announce_config,
+		is_major_syncing: sync_service.major_syncing_flag(),
 	};
 
 	let has_bootnodes = !network_params.network_config.network_config.boot_nodes.is_empty();
@@ -77,7 +77,7 @@ pub use sc_chain_spec::{
 
 pub use sc_consensus::ImportQueue;
 pub use sc_executor::NativeExecutionDispatch;
-pub use sc_network_sync::warp::WarpSyncParams;
+pub use sc_network_sync::warp::{WarpSyncOptions, WarpSyncParams};
 #[doc(hidden)]
 pub use sc_network_transactions::config::{TransactionImport, TransactionImportFuture};
 pub use sc_rpc::{
@@ -307,7 +307,7 @@ pub async fn build_system_rpc_future<
 				};
 			},
 			sc_rpc::system::Request::NetworkReservedPeers(sender) => {
-				let reserved_peers = network_service.reserved_peers().await;
+				let reserved_peers = network_service.sync_reserved_peers().await;
 				if let Ok(reserved_peers) = reserved_peers {
 					let reserved_peers =
 						reserved_peers.iter().map(|peer_id| peer_id.to_base58()).collect();
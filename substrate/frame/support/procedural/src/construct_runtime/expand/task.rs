@@ -29,6 +29,7 @@ pub fn expand_outer_task(
 	let mut task_variants = Vec::new();
 	let mut variant_names = Vec::new();
 	let mut task_paths = Vec::new();
+	let mut pallet_indices = Vec::new();
 	for decl in pallet_decls {
 		if decl.find_part("Task").is_none() {
 			continue;
@@ -65,6 +66,8 @@ pub fn expand_outer_task(
 		variant_names.push(quote!(#variant_name));
 
 		task_paths.push(quote!(#path::Task));
+
+		pallet_indices.push(index);
 	}
 
 	let prelude = quote!(#scrate::traits::tasks::__private);
@@ -103,6 +106,13 @@ pub fn expand_outer_task(
 				}
 			}
 
+			fn run_async(&self) -> #scrate::traits::tasks::TaskFuture<'_> {
+				match self {
+					#(RuntimeTask::#variant_names(val) => val.run_async(),)*
+					_ => unreachable!(#INCOMPLETE_MATCH_QED),
+				}
+			}
+
 			fn weight(&self) -> #scrate::pallet_prelude::Weight {
 				match self {
 					#(RuntimeTask::#variant_names(val) => val.weight(),)*
@@ -124,6 +134,43 @@ pub fn expand_outer_task(
 			}
 		}
 
+		impl RuntimeTask {
+			/// Returns an iterator over the currently valid tasks of all pallets.
+			///
+			/// Unlike `Task::iter`, the tasks of each pallet are only enumerated once the iterator
+			/// reaches them, and are not collected beforehand.
+			pub fn iter_valid() -> impl Iterator<Item = RuntimeTask> {
+				core::iter::empty::<RuntimeTask>()
+					#(.chain(
+						core::iter::once_with(<#task_paths<#runtime_name> as #scrate::traits::Task>::iter)
+							.flatten()
+							.map(RuntimeTask::from)
+					))*
+					.filter(|task| #scrate::traits::Task::is_valid(task))
+			}
+
+			/// Returns the total number of tasks currently enumerated by all pallets.
+			pub fn task_count() -> usize {
+				0 #(+ <#task_paths<#runtime_name> as #scrate::traits::Task>::iter().count())*
+			}
+
+			/// Returns the `task_index`-th task enumerated by the pallet at `pallet_index`.
+			///
+			/// Only the tasks of that pallet are enumerated. Returns `None` if there is no pallet
+			/// with tasks at `pallet_index`, or if it currently has fewer tasks.
+			#[allow(unused_variables)]
+			pub fn task_by_index(pallet_index: u8, task_index: u32) -> Option<RuntimeTask> {
+				match pallet_index {
+					#(
+						#pallet_indices => <#task_paths<#runtime_name> as #scrate::traits::Task>::iter()
+							.nth(task_index as usize)
+							.map(RuntimeTask::from),
+					)*
+					_ => None,
+				}
+			}
+		}
+
 		#( #from_impls )*
 	};
 
@@ -156,9 +156,29 @@ impl ToTokens for TasksDef {
 			task_fn_impl
 		});
 
-		let task_fn_names = self.tasks.iter().map(|task| &task.item.sig.ident);
+		let task_fn_names = self.tasks.iter().map(|task| &task.item.sig.ident).collect::<Vec<_>>();
 		let task_arg_names = self.tasks.iter().map(|task| &task.arg_names).collect::<Vec<_>>();
 
+		// `async fn` tasks can only be driven through `run_async`, sync tasks are simply called
+		// from both `run` and `run_async`.
+		let task_run_bodies = self.tasks.iter().zip(&task_fn_names).map(|(task, fn_name)| {
+			if task.item.sig.asyncness.is_some() {
+				quote! {
+					Err(#scrate::pallet_prelude::DispatchError::Other(
+						concat!("task `", stringify!(#fn_name), "` can only be run asynchronously"),
+					))
+				}
+			} else {
+				let arg_names = &task.arg_names;
+				quote!(<#enum_use>::#fn_name(#( #arg_names, )* ))
+			}
+		});
+		let task_run_async_bodies = self.tasks.iter().zip(&task_fn_names).map(|(task, fn_name)| {
+			let arg_names = &task.arg_names;
+			let await_token = task.item.sig.asyncness.map(|_| quote!(.await));
+			quote!(<#enum_use>::#fn_name(#( #arg_names, )* )#await_token)
+		});
+
 		let sp_std = quote!(#scrate::__private::sp_std);
 		let impl_generics = &self.item_impl.generics;
 		tokens.extend(quote! {
@@ -194,15 +214,28 @@ impl ToTokens for TasksDef {
 					}
 				}
 
+				#[allow(unused_variables)]
 				fn run(&self) -> Result<(), #scrate::pallet_prelude::DispatchError> {
 					match self.clone() {
 						#(#enum_ident::#task_fn_idents { #(#task_arg_names),* } => {
-							<#enum_use>::#task_fn_names(#( #task_arg_names, )* )
+							#task_run_bodies
 						},)*
 						Task::__Ignore(_, _) => unreachable!(),
 					}
 				}
 
+				fn run_async(&self) -> #scrate::traits::tasks::TaskFuture<'_> {
+					let task = self.clone();
+					#scrate::traits::tasks::__private::Box::pin(async move {
+						match task {
+							#(#enum_ident::#task_fn_idents { #(#task_arg_names),* } => {
+								#task_run_async_bodies
+							},)*
+							Task::__Ignore(_, _) => unreachable!(),
+						}
+					})
+				}
+
 				#[allow(unused_variables)]
 				fn weight(&self) -> #scrate::pallet_prelude::Weight {
 					match self.clone() {
@@ -2718,6 +2718,12 @@ pub mod pallet_macros {
 	/// * [`pallet::task_weight`](`task_weight`)
 	/// * [`pallet::task_index`](`task_index`)
 	///
+	/// A function may also be declared as an `async fn`. Such a task is only executed through
+	/// [`Task::run_async`](frame_support::traits::Task::run_async), while calling
+	/// [`Task::run`](frame_support::traits::Task::run) on it returns an error. Synchronous
+	/// tasks can be run either way. As `frame_system::Pallet::do_task` calls `run`, `async fn`
+	/// tasks can never be executed on-chain and are only meant for off-chain workers.
+	///
 	/// All of such Tasks are then aggregated into a `RuntimeTask` by
 	/// [`construct_runtime`](frame_support::construct_runtime).
 	///
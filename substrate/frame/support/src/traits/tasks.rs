@@ -19,9 +19,10 @@
 //! service work, and supporting types.
 
 use codec::FullCodec;
+use core::{future::Future, pin::Pin};
 use scale_info::TypeInfo;
 use sp_runtime::DispatchError;
-use sp_std::{fmt::Debug, iter::Iterator, vec, vec::IntoIter};
+use sp_std::{boxed::Box, fmt::Debug, iter::Iterator, vec, vec::IntoIter};
 use sp_weights::Weight;
 
 /// Contain's re-exports of all the supporting types for the [`Task`] trait. Used in the macro
@@ -31,10 +32,13 @@ pub mod __private {
 	pub use codec::FullCodec;
 	pub use scale_info::TypeInfo;
 	pub use sp_runtime::DispatchError;
-	pub use sp_std::{fmt::Debug, iter::Iterator, vec, vec::IntoIter};
+	pub use sp_std::{boxed::Box, fmt::Debug, iter::Iterator, vec, vec::IntoIter};
 	pub use sp_weights::Weight;
 }
 
+/// The future returned by [`Task::run_async`].
+pub type TaskFuture<'a> = Pin<Box<dyn Future<Output = Result<(), DispatchError>> + 'a>>;
+
 /// A general-purpose trait which defines a type of service work (i.e., work to performed by an
 /// off-chain worker) including methods for enumerating, validating, indexing, and running
 /// tasks of this type.
@@ -51,6 +55,17 @@ pub trait Task: Sized + FullCodec + TypeInfo + Clone + Debug + PartialEq + Eq {
 	/// Performs the work for this particular `Task` variant.
 	fn run(&self) -> Result<(), DispatchError>;
 
+	/// Performs the work for this particular `Task` variant asynchronously.
+	///
+	/// Defaults to [`Task::run`], so only tasks that actually need to await something (e.g.
+	/// long-running off-chain maintenance) have to provide their own implementation.
+	///
+	/// Tasks executed on-chain, e.g. through `frame_system::Pallet::do_task`, are always run
+	/// with [`Task::run`], never with this method.
+	fn run_async(&self) -> TaskFuture<'_> {
+		Box::pin(async move { self.run() })
+	}
+
 	/// Returns the weight of executing this `Task`.
 	fn weight(&self) -> Weight;
 
@@ -0,0 +1,181 @@
+// This file is part of Substrate.
+
+// Copyright (C) Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Tests for the `RuntimeTask` aggregated by the construct_runtime macro.
+
+use frame_support::{derive_impl, traits::Task};
+use futures::executor::block_on;
+use sp_runtime::DispatchError;
+
+#[frame_support::pallet(dev_mode)]
+mod module {
+	use frame_support::pallet_prelude::*;
+
+	#[pallet::pallet]
+	pub struct Pallet<T>(_);
+
+	#[pallet::config]
+	pub trait Config: frame_system::Config {}
+
+	#[pallet::error]
+	pub enum Error<T> {
+		NotFound,
+	}
+
+	#[pallet::storage]
+	pub type Numbers<T> = StorageMap<_, Twox64Concat, u32, u32, OptionQuery>;
+
+	#[pallet::tasks_experimental]
+	impl<T: Config> Pallet<T> {
+		#[pallet::task_list(Numbers::<T>::iter_keys())]
+		#[pallet::task_condition(|i| Numbers::<T>::contains_key(i))]
+		#[pallet::task_weight(0.into())]
+		#[pallet::task_index(0)]
+		pub fn increment(i: u32) -> DispatchResult {
+			Numbers::<T>::mutate(i, |v| v.as_mut().map(|v| *v += 1)).ok_or(Error::<T>::NotFound)?;
+			Ok(())
+		}
+
+		#[pallet::task_list(Numbers::<T>::iter_keys())]
+		#[pallet::task_condition(|i| Numbers::<T>::get(i).is_some_and(|v| v != 0))]
+		#[pallet::task_weight(0.into())]
+		#[pallet::task_index(1)]
+		pub async fn double(i: u32) -> DispatchResult {
+			let v = Numbers::<T>::get(i).ok_or(Error::<T>::NotFound)?;
+			let doubled = core::future::ready(v * 2).await;
+			Numbers::<T>::insert(i, doubled);
+			Ok(())
+		}
+	}
+}
+
+pub type Header = sp_runtime::generic::Header<u32, sp_runtime::traits::BlakeTwo256>;
+pub type Block = sp_runtime::generic::Block<Header, UncheckedExtrinsic>;
+pub type UncheckedExtrinsic = sp_runtime::generic::UncheckedExtrinsic<u32, RuntimeCall, (), ()>;
+
+frame_support::construct_runtime!(
+	pub struct Runtime {
+		System: frame_system,
+		Module: module,
+	}
+);
+
+#[derive_impl(frame_system::config_preludes::TestDefaultConfig as frame_system::DefaultConfig)]
+impl frame_system::Config for Runtime {
+	type Block = Block;
+}
+
+impl module::Config for Runtime {}
+
+fn new_test_ext() -> sp_io::TestExternalities {
+	sp_io::TestExternalities::default()
+}
+
+#[test]
+fn run_async_falls_back_to_sync_task() {
+	new_test_ext().execute_with(|| {
+		module::Numbers::<Runtime>::insert(0, 1);
+
+		let task = RuntimeTask::Module(module::Task::<Runtime>::Increment { i: 0 });
+		assert_eq!(block_on(task.run_async()), Ok(()));
+		assert_eq!(module::Numbers::<Runtime>::get(0), Some(2));
+
+		assert_eq!(task.run(), Ok(()));
+		assert_eq!(module::Numbers::<Runtime>::get(0), Some(3));
+	});
+}
+
+#[test]
+fn run_async_dispatches_to_async_task() {
+	new_test_ext().execute_with(|| {
+		module::Numbers::<Runtime>::insert(0, 3);
+
+		let task = RuntimeTask::Module(module::Task::<Runtime>::Double { i: 0 });
+		assert_eq!(block_on(task.run_async()), Ok(()));
+		assert_eq!(module::Numbers::<Runtime>::get(0), Some(6));
+
+		let task = RuntimeTask::Module(module::Task::<Runtime>::Double { i: 1 });
+		assert_eq!(block_on(task.run_async()), Err(module::Error::<Runtime>::NotFound.into()));
+	});
+}
+
+#[test]
+fn async_task_cannot_be_run_synchronously() {
+	new_test_ext().execute_with(|| {
+		module::Numbers::<Runtime>::insert(0, 3);
+
+		let task = RuntimeTask::Module(module::Task::<Runtime>::Double { i: 0 });
+		assert_eq!(
+			task.run(),
+			Err(DispatchError::Other("task `double` can only be run asynchronously"))
+		);
+		assert_eq!(module::Numbers::<Runtime>::get(0), Some(3));
+	});
+}
+
+#[test]
+fn task_count_counts_tasks_of_all_pallets() {
+	new_test_ext().execute_with(|| {
+		assert_eq!(RuntimeTask::task_count(), 0);
+
+		module::Numbers::<Runtime>::insert(0, 1);
+		module::Numbers::<Runtime>::insert(1, 1);
+
+		// Both `increment` and `double` are enumerated for each number.
+		assert_eq!(RuntimeTask::task_count(), 4);
+		assert_eq!(RuntimeTask::task_count(), RuntimeTask::iter().count());
+	});
+}
+
+#[test]
+fn task_by_index_works() {
+	new_test_ext().execute_with(|| {
+		module::Numbers::<Runtime>::insert(7, 1);
+
+		assert_eq!(
+			RuntimeTask::task_by_index(1, 0),
+			Some(RuntimeTask::Module(module::Task::<Runtime>::Increment { i: 7 })),
+		);
+		assert_eq!(
+			RuntimeTask::task_by_index(1, 1),
+			Some(RuntimeTask::Module(module::Task::<Runtime>::Double { i: 7 })),
+		);
+		assert_eq!(RuntimeTask::task_by_index(1, 2), None);
+		// `System` has no tasks.
+		assert_eq!(RuntimeTask::task_by_index(0, 0), None);
+		assert_eq!(RuntimeTask::task_by_index(2, 0), None);
+	});
+}
+
+#[test]
+fn iter_valid_skips_invalid_tasks() {
+	new_test_ext().execute_with(|| {
+		assert_eq!(RuntimeTask::iter_valid().count(), 0);
+
+		module::Numbers::<Runtime>::insert(0, 0);
+		module::Numbers::<Runtime>::insert(1, 2);
+
+		// Doubling zero is not a valid task.
+		assert_eq!(RuntimeTask::iter().count(), 4);
+		let valid = RuntimeTask::iter_valid().collect::<Vec<_>>();
+		assert_eq!(valid.len(), 3);
+		assert!(valid.contains(&RuntimeTask::Module(module::Task::<Runtime>::Increment { i: 0 })));
+		assert!(valid.contains(&RuntimeTask::Module(module::Task::<Runtime>::Increment { i: 1 })));
+		assert!(valid.contains(&RuntimeTask::Module(module::Task::<Runtime>::Double { i: 1 })));
+		assert!(valid.iter().all(|task| task.is_valid()));
+	});
+}